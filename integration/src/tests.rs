@@ -464,6 +464,73 @@ fn read_write_read() {
     );
 }
 
+/// Connect to a server without security, read a variable and browse the objects folder, exercising
+/// the whole server loop from HEL / OPN through CreateSession / ActivateSession to the services.
+///
+/// Unlike the other tests this one is not ignored, so a plain `cargo test` runs a client against
+/// the real server loop. It uses its own port and needs no certificates to be trusted.
+#[test]
+fn read_and_browse_none() {
+    let port = next_port();
+    let client_endpoint = endpoint_none(port);
+    connect_with_client_test(
+        port,
+        move |_rx_client_command: mpsc::Receiver<ClientCommand>, mut client: Client| {
+            let session = client
+                .connect_to_endpoint(client_endpoint, IdentityToken::Anonymous)
+                .unwrap();
+            let session = session.read();
+
+            // Read a variable from the address space
+            let results = session
+                .read(
+                    &[stress_node_id(1).into()],
+                    TimestampsToReturn::Neither,
+                    0.0,
+                )
+                .unwrap();
+            assert_eq!(*results[0].value.as_ref().unwrap(), Variant::Int32(0));
+
+            // Browse the objects folder, which must contain the server object
+            let results = session
+                .browse(&[BrowseDescription {
+                    node_id: ObjectId::ObjectsFolder.into(),
+                    browse_direction: BrowseDirection::Forward,
+                    reference_type_id: ReferenceTypeId::Organizes.into(),
+                    include_subtypes: true,
+                    node_class_mask: 0,
+                    result_mask: BrowseDescriptionResultMask::all().bits() as u32,
+                }])
+                .unwrap()
+                .unwrap();
+            assert_eq!(results[0].status_code, StatusCode::Good);
+            let server_node_id: NodeId = ObjectId::Server.into();
+            assert!(results[0]
+                .references
+                .as_ref()
+                .unwrap()
+                .iter()
+                .any(|r| r.node_id.node_id == server_node_id));
+
+            // The transport counts HEL, OPN, CreateSession, ActivateSession, Read and Browse out
+            // and a response to all but HEL back in
+            let metrics = session.transport_metrics();
+            assert!(metrics.messages_sent >= 6);
+            assert!(metrics.messages_received >= 5);
+            assert!(metrics.chunks_sent >= metrics.messages_sent);
+            assert!(metrics.chunks_received >= metrics.messages_received);
+            assert!(metrics.bytes_sent > 0);
+            assert!(metrics.bytes_received > 0);
+            assert_eq!(
+                metrics.message_sizes.iter().sum::<u64>(),
+                metrics.messages_sent + metrics.messages_received
+            );
+
+            session.disconnect();
+        },
+    );
+}
+
 /// Connect with the server and attempt to subscribe and monitor 1000 variables
 #[test]
 #[ignore]
@@ -542,6 +609,108 @@ fn subscribe_1000() {
     );
 }
 
+/// Connect with the server, monitor a variable and check that the publish requests the session
+/// keeps in flight deliver each change to the subscription callback
+#[test]
+#[ignore]
+fn subscribe_data_change_notifications() {
+    let port = next_port();
+    let client_endpoint = endpoint_none(port);
+
+    connect_with_client_test(
+        port,
+        move |_rx_client_command: mpsc::Receiver<ClientCommand>, mut client: Client| {
+            info!(
+                "Client will try to connect to endpoint {:?}",
+                client_endpoint
+            );
+            let session = client
+                .connect_to_endpoint(client_endpoint, IdentityToken::Anonymous)
+                .unwrap();
+
+            let node_id = stress_node_id(1);
+
+            // The callback forwards each value it receives to the test
+            let (tx, rx) = channel();
+            let tx = Mutex::new(tx);
+            let subscription_id = {
+                let session = session.read();
+                session
+                    .create_subscription(
+                        100.0f64,
+                        100,
+                        10,
+                        0,
+                        0,
+                        true,
+                        DataChangeCallback::new(move |items| {
+                            let tx = tx.lock();
+                            items.iter().for_each(|item| {
+                                let _ = tx.send(item.last_value().value.clone());
+                            });
+                        }),
+                    )
+                    .unwrap()
+            };
+
+            {
+                let session = session.read();
+                let items_to_create = vec![MonitoredItemCreateRequest {
+                    item_to_monitor: node_id.clone().into(),
+                    monitoring_mode: MonitoringMode::Reporting,
+                    requested_parameters: MonitoringParameters {
+                        client_handle: 1,
+                        sampling_interval: 50.0f64,
+                        filter: ExtensionObject::null(),
+                        queue_size: 1,
+                        discard_oldest: true,
+                    },
+                }];
+                let results = session
+                    .create_monitored_items(
+                        subscription_id,
+                        TimestampsToReturn::Both,
+                        &items_to_create,
+                    )
+                    .unwrap();
+                assert!(results[0].status_code.is_good());
+            }
+
+            // Run the session so publish responses are processed
+            let session_tx = Session::run_async(session.clone());
+
+            let timeout = std::time::Duration::from_secs(5);
+
+            // The first notification carries the initial value
+            let value = rx.recv_timeout(timeout).unwrap();
+            assert_eq!(value, Some(Variant::Int32(0)));
+
+            // Changing the value produces a second notification
+            {
+                let session = session.read();
+                let results = session
+                    .write(&[WriteValue {
+                        node_id: node_id.clone(),
+                        attribute_id: AttributeId::Value as u32,
+                        index_range: UAString::null(),
+                        value: Variant::Int32(1).into(),
+                    }])
+                    .unwrap();
+                assert_eq!(results[0], StatusCode::Good);
+            }
+            let value = rx.recv_timeout(timeout).unwrap();
+            assert_eq!(value, Some(Variant::Int32(1)));
+
+            let _ = session_tx.send(SessionCommand::Stop);
+
+            {
+                let session = session.read();
+                session.disconnect();
+            }
+        },
+    );
+}
+
 #[test]
 #[ignore]
 fn method_call() {
@@ -31,6 +31,7 @@ use crate::core::{
         message_writer::MessageWriter,
         tcp_codec::{Message, TcpCodec},
         tcp_types::HelloMessage,
+        transport_metrics::{TransportCounters, TransportMetrics},
         url::hostname_port_from_url,
     },
     prelude::*,
@@ -50,6 +51,8 @@ use crate::client::{
 struct MessageChunkWithChunkInfo {
     header: ChunkInfo,
     data_with_header: Vec<u8>,
+    /// Size in bytes of the chunk as it was received
+    received_size: usize,
 }
 
 struct ReadState {
@@ -60,6 +63,8 @@ struct ReadState {
     /// Last decoded sequence number
     last_received_sequence_number: u32,
     chunks: HashMap<u32, Vec<MessageChunkWithChunkInfo>>,
+    /// Counters of the messages received
+    transport_counters: Arc<TransportCounters>,
     pub framed_read: FramedRead<ReadHalf<TcpStream>, TcpCodec>,
 }
 
@@ -75,6 +80,7 @@ impl ReadState {
         secure_channel: Arc<RwLock<SecureChannel>>,
         message_queue: Arc<RwLock<MessageQueue>>,
         session_state: &SessionState,
+        transport_counters: Arc<TransportCounters>,
         framed_read: FramedRead<ReadHalf<TcpStream>, TcpCodec>,
     ) -> Self {
         ReadState {
@@ -84,6 +90,7 @@ impl ReadState {
             last_received_sequence_number: 0,
             message_queue,
             chunks: HashMap::new(),
+            transport_counters,
             framed_read,
         }
     }
@@ -107,6 +114,7 @@ impl ReadState {
         chunk: MessageChunk,
     ) -> Result<Option<SupportedMessage>, StatusCode> {
         // trace!("Got a chunk {:?}", chunk);
+        let received_size = chunk.data.len();
         let chunk = {
             let mut secure_channel = trace_write_lock!(self.secure_channel);
             secure_channel.verify_and_remove_security(&chunk.data)?
@@ -128,6 +136,7 @@ impl ReadState {
                 chunks.push(MessageChunkWithChunkInfo {
                     header: chunk_info,
                     data_with_header: chunk.data,
+                    received_size,
                 });
                 let chunks_len = self.chunks.len();
                 if self.max_chunk_count > 0 && chunks_len > self.max_chunk_count {
@@ -153,8 +162,13 @@ impl ReadState {
         chunks.push(MessageChunkWithChunkInfo {
             header: chunk_info,
             data_with_header: chunk.data,
+            received_size,
         });
-        let in_chunks = Self::merge_chunks(self.chunks.remove(&req_id).unwrap())?;
+        let chunks = self.chunks.remove(&req_id).unwrap();
+        let message_size = chunks.iter().map(|c| c.received_size).sum();
+        self.transport_counters
+            .record_received(message_size, chunks.len());
+        let in_chunks = Self::merge_chunks(chunks)?;
         let message = self.turn_received_chunks_into_message(&in_chunks)?;
 
         Ok(Some(message))
@@ -221,19 +235,22 @@ impl WriteState {
         message_queue: Arc<RwLock<MessageQueue>>,
         writer: WriteHalf<TcpStream>,
         session_state: &SessionState,
+        transport_counters: Arc<TransportCounters>,
     ) -> Self {
         let receiver = {
             let mut queue = trace_write_lock!(message_queue);
             queue.clear();
             queue.make_request_channel()
         };
+        let mut send_buffer = MessageWriter::new(
+            session_state.send_buffer_size(),
+            session_state.max_message_size(),
+            session_state.max_chunk_count(),
+        );
+        send_buffer.set_transport_counters(transport_counters);
         WriteState {
             secure_channel,
-            send_buffer: MessageWriter::new(
-                session_state.send_buffer_size(),
-                session_state.max_message_size(),
-                session_state.max_chunk_count(),
-            ),
+            send_buffer,
             writer,
             message_queue,
             receiver,
@@ -263,6 +280,8 @@ pub(crate) struct TcpTransport {
     connection_state: ConnectionStateMgr,
     /// Message queue for requests / responses
     message_queue: Arc<RwLock<MessageQueue>>,
+    /// Counters of the messages sent and received, kept across reconnects
+    transport_counters: Arc<TransportCounters>,
     /// Tokio runtime
     runtime: Arc<Mutex<tokio::runtime::Runtime>>,
 }
@@ -305,10 +324,16 @@ impl TcpTransport {
             secure_channel,
             connection_state,
             message_queue,
+            transport_counters: Arc::new(TransportCounters::new()),
             runtime: Arc::new(Mutex::new(runtime)),
         }
     }
 
+    /// Returns a snapshot of the counters of messages sent and received by the transport
+    pub fn metrics(&self) -> TransportMetrics {
+        self.transport_counters.snapshot()
+    }
+
     /// Connects the stream to the specified endpoint
     pub fn connect(&self, endpoint_url: &str) -> Result<(), StatusCode> {
         debug_assert!(!self.is_connected(), "Should not try to connect when already connected");
@@ -343,10 +368,12 @@ impl TcpTransport {
             session_state,
             secure_channel,
             message_queue,
+            transport_counters,
         ) = (self.connection_state.clone(),
              self.session_state.clone(),
              self.secure_channel.clone(),
-             self.message_queue.clone(), );
+             self.message_queue.clone(),
+             self.transport_counters.clone(), );
 
         let (connection_status_sender,
             connection_status_receiver) = std::sync::mpsc::channel();
@@ -357,6 +384,7 @@ impl TcpTransport {
             session_state.clone(),
             secure_channel,
             message_queue,
+            transport_counters,
         );
         let runtime = self.runtime.clone();
         thread::spawn(move || {
@@ -400,6 +428,7 @@ impl TcpTransport {
         session_state: Arc<RwLock<SessionState>>,
         secure_channel: Arc<RwLock<SecureChannel>>,
         message_queue: Arc<RwLock<MessageQueue>>,
+        transport_counters: Arc<TransportCounters>,
     ) -> Result<(ReadState, WriteState), StatusCode> {
         debug!(
             "Creating a connection task to connect to {} with url {}",
@@ -430,6 +459,7 @@ impl TcpTransport {
                 secure_channel.clone(),
                 message_queue.clone(),
                 &session_state,
+                transport_counters.clone(),
                 framed_read,
             );
             let write_state = WriteState::new(
@@ -437,19 +467,29 @@ impl TcpTransport {
                 message_queue.clone(),
                 writer,
                 &session_state,
+                transport_counters,
             );
             (hello, read_state, write_state)
         };
 
-        write_state.writer.write_all(&hello.encode_to_vec()).await.map_err(|err| {
+        let hello = hello.encode_to_vec();
+        write_state.writer.write_all(&hello).await.map_err(|err| {
             error!("Cannot send hello to server, err = {:?}", err);
             StatusCode::BadCommunicationError
         })?;
+        write_state
+            .send_buffer
+            .transport_counters()
+            .record_sent(hello.len(), 1);
         connection_state.set_state(ConnectionState::WaitingForAck);
         match read_state.framed_read.next().await {
             Some(Ok(Message::Acknowledge(ack))) => {
                 // TODO revise our sizes and other things according to the ACK
-                log::trace!("Received acknowledgement: {:?}", ack)
+                log::trace!("Received acknowledgement: {:?}", ack);
+                if !ack.is_protocol_version_supported() {
+                    error!("Server protocol version {} is unsupported, client supports {}", ack.protocol_version, PROTOCOL_VERSION);
+                    return Err(StatusCode::BadProtocolVersionUnsupported);
+                }
             }
             other => {
                 error!("Unexpected error while waiting for server ACK. Expected ACK, got {:?}", other);
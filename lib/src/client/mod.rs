@@ -107,6 +107,7 @@ mod builder;
 mod callbacks;
 mod client;
 mod config;
+mod retry_policy;
 mod session;
 mod session_retry_policy;
 
@@ -152,6 +153,7 @@ pub mod prelude {
         callbacks::*,
         client::*,
         config::*,
+        retry_policy::RetryPolicy,
         session::{services::*, session::*},
         subscription::MonitoredItem,
     };
@@ -0,0 +1,188 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+use std::time::Duration;
+
+use crate::types::status_code::StatusCode;
+
+/// The retry policy determines whether a request that failed should be sent again. Unlike the
+/// `SessionRetryPolicy`, which governs reconnecting a session that has gone down, this policy
+/// applies to individual service calls made through the session.
+///
+/// Only failures that are transient in nature, i.e. the transport dropped or timed out, are retried.
+/// Logical errors such as `BadNodeIdUnknown` or `BadTypeMismatch` would fail the same way every
+/// time and so are returned immediately.
+///
+/// Between each attempt the policy waits for a backoff period which starts at `initial_backoff`
+/// and doubles with each subsequent attempt up to `max_backoff`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first. A value of 1 means no retries.
+    max_attempts: u32,
+    /// Backoff before the first retry in milliseconds
+    initial_backoff: u64,
+    /// The maximum backoff between retries in milliseconds
+    max_backoff: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::never()
+    }
+}
+
+impl RetryPolicy {
+    /// The default initial backoff
+    pub const DEFAULT_INITIAL_BACKOFF_MS: u64 = 100;
+    /// The default maximum backoff
+    pub const DEFAULT_MAX_BACKOFF_MS: u64 = 5000;
+
+    /// Create a `RetryPolicy` that makes up to `max_attempts` attempts with exponential backoff
+    /// between them. A `max_attempts` of 0 is treated as 1.
+    pub fn new(max_attempts: u32, initial_backoff: u64, max_backoff: u64) -> Self {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+        }
+    }
+
+    /// Create a `RetryPolicy` that makes a single attempt and never retries.
+    pub fn never() -> Self {
+        Self::new(1, 0, 0)
+    }
+
+    /// Create a `RetryPolicy` with the default backoff settings
+    pub fn with_max_attempts(max_attempts: u32) -> Self {
+        Self::new(
+            max_attempts,
+            Self::DEFAULT_INITIAL_BACKOFF_MS,
+            Self::DEFAULT_MAX_BACKOFF_MS,
+        )
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Tests if the status code is a transient failure that is worth retrying.
+    pub fn is_transient(status_code: StatusCode) -> bool {
+        matches!(
+            status_code.status(),
+            StatusCode::BadConnectionClosed
+                | StatusCode::BadTimeout
+                | StatusCode::BadSecureChannelClosed
+        )
+    }
+
+    /// Returns the time to wait before making the attempt following the supplied (1 based)
+    /// failed attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        // Shift is capped to avoid overflow, the max backoff clamps it anyway
+        let shift = attempt.saturating_sub(1).min(32);
+        let backoff = self.initial_backoff.saturating_mul(1u64 << shift);
+        Duration::from_millis(backoff.min(self.max_backoff))
+    }
+
+    /// Calls the supplied function until it succeeds, fails with a non-transient error or the
+    /// maximum number of attempts has been made. The result of the last attempt is returned.
+    pub fn execute<T, F>(&self, mut f: F) -> Result<T, StatusCode>
+    where
+        F: FnMut() -> Result<T, StatusCode>,
+    {
+        let mut attempt = 1;
+        loop {
+            match f() {
+                Err(status_code)
+                    if attempt < self.max_attempts && Self::is_transient(status_code) =>
+                {
+                    let backoff = self.backoff(attempt);
+                    info!(
+                        "Request failed with transient error {}, attempt {} of {}, retrying in {} ms",
+                        status_code,
+                        attempt,
+                        self.max_attempts,
+                        backoff.as_millis()
+                    );
+                    if !backoff.is_zero() {
+                        std::thread::sleep(backoff);
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[test]
+fn retry_backoff() {
+    let retry_policy = RetryPolicy::new(10, 100, 1000);
+    assert_eq!(retry_policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(retry_policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(retry_policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(retry_policy.backoff(4), Duration::from_millis(800));
+    assert_eq!(retry_policy.backoff(5), Duration::from_millis(1000));
+    assert_eq!(retry_policy.backoff(100), Duration::from_millis(1000));
+}
+
+#[test]
+fn retry_transient_error() {
+    let retry_policy = RetryPolicy::new(3, 1, 1);
+    [
+        StatusCode::BadConnectionClosed,
+        StatusCode::BadTimeout,
+        StatusCode::BadSecureChannelClosed,
+    ]
+    .iter()
+    .for_each(|status_code| {
+        let mut attempts = 0;
+        let result: Result<(), StatusCode> = retry_policy.execute(|| {
+            attempts += 1;
+            Err(*status_code)
+        });
+        assert_eq!(result.unwrap_err(), *status_code);
+        assert_eq!(attempts, 3);
+    });
+
+    // Succeeds on the second attempt
+    let mut attempts = 0;
+    let result = retry_policy.execute(|| {
+        attempts += 1;
+        if attempts == 1 {
+            Err(StatusCode::BadTimeout)
+        } else {
+            Ok(attempts)
+        }
+    });
+    assert_eq!(result.unwrap(), 2);
+}
+
+#[test]
+fn retry_logical_error() {
+    let retry_policy = RetryPolicy::new(3, 1, 1);
+    [StatusCode::BadNodeIdUnknown, StatusCode::BadTypeMismatch]
+        .iter()
+        .for_each(|status_code| {
+            let mut attempts = 0;
+            let result: Result<(), StatusCode> = retry_policy.execute(|| {
+                attempts += 1;
+                Err(*status_code)
+            });
+            assert_eq!(result.unwrap_err(), *status_code);
+            assert_eq!(attempts, 1);
+        });
+}
+
+#[test]
+fn retry_never() {
+    let retry_policy = RetryPolicy::never();
+    let mut attempts = 0;
+    let result: Result<(), StatusCode> = retry_policy.execute(|| {
+        attempts += 1;
+        Err(StatusCode::BadTimeout)
+    });
+    assert_eq!(result.unwrap_err(), StatusCode::BadTimeout);
+    assert_eq!(attempts, 1);
+}
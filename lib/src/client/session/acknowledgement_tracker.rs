@@ -0,0 +1,133 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+//! Tracks the notification messages the client has received so they can be acknowledged to the
+//! server in subsequent publish requests.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::types::{service_types::SubscriptionAcknowledgement, status_code::StatusCode};
+
+/// Holds the sequence numbers of received notification messages, per subscription, until the
+/// server confirms it has been told about them.
+///
+/// Sequence numbers start out pending. When a publish request is sent they are moved into the
+/// request and held against its request handle. The publish response reports a result for each
+/// acknowledgement in the request, at which point the acknowledgement is either done with or
+/// put back to be sent again. If the request never gets a response, all of its acknowledgements
+/// are put back.
+#[derive(Debug, Default)]
+pub(crate) struct AcknowledgementTracker {
+    /// Sequence numbers not yet sent in a publish request, by subscription id
+    pending: BTreeMap<u32, BTreeSet<u32>>,
+    /// Acknowledgements sent in publish requests awaiting a response, by request handle
+    in_flight: HashMap<u32, Vec<SubscriptionAcknowledgement>>,
+}
+
+impl AcknowledgementTracker {
+    pub fn new() -> AcknowledgementTracker {
+        AcknowledgementTracker::default()
+    }
+
+    /// Records that a notification message with the sequence number was received for the
+    /// subscription and must be acknowledged.
+    pub fn received(&mut self, subscription_id: u32, sequence_number: u32) {
+        self.pending
+            .entry(subscription_id)
+            .or_insert_with(BTreeSet::new)
+            .insert(sequence_number);
+    }
+
+    /// Returns the number of acknowledgements that have not been sent yet
+    pub fn pending_len(&self) -> usize {
+        self.pending.values().map(|s| s.len()).sum()
+    }
+
+    /// Returns the number of acknowledgements that have been sent but not yet confirmed
+    pub fn in_flight_len(&self) -> usize {
+        self.in_flight.values().map(|s| s.len()).sum()
+    }
+
+    /// Takes every pending acknowledgement for a publish request with the supplied request handle.
+    /// Returns `None` if there is nothing to acknowledge.
+    pub fn take_for_publish(
+        &mut self,
+        request_handle: u32,
+    ) -> Option<Vec<SubscriptionAcknowledgement>> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            let subscription_acknowledgements: Vec<SubscriptionAcknowledgement> =
+                std::mem::take(&mut self.pending)
+                    .into_iter()
+                    .flat_map(|(subscription_id, sequence_numbers)| {
+                        sequence_numbers.into_iter().map(move |sequence_number| {
+                            SubscriptionAcknowledgement {
+                                subscription_id,
+                                sequence_number,
+                            }
+                        })
+                    })
+                    .collect();
+            self.in_flight
+                .insert(request_handle, subscription_acknowledgements.clone());
+            Some(subscription_acknowledgements)
+        }
+    }
+
+    /// Processes the acknowledgement results from the response to a publish request. The results
+    /// are in the same order as the acknowledgements sent in the request.
+    ///
+    /// Acknowledgements that succeeded are done with. A `BadSequenceNumberUnknown` means the server
+    /// no longer holds the notification, e.g. because it was already acknowledged, and a
+    /// `BadSubscriptionIdInvalid` means the subscription is gone, so these are dropped
+    /// too. Anything else is retried in the next publish request.
+    pub fn publish_response(&mut self, request_handle: u32, results: Option<&[StatusCode]>) {
+        if let Some(subscription_acknowledgements) = self.in_flight.remove(&request_handle) {
+            let results = results.unwrap_or(&[]);
+            subscription_acknowledgements
+                .into_iter()
+                .enumerate()
+                .for_each(|(i, ack)| {
+                    match results.get(i) {
+                        Some(&StatusCode::Good) => {}
+                        Some(&StatusCode::BadSequenceNumberUnknown) => {
+                            debug!(
+                                "Dropping acknowledgement of sequence nr {} for subscription {}, server doesn't know it",
+                                ack.sequence_number, ack.subscription_id
+                            );
+                        }
+                        Some(&StatusCode::BadSubscriptionIdInvalid) => {
+                            debug!(
+                                "Dropping acknowledgement of sequence nr {} for subscription {} which no longer exists",
+                                ack.sequence_number, ack.subscription_id
+                            );
+                        }
+                        result => {
+                            debug!(
+                                "Acknowledgement of sequence nr {} for subscription {} will be retried, result {:?}",
+                                ack.sequence_number, ack.subscription_id, result
+                            );
+                            self.received(ack.subscription_id, ack.sequence_number);
+                        }
+                    }
+                });
+        }
+    }
+
+    /// Puts the acknowledgements from a publish request that failed back to be sent again.
+    pub fn publish_failed(&mut self, request_handle: u32) {
+        if let Some(subscription_acknowledgements) = self.in_flight.remove(&request_handle) {
+            subscription_acknowledgements.into_iter().for_each(|ack| {
+                self.received(ack.subscription_id, ack.sequence_number);
+            });
+        }
+    }
+
+    /// Forgets about all acknowledgements, e.g. because the session has gone.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.in_flight.clear();
+    }
+}
@@ -1,3 +1,4 @@
+pub mod acknowledgement_tracker;
 pub mod services;
 pub mod session;
 pub mod session_state;
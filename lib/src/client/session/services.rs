@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: MPL-2.0
 // Copyright (C) 2017-2022 Adam Lock
 
-use std::{convert::TryFrom, sync::mpsc::SyncSender};
+use std::{collections::BTreeMap, convert::TryFrom, sync::mpsc::SyncSender};
 
 use crate::{
     client::callbacks::OnSubscriptionNotification,
     core::supported_message::SupportedMessage,
     types::{
-        node_ids::{MethodId, ObjectId},
+        node_ids::{MethodId, ObjectId, VariableId},
         status_code::StatusCode,
         *,
     },
@@ -419,6 +419,44 @@ pub trait AttributeService: Service {
         max_age: f64,
     ) -> Result<Vec<DataValue>, StatusCode>;
 
+    /// Reads the server's service level, a byte from 0 to 255 indicating its ability to provide
+    /// its data from worst to best. Clients of redundant servers use this to choose which server
+    /// to connect to or fail over to.
+    ///
+    /// See OPC UA Part 4 - Services 6.6.2.4.2 for a description of the service level.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u8)` - The service level of the server.
+    /// * `Err(StatusCode)` - Status code reason for failure, e.g. `BadTypeMismatch` if the
+    ///   server's value is not a byte.
+    ///
+    fn read_service_level(&self) -> Result<u8, StatusCode> {
+        let node_id: NodeId = VariableId::Server_ServiceLevel.into();
+        let mut results = self.read(
+            &[ReadValueId::from(node_id)],
+            TimestampsToReturn::Neither,
+            0.0,
+        )?;
+        if results.len() != 1 {
+            error!(
+                "Expected 1 result reading the service level and got {}",
+                results.len()
+            );
+            return Err(StatusCode::BadUnexpectedError);
+        }
+        let value = results.remove(0);
+        let status = value.status().status();
+        if !status.is_good() {
+            Err(status)
+        } else if let Some(Variant::Byte(service_level)) = value.value {
+            Ok(service_level)
+        } else {
+            error!("Service level value {:?} is not a byte", value.value);
+            Err(StatusCode::BadTypeMismatch)
+        }
+    }
+
     /// Reads historical values or events of one or more nodes. The caller is expected to provide
     /// a HistoryReadAction enum which must be one of the following:
     ///
@@ -495,6 +533,45 @@ pub trait AttributeService: Service {
     ) -> Result<Vec<HistoryUpdateResult>, StatusCode>;
 }
 
+/// Reads every attribute of a node through the attribute service, leaving out those the server
+/// rejects with `BadAttributeIdInvalid`. This is the implementation of `Session::read_all_attributes`.
+pub(crate) fn read_all_attributes<S>(
+    session: &S,
+    node_id: &NodeId,
+) -> Result<BTreeMap<AttributeId, DataValue>, StatusCode>
+where
+    S: AttributeService,
+{
+    let attribute_ids = (AttributeId::NodeId as u32..=AttributeId::AccessLevelEx as u32)
+        .filter_map(|attribute_id| AttributeId::from_u32(attribute_id).ok())
+        .collect::<Vec<_>>();
+    let nodes_to_read = attribute_ids
+        .iter()
+        .map(|attribute_id| ReadValueId {
+            node_id: node_id.clone(),
+            attribute_id: *attribute_id as u32,
+            index_range: UAString::null(),
+            data_encoding: QualifiedName::null(),
+        })
+        .collect::<Vec<_>>();
+    let results = session.read(&nodes_to_read, TimestampsToReturn::Neither, 0.0)?;
+    if results.len() != attribute_ids.len() {
+        error!(
+            "Expected {} results reading attributes of {} and got {}",
+            attribute_ids.len(),
+            node_id,
+            results.len()
+        );
+        Err(StatusCode::BadUnexpectedError)
+    } else {
+        Ok(attribute_ids
+            .into_iter()
+            .zip(results)
+            .filter(|(_, value)| value.status().status() != StatusCode::BadAttributeIdInvalid)
+            .collect())
+    }
+}
+
 /// Method Service set
 pub trait MethodService: Service {
     /// Calls a single method on an object on the server by sending a [`CallRequest`] to the server.
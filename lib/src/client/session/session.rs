@@ -9,7 +9,7 @@
 //! and events.
 use std::{
     cmp,
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
     result::Result,
     str::FromStr,
     sync::{mpsc::SyncSender, Arc},
@@ -24,14 +24,16 @@ use tokio::{
 use crate::core::{
     comms::{
         secure_channel::{Role, SecureChannel},
+        transport_metrics::TransportMetrics,
         url::*,
     },
     supported_message::SupportedMessage,
     RUNTIME,
 };
 use crate::crypto::{
-    self as crypto, user_identity::make_user_name_identity_token, CertificateStore, SecurityPolicy,
-    X509,
+    self as crypto,
+    user_identity::{make_user_name_identity_token, make_x509_identity_token},
+    CertificateStore, SecurityPolicy, X509,
 };
 use crate::sync::*;
 use crate::types::{node_ids::ObjectId, status_code::StatusCode, *};
@@ -43,7 +45,8 @@ use crate::client::{
     comms::tcp_transport::TcpTransport,
     message_queue::MessageQueue,
     process_service_result, process_unexpected_response,
-    session::services::*,
+    retry_policy::RetryPolicy,
+    session::services::{self, *},
     session::session_state::{ConnectionState, SessionState},
     session_retry_policy::{Answer, SessionRetryPolicy},
     subscription::{self, Subscription},
@@ -138,6 +141,8 @@ pub struct Session {
     message_queue: Arc<RwLock<MessageQueue>>,
     /// Session retry policy.
     session_retry_policy: Arc<Mutex<SessionRetryPolicy>>,
+    /// Retry policy for requests that fail with a transient error.
+    request_retry_policy: RetryPolicy,
     /// Ignore clock skew between the client and the server.
     ignore_clock_skew: bool,
     /// Single threaded executor flag (for TCP transport). Unused.
@@ -223,6 +228,7 @@ impl Session {
             secure_channel,
             message_queue,
             session_retry_policy: Arc::new(Mutex::new(session_retry_policy)),
+            request_retry_policy: RetryPolicy::default(),
             ignore_clock_skew,
             single_threaded_executor,
             runtime: Arc::new(Mutex::new(runtime)),
@@ -274,6 +280,31 @@ impl Session {
         self.session_retry_policy = Arc::new(Mutex::new(session_retry_policy));
     }
 
+    /// Sets the retry policy that dictates what this session will do if a request fails with a
+    /// transient error such as a timeout or the connection closing. By default requests are not
+    /// retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_retry_policy` - the request retry policy to use
+    ///
+    pub fn set_request_retry_policy(&mut self, request_retry_policy: RetryPolicy) {
+        self.request_retry_policy = request_retry_policy;
+    }
+
+    /// Sets the number of publish requests the session keeps outstanding with the server while
+    /// it has subscriptions. The default is 2. The number is reduced automatically if the server
+    /// responds that it has too many publish requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_inflight_publish_requests` - the number of publish requests to keep in flight
+    ///
+    pub fn set_max_inflight_publish_requests(&mut self, max_inflight_publish_requests: usize) {
+        let mut session_state = trace_write_lock!(self.session_state);
+        session_state.set_max_inflight_publish_requests(max_inflight_publish_requests);
+    }
+
     /// Register a callback to be notified when the session has been closed.
     ///
     /// # Arguments
@@ -594,6 +625,13 @@ impl Session {
         self.transport.is_connected()
     }
 
+    /// Returns a snapshot of the counters of messages and chunks sent and received by the
+    /// session's transport. The counters accumulate over every connection the session makes,
+    /// including reconnects.
+    pub fn transport_metrics(&self) -> TransportMetrics {
+        self.transport.metrics()
+    }
+
     /// Internal constant for the sleep interval used during polling
     const POLL_SLEEP_INTERVAL: u64 = 10;
 
@@ -908,9 +946,13 @@ impl Session {
 
                     // Calculate to interval since last check
                     let interval = now - last_timeout;
-                    if interval > subscription_activity_interval {
-                        let mut session_state = trace_write_lock!(session_state);
-                        let _ = session_state.async_publish();
+
+                    // Publish requests that were lost must not hold their place in the window
+                    // forever, or no more requests would be sent
+                    let mut session_state = trace_write_lock!(session_state);
+                    let timed_out = session_state.publish_requests_timed_out(now);
+                    if timed_out > 0 || interval > subscription_activity_interval {
+                        let _ = session_state.fill_publish_window();
                     }
                 }
             }
@@ -960,10 +1002,10 @@ impl Session {
                 subscription_state.add_subscription(subscription);
             }
 
-            // Send an async publish request for this new subscription
+            // Send async publish requests for this new subscription
             {
                 let mut session_state = trace_write_lock!(self.session_state);
-                let _ = session_state.async_publish();
+                let _ = session_state.fill_publish_window();
             }
 
             session_debug!(
@@ -1048,6 +1090,30 @@ impl Session {
         }
     }
 
+    /// Reads every attribute of a node in a single [`ReadRequest`], e.g. to dump a node when
+    /// debugging. All attribute ids are requested since the node class is not known up front.
+    /// Those the server rejects with `BadAttributeIdInvalid` because the node does not have them
+    /// are left out, any other result is returned as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node to read the attributes of.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BTreeMap<AttributeId, DataValue>)` - The [`DataValue`] of each attribute the node has.
+    /// * `Err(StatusCode)` - Status code reason for failure.
+    ///
+    /// [`ReadRequest`]: ./struct.ReadRequest.html
+    /// [`DataValue`]: ./struct.DataValue.html
+    ///
+    pub fn read_all_attributes(
+        &self,
+        node_id: &NodeId,
+    ) -> Result<BTreeMap<AttributeId, DataValue>, StatusCode> {
+        services::read_all_attributes(self, node_id)
+    }
+
     /// Returns the subscription state object
     pub fn subscription_state(&self) -> Arc<RwLock<SubscriptionState>> {
         self.subscription_state.clone()
@@ -1106,6 +1172,16 @@ impl Session {
                 );
                 Err(StatusCode::BadSecurityPolicyRejected)
             }
+            Some(policy) if !policy.is_valid_for_endpoint(endpoint.security_mode) => {
+                // e.g. a password would be sent in the clear, so the token is not sent at all
+                session_error!(
+                    self,
+                    "User token policy {} is not secure for an endpoint with security mode {}, cannot connect",
+                    policy.policy_id,
+                    endpoint.security_mode
+                );
+                Err(StatusCode::BadSecurityPolicyRejected)
+            }
             Some(policy) => {
                 let security_policy = if policy.security_policy_uri.is_null() {
                     // Assume None
@@ -1170,19 +1246,20 @@ impl Session {
                                         StatusCode::BadSecurityPolicyRejected
                                     })?;
 
-                                // Create a signature using the X509 private key to sign the server's cert and nonce
-                                let user_token_signature = crypto::create_signature_data(
-                                    &private_key,
-                                    security_policy,
-                                    &server_cert.as_byte_string(),
-                                    &ByteString::from(server_nonce),
-                                )?;
-
-                                // Create identity token
-                                let identity_token = X509IdentityToken {
-                                    policy_id: policy.policy_id.clone(),
-                                    certificate_data: certificate_data.as_byte_string(),
+                                // Sign the server's cert and nonce with the X509 private key
+                                let channel_security_policy = {
+                                    let secure_channel = trace_read_lock!(self.secure_channel);
+                                    secure_channel.security_policy()
                                 };
+                                let (identity_token, user_token_signature) =
+                                    make_x509_identity_token(
+                                        channel_security_policy,
+                                        policy,
+                                        server_cert,
+                                        server_nonce,
+                                        &certificate_data,
+                                        &private_key,
+                                    )?;
                                 let identity_token = ExtensionObject::from_encodable(
                                     ObjectId::X509IdentityToken_Encoding_DefaultBinary,
                                     &identity_token,
@@ -1292,10 +1369,16 @@ impl Session {
                     }
                 }
 
-                // Send another publish request
+                // Replace the publish request the server just consumed
                 {
                     let mut session_state = trace_write_lock!(self.session_state);
-                    let _ = session_state.async_publish();
+                    session_state.subscription_acknowledgements_completed(
+                        response.response_header.request_handle,
+                        response.results.as_deref(),
+                    );
+                    session_state
+                        .publish_request_completed(response.response_header.request_handle);
+                    let _ = session_state.fill_publish_window();
                 }
             }
             SupportedMessage::ServiceFault(response) => {
@@ -1307,21 +1390,28 @@ impl Session {
                 );
                 session_trace!(self, "ServiceFault {:?}", response);
 
+                let mut session_state = trace_write_lock!(self.session_state);
+                session_state.publish_request_completed(response.response_header.request_handle);
+                // The acknowledgements in the failed request go out again with the next one
+                session_state
+                    .subscription_acknowledgements_failed(response.response_header.request_handle);
                 match service_result {
                     StatusCode::BadTimeout => {
                         debug!("Publish request timed out so sending another");
-                        let mut session_state = trace_write_lock!(self.session_state);
-                        let _ = session_state.async_publish();
+                        let _ = session_state.fill_publish_window();
                     }
                     StatusCode::BadTooManyPublishRequests => {
-                        // Turn off publish requests until server says otherwise
-                        debug!("Server tells us too many publish requests so waiting for a response before resuming");
+                        // Keep no more requests in flight than the server is prepared to hold
+                        session_state.shrink_publish_window();
+                    }
+                    StatusCode::BadNoSubscription => {
+                        // The session is fine, there is just nothing to publish. The window is
+                        // filled again when a subscription is created.
+                        debug!("Server has no subscriptions for the session, so publishing stops");
                     }
                     StatusCode::BadSessionClosed
                     | StatusCode::BadSessionIdInvalid
-                    | StatusCode::BadNoSubscription
                     | StatusCode::BadSubscriptionIdInvalid => {
-                        let mut session_state = trace_write_lock!(self.session_state);
                         session_state.on_session_closed(service_result)
                     }
                     _ => (),
@@ -1342,13 +1432,17 @@ impl Service for Session {
         session_state.make_request_header()
     }
 
-    /// Synchronously sends a request. The return value is the response to the request
+    /// Synchronously sends a request. The return value is the response to the request. If the
+    /// request fails with a transient error it may be sent again according to the request retry policy.
     fn send_request<T>(&self, request: T) -> Result<SupportedMessage, StatusCode>
     where
         T: Into<SupportedMessage>,
     {
-        let mut session_state = trace_write_lock!(self.session_state);
-        session_state.send_request(request)
+        let request = request.into();
+        self.request_retry_policy.execute(|| {
+            let mut session_state = trace_write_lock!(self.session_state);
+            session_state.send_request(request.clone())
+        })
     }
 
     // Asynchronously sends a request. The return value is the request handle of the request
@@ -1472,7 +1566,7 @@ impl SessionService for Session {
 
         // Security
         let client_certificate = if let Some(ref client_certificate) = client_certificate {
-            client_certificate.as_byte_string()
+            client_certificate.as_byte_string()?
         } else {
             ByteString::null()
         };
@@ -1489,8 +1583,8 @@ impl SessionService for Session {
             server_uri,
             endpoint_url,
             session_name,
-            client_nonce,
-            client_certificate,
+            client_nonce: client_nonce.clone(),
+            client_certificate: client_certificate.clone(),
             requested_session_timeout,
             max_response_message_size: 0,
         };
@@ -1547,7 +1641,15 @@ impl SessionService for Session {
                     if result.is_bad() {
                         result
                     } else {
-                        StatusCode::Good
+                        // The server proves it holds the certificate's private key by signing
+                        // our certificate and nonce with it
+                        crypto::verify_server_signature(
+                            &response.server_signature,
+                            security_policy,
+                            &server_certificate,
+                            &client_certificate,
+                            client_nonce.as_ref(),
+                        )
                     }
                 } else {
                     session_error!(self, "Server did not supply a valid X509 certificate");
@@ -1558,7 +1660,7 @@ impl SessionService for Session {
             };
 
             if !cert_status_code.is_good() {
-                session_error!(self, "Server's certificate was rejected");
+                session_error!(self, "Server's certificate or signature was rejected");
                 Err(cert_status_code)
             } else {
                 // Spawn a task to ping the server to keep the connection alive before the session
@@ -1571,8 +1673,6 @@ impl SessionService for Session {
                 self.spawn_session_activity_task(response.revised_session_timeout);
                 self.spawn_subscription_activity_task();
 
-                // TODO Verify signature using server's public key (from endpoint) comparing with data made from client certificate and nonce.
-                // crypto::verify_signature_data(verification_key, security_policy, server_certificate, client_certificate, client_nonce);
                 Ok(session_id)
             }
         } else {
@@ -1635,14 +1735,13 @@ impl SessionService for Session {
                     .remote_cert()
                     .as_ref()
                     .unwrap()
-                    .as_byte_string();
-                let server_nonce = ByteString::from(secure_channel.remote_nonce());
+                    .as_byte_string()?;
                 let signing_key = client_pkey.as_ref().unwrap();
-                crypto::create_signature_data(
+                crypto::create_client_signature(
                     signing_key,
-                    security_policy,
                     &server_cert,
-                    &server_nonce,
+                    server_nonce,
+                    security_policy,
                 )?
             }
         };
@@ -3,6 +3,7 @@
 // Copyright (C) 2017-2022 Adam Lock
 
 use std::{
+    collections::BTreeMap,
     sync::{
         atomic::{AtomicU32, Ordering},
         mpsc::{self, Receiver, SyncSender},
@@ -15,7 +16,9 @@ use chrono::Duration;
 use tokio::time::Instant;
 
 use crate::core::{
-    comms::secure_channel::SecureChannel, handle::Handle, supported_message::SupportedMessage,
+    comms::{secure_channel::SecureChannel, tcp_types::PROTOCOL_VERSION},
+    handle::Handle,
+    supported_message::SupportedMessage,
 };
 use crate::crypto::SecurityPolicy;
 use crate::sync::*;
@@ -25,6 +28,7 @@ use crate::client::{
     callbacks::{OnConnectionStatusChange, OnSessionClosed},
     message_queue::MessageQueue,
     process_unexpected_response,
+    session::acknowledgement_tracker::AcknowledgementTracker,
     subscription_state::SubscriptionState,
 };
 
@@ -122,8 +126,13 @@ pub(crate) struct SessionState {
     request_handle: Handle,
     /// Next monitored item client side handle
     monitored_item_handle: Handle,
-    /// Subscription acknowledgements pending for send
-    subscription_acknowledgements: Vec<SubscriptionAcknowledgement>,
+    /// Subscription acknowledgements pending for send or awaiting confirmation
+    subscription_acknowledgements: AcknowledgementTracker,
+    /// The publish requests that have been sent and are awaiting a response, by request handle,
+    /// with the time each was sent
+    inflight_publish_requests: BTreeMap<u32, Instant>,
+    /// The number of publish requests the session tries to keep in flight
+    max_inflight_publish_requests: usize,
     /// Subscription state
     subscription_state: Arc<RwLock<SubscriptionState>>,
     /// The message queue
@@ -157,6 +166,7 @@ impl SessionState {
     const SEND_BUFFER_SIZE: usize = 65535;
     const RECEIVE_BUFFER_SIZE: usize = 65535;
     const MAX_BUFFER_SIZE: usize = 65535;
+    const DEFAULT_MAX_INFLIGHT_PUBLISH_REQUESTS: usize = 2;
 
     pub fn new(
         ignore_clock_skew: bool,
@@ -180,7 +190,9 @@ impl SessionState {
             session_id: NodeId::null(),
             authentication_token: NodeId::null(),
             monitored_item_handle: Handle::new(Self::FIRST_MONITORED_ITEM_HANDLE),
-            subscription_acknowledgements: Vec::new(),
+            subscription_acknowledgements: AcknowledgementTracker::new(),
+            inflight_publish_requests: BTreeMap::new(),
+            max_inflight_publish_requests: Self::DEFAULT_MAX_INFLIGHT_PUBLISH_REQUESTS,
             subscription_state,
             message_queue,
             session_closed_callback: None,
@@ -228,9 +240,34 @@ impl SessionState {
     pub fn add_subscription_acknowledgement(
         &mut self,
         subscription_acknowledgement: SubscriptionAcknowledgement,
+    ) {
+        self.subscription_acknowledgements.received(
+            subscription_acknowledgement.subscription_id,
+            subscription_acknowledgement.sequence_number,
+        );
+    }
+
+    /// Returns the number of subscription acknowledgements that are queued for the next publish
+    /// request
+    pub fn pending_subscription_acknowledgements(&self) -> usize {
+        self.subscription_acknowledgements.pending_len()
+    }
+
+    /// Called with the acknowledgement results of a publish response so acknowledgements the
+    /// server did not accept can be retried.
+    pub(crate) fn subscription_acknowledgements_completed(
+        &mut self,
+        request_handle: u32,
+        results: Option<&[StatusCode]>,
     ) {
         self.subscription_acknowledgements
-            .push(subscription_acknowledgement);
+            .publish_response(request_handle, results);
+    }
+
+    /// Called when a publish request failed so its acknowledgements are sent again.
+    pub(crate) fn subscription_acknowledgements_failed(&mut self, request_handle: u32) {
+        self.subscription_acknowledgements
+            .publish_failed(request_handle);
     }
 
     pub fn set_authentication_token(&mut self, authentication_token: NodeId) {
@@ -277,11 +314,11 @@ impl SessionState {
 
     /// Sends a publish request containing acknowledgements for previous notifications.
     pub fn async_publish(&mut self) -> Result<u32, StatusCode> {
-        let subscription_acknowledgements = if self.subscription_acknowledgements.is_empty() {
-            None
-        } else {
-            let subscription_acknowledgements: Vec<SubscriptionAcknowledgement> =
-                self.subscription_acknowledgements.drain(..).collect();
+        let request_header = self.make_request_header();
+        let subscription_acknowledgements = self
+            .subscription_acknowledgements
+            .take_for_publish(request_header.request_handle);
+        if let Some(ref subscription_acknowledgements) = subscription_acknowledgements {
             // Debug sequence nrs
             if log_enabled!(log::Level::Debug) {
                 let sequence_nrs: Vec<u32> = subscription_acknowledgements
@@ -293,13 +330,20 @@ impl SessionState {
                     sequence_nrs
                 );
             }
-            Some(subscription_acknowledgements)
-        };
+        }
+        let request_handle = request_header.request_handle;
         let request = PublishRequest {
-            request_header: self.make_request_header(),
+            request_header,
             subscription_acknowledgements,
         };
-        let request_handle = self.async_send_request(request, None)?;
+        let request_handle = self.async_send_request(request, None).map_err(|err| {
+            // Acknowledgements that could not be sent go out with the next request
+            self.subscription_acknowledgements
+                .publish_failed(request_handle);
+            err
+        })?;
+        self.inflight_publish_requests
+            .insert(request_handle, Instant::now());
 
         {
             let mut subscription_state = trace_write_lock!(self.subscription_state);
@@ -310,6 +354,74 @@ impl SessionState {
         Ok(request_handle)
     }
 
+    /// Returns the number of publish requests that are awaiting a response
+    pub fn inflight_publish_requests(&self) -> usize {
+        self.inflight_publish_requests.len()
+    }
+
+    /// Returns the number of publish requests the session tries to keep in flight
+    pub fn max_inflight_publish_requests(&self) -> usize {
+        self.max_inflight_publish_requests
+    }
+
+    /// Sets the number of publish requests the session tries to keep in flight. Keeping more than
+    /// one request with the server means it always has one ready to return notifications with,
+    /// even while the response to another is in transit. A value of 0 is treated as 1.
+    pub fn set_max_inflight_publish_requests(&mut self, max_inflight_publish_requests: usize) {
+        self.max_inflight_publish_requests = max_inflight_publish_requests.max(1);
+    }
+
+    /// Sends publish requests until the configured number are in flight. Returns the number of
+    /// requests that were sent.
+    pub(crate) fn fill_publish_window(&mut self) -> usize {
+        let mut sent = 0;
+        while self.inflight_publish_requests.len() < self.max_inflight_publish_requests {
+            if self.async_publish().is_err() {
+                break;
+            }
+            sent += 1;
+        }
+        sent
+    }
+
+    /// Called when the server has answered a publish request, either with a response or a fault.
+    pub(crate) fn publish_request_completed(&mut self, request_handle: u32) {
+        let _ = self.inflight_publish_requests.remove(&request_handle);
+    }
+
+    /// Gives up on publish requests that have been in flight for longer than the request timeout,
+    /// e.g. because the request or its response was lost. The server should have answered each
+    /// of them by then, if only with a `BadTimeout` fault, so any response that still arrives is
+    /// ignored. This frees their places in the publish window so it can be filled again, and the
+    /// acknowledgements they carried are sent again with the next publish request. Returns the
+    /// number of requests that timed out.
+    pub(crate) fn publish_requests_timed_out(&mut self, now: Instant) -> usize {
+        let request_timeout = std::time::Duration::from_millis(self.request_timeout as u64);
+        let timed_out = self
+            .inflight_publish_requests
+            .iter()
+            .filter(|(_, sent)| now.saturating_duration_since(**sent) > request_timeout)
+            .map(|(request_handle, _)| *request_handle)
+            .collect::<Vec<u32>>();
+        timed_out.iter().for_each(|request_handle| {
+            let _ = self.inflight_publish_requests.remove(request_handle);
+            self.request_has_timed_out(*request_handle);
+            self.subscription_acknowledgements
+                .publish_failed(*request_handle);
+        });
+        timed_out.len()
+    }
+
+    /// Called when the server rejects a publish request because it holds too many. The number of
+    /// requests kept in flight is reduced to what the server currently holds.
+    pub(crate) fn shrink_publish_window(&mut self) {
+        self.max_inflight_publish_requests = self.inflight_publish_requests.len().max(1);
+        debug!(
+            "Server has too many publish requests, reducing the publish window to {}",
+            self.max_inflight_publish_requests
+        );
+    }
+
     /// Synchronously sends a request. The return value is the response to the request
     pub(crate) fn send_request<T>(&mut self, request: T) -> Result<SupportedMessage, StatusCode>
     where
@@ -330,6 +442,8 @@ impl SessionState {
         self.authentication_token = NodeId::null();
         self.request_handle.reset();
         self.monitored_item_handle.reset();
+        self.inflight_publish_requests.clear();
+        self.subscription_acknowledgements.clear();
 
         // Clear the message queue
         {
@@ -447,7 +561,7 @@ impl SessionState {
         let requested_lifetime = REQUESTED_LIFETIME;
         let request = OpenSecureChannelRequest {
             request_header: self.make_request_header(),
-            client_protocol_version: 0,
+            client_protocol_version: PROTOCOL_VERSION,
             request_type,
             security_mode,
             client_nonce,
@@ -455,6 +569,14 @@ impl SessionState {
         };
         let response = self.send_request(request)?;
         if let SupportedMessage::OpenSecureChannelResponse(response) = response {
+            if response.server_protocol_version > PROTOCOL_VERSION {
+                error!(
+                    "Server protocol version {} is unsupported, client supports {}",
+                    response.server_protocol_version, PROTOCOL_VERSION
+                );
+                return Err(StatusCode::BadProtocolVersionUnsupported);
+            }
+
             // Extract the security token from the response.
             let mut security_token = response.security_token.clone();
 
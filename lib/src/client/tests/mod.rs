@@ -1,12 +1,23 @@
-use std::{self, collections::BTreeMap, path::PathBuf};
+use std::{self, collections::BTreeMap, path::PathBuf, sync::mpsc::SyncSender, sync::Arc};
 
-use crate::core::config::Config;
+use tokio::time::Instant;
+
+use crate::core::{
+    comms::secure_channel::SecureChannel, config::Config, supported_message::SupportedMessage,
+};
 use crate::crypto::SecurityPolicy;
+use crate::sync::*;
 use crate::types::*;
 
 use crate::client::{
     builder::ClientBuilder,
     config::{ClientConfig, ClientEndpoint, ClientUserToken, ANONYMOUS_USER_TOKEN_ID},
+    message_queue::{self, MessageQueue},
+    session::{
+        services::{self, AttributeService, HistoryReadAction, HistoryUpdateAction, Service},
+        session_state::SessionState,
+    },
+    subscription_state::SubscriptionState,
 };
 
 fn make_test_file(filename: &str) -> PathBuf {
@@ -159,3 +170,388 @@ fn client_anonymous_user_tokens_id() {
     );
     assert!(!config.is_valid());
 }
+
+fn make_session_state() -> (
+    SessionState,
+    tokio::sync::mpsc::UnboundedReceiver<message_queue::Message>,
+) {
+    let secure_channel = Arc::new(RwLock::new(SecureChannel::new_no_certificate_store()));
+    let subscription_state = Arc::new(RwLock::new(SubscriptionState::new()));
+    let mut message_queue = MessageQueue::new();
+    let rx = message_queue.make_request_channel();
+    let message_queue = Arc::new(RwLock::new(message_queue));
+    let session_state = SessionState::new(true, secure_channel, subscription_state, message_queue);
+    (session_state, rx)
+}
+
+fn count_publish_requests(
+    rx: &mut tokio::sync::mpsc::UnboundedReceiver<message_queue::Message>,
+) -> usize {
+    let mut count = 0;
+    while let Ok(message) = rx.try_recv() {
+        if let message_queue::Message::SupportedMessage(SupportedMessage::PublishRequest(_)) =
+            message
+        {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn take_publish_requests(
+    rx: &mut tokio::sync::mpsc::UnboundedReceiver<message_queue::Message>,
+) -> Vec<PublishRequest> {
+    let mut requests = Vec::new();
+    while let Ok(message) = rx.try_recv() {
+        if let message_queue::Message::SupportedMessage(SupportedMessage::PublishRequest(request)) =
+            message
+        {
+            requests.push(*request);
+        }
+    }
+    requests
+}
+
+fn publish_request_handles(
+    rx: &mut tokio::sync::mpsc::UnboundedReceiver<message_queue::Message>,
+) -> Vec<u32> {
+    take_publish_requests(rx)
+        .iter()
+        .map(|request| request.request_header.request_handle)
+        .collect()
+}
+
+#[test]
+fn publish_window() {
+    let (mut session_state, mut rx) = make_session_state();
+    session_state.set_max_inflight_publish_requests(3);
+
+    // Filling the window sends enough requests to reach the maximum
+    assert_eq!(session_state.fill_publish_window(), 3);
+    assert_eq!(session_state.inflight_publish_requests(), 3);
+    let mut request_handles = publish_request_handles(&mut rx);
+    assert_eq!(request_handles.len(), 3);
+
+    // A full window sends nothing
+    assert_eq!(session_state.fill_publish_window(), 0);
+    assert_eq!(count_publish_requests(&mut rx), 0);
+
+    // Each completed request is replaced
+    session_state.publish_request_completed(request_handles.remove(0));
+    assert_eq!(session_state.inflight_publish_requests(), 2);
+    assert_eq!(session_state.fill_publish_window(), 1);
+    request_handles.extend(publish_request_handles(&mut rx));
+    assert_eq!(request_handles.len(), 3);
+
+    // Completing a request twice, or one that isn't a publish request, changes nothing
+    session_state.publish_request_completed(request_handles[0]);
+    session_state.publish_request_completed(request_handles.remove(0));
+    session_state.publish_request_completed(u32::MAX);
+    assert_eq!(session_state.inflight_publish_requests(), 2);
+
+    // Too many publish requests shrinks the window to what the server holds
+    session_state.shrink_publish_window();
+    assert_eq!(session_state.max_inflight_publish_requests(), 2);
+    assert_eq!(session_state.fill_publish_window(), 0);
+
+    // The window never drops below a single request
+    request_handles
+        .drain(..)
+        .for_each(|request_handle| session_state.publish_request_completed(request_handle));
+    assert_eq!(session_state.inflight_publish_requests(), 0);
+    session_state.shrink_publish_window();
+    assert_eq!(session_state.max_inflight_publish_requests(), 1);
+    session_state.set_max_inflight_publish_requests(0);
+    assert_eq!(session_state.max_inflight_publish_requests(), 1);
+    assert_eq!(session_state.fill_publish_window(), 1);
+
+    // Resetting the session forgets requests that were in flight
+    session_state.reset();
+    assert_eq!(session_state.inflight_publish_requests(), 0);
+}
+
+#[test]
+fn publish_window_timeout() {
+    let (mut session_state, mut rx) = make_session_state();
+    session_state.set_max_inflight_publish_requests(3);
+
+    // The window fills with requests that the server never answers
+    assert_eq!(session_state.fill_publish_window(), 3);
+    let request_handles = publish_request_handles(&mut rx);
+    assert_eq!(request_handles.len(), 3);
+    assert_eq!(session_state.fill_publish_window(), 0);
+
+    // Nothing times out until the request timeout has passed
+    let request_timeout = std::time::Duration::from_millis(session_state.request_timeout() as u64);
+    assert_eq!(session_state.publish_requests_timed_out(Instant::now()), 0);
+    assert_eq!(session_state.inflight_publish_requests(), 3);
+
+    // After that they all time out and the window is filled with new requests
+    let later = Instant::now() + request_timeout + std::time::Duration::from_millis(1);
+    assert_eq!(session_state.publish_requests_timed_out(later), 3);
+    assert_eq!(session_state.inflight_publish_requests(), 0);
+    assert_eq!(session_state.fill_publish_window(), 3);
+    let new_request_handles = publish_request_handles(&mut rx);
+    assert_eq!(new_request_handles.len(), 3);
+    assert!(new_request_handles
+        .iter()
+        .all(|request_handle| !request_handles.contains(request_handle)));
+
+    // A response to a request that timed out doesn't free a place taken by a new one
+    session_state.publish_request_completed(request_handles[0]);
+    assert_eq!(session_state.inflight_publish_requests(), 3);
+}
+
+#[test]
+fn publish_acknowledgements() {
+    let (mut session_state, mut rx) = make_session_state();
+
+    // Two notifications arrive for a subscription
+    [1, 2].iter().for_each(|sequence_number| {
+        session_state.add_subscription_acknowledgement(SubscriptionAcknowledgement {
+            subscription_id: 10,
+            sequence_number: *sequence_number,
+        })
+    });
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 2);
+
+    // Both are acknowledged in the next publish request
+    let request_handle = session_state.async_publish().unwrap();
+    let requests = take_publish_requests(&mut rx);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].subscription_acknowledgements,
+        Some(vec![
+            SubscriptionAcknowledgement {
+                subscription_id: 10,
+                sequence_number: 1,
+            },
+            SubscriptionAcknowledgement {
+                subscription_id: 10,
+                sequence_number: 2,
+            },
+        ])
+    );
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 0);
+
+    // The server accepts one and had already been told about the other, so both are cleared
+    session_state.subscription_acknowledgements_completed(
+        request_handle,
+        Some(&[StatusCode::Good, StatusCode::BadSequenceNumberUnknown]),
+    );
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 0);
+    let _ = session_state.async_publish().unwrap();
+    let requests = take_publish_requests(&mut rx);
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].subscription_acknowledgements.is_none());
+
+    // An acknowledgement in a publish request that fails is sent again
+    session_state.add_subscription_acknowledgement(SubscriptionAcknowledgement {
+        subscription_id: 10,
+        sequence_number: 3,
+    });
+    let request_handle = session_state.async_publish().unwrap();
+    assert_eq!(take_publish_requests(&mut rx).len(), 1);
+    session_state.subscription_acknowledgements_failed(request_handle);
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 1);
+
+    // As is one the server reports some other error for
+    let request_handle = session_state.async_publish().unwrap();
+    let requests = take_publish_requests(&mut rx);
+    assert_eq!(
+        requests[0].subscription_acknowledgements,
+        Some(vec![SubscriptionAcknowledgement {
+            subscription_id: 10,
+            sequence_number: 3,
+        }])
+    );
+    session_state.subscription_acknowledgements_completed(
+        request_handle,
+        Some(&[StatusCode::BadInternalError]),
+    );
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 1);
+
+    // Resetting the session forgets them
+    session_state.reset();
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 0);
+}
+
+#[test]
+fn publish_acknowledgements_timeout() {
+    let (mut session_state, mut rx) = make_session_state();
+
+    // A notification is acknowledged in a publish request that never gets a response
+    session_state.add_subscription_acknowledgement(SubscriptionAcknowledgement {
+        subscription_id: 10,
+        sequence_number: 1,
+    });
+    let _ = session_state.async_publish().unwrap();
+    assert_eq!(take_publish_requests(&mut rx).len(), 1);
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 0);
+
+    // When the request times out the acknowledgement goes out in the next one instead
+    let later = Instant::now()
+        + std::time::Duration::from_millis(session_state.request_timeout() as u64 + 1);
+    assert_eq!(session_state.publish_requests_timed_out(later), 1);
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 1);
+    let request_handle = session_state.async_publish().unwrap();
+    let requests = take_publish_requests(&mut rx);
+    assert_eq!(
+        requests[0].subscription_acknowledgements,
+        Some(vec![SubscriptionAcknowledgement {
+            subscription_id: 10,
+            sequence_number: 1,
+        }])
+    );
+
+    // And is done with once the server confirms it
+    session_state
+        .subscription_acknowledgements_completed(request_handle, Some(&[StatusCode::Good]));
+    session_state.publish_request_completed(request_handle);
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 0);
+    assert_eq!(session_state.publish_requests_timed_out(later), 0);
+    assert_eq!(session_state.pending_subscription_acknowledgements(), 0);
+}
+
+/// Stands in for a server, answering reads from the attributes of a single node
+struct MockAttributeServer {
+    node_id: NodeId,
+    attributes: BTreeMap<AttributeId, DataValue>,
+}
+
+impl Service for MockAttributeServer {
+    fn make_request_header(&self) -> RequestHeader {
+        RequestHeader::dummy()
+    }
+
+    fn send_request<T>(&self, _request: T) -> Result<SupportedMessage, StatusCode>
+    where
+        T: Into<SupportedMessage>,
+    {
+        Err(StatusCode::BadNotImplemented)
+    }
+
+    fn async_send_request<T>(
+        &self,
+        _request: T,
+        _sender: Option<SyncSender<SupportedMessage>>,
+    ) -> Result<u32, StatusCode>
+    where
+        T: Into<SupportedMessage>,
+    {
+        Err(StatusCode::BadNotImplemented)
+    }
+}
+
+impl AttributeService for MockAttributeServer {
+    fn read(
+        &self,
+        nodes_to_read: &[ReadValueId],
+        _timestamps_to_return: TimestampsToReturn,
+        _max_age: f64,
+    ) -> Result<Vec<DataValue>, StatusCode> {
+        Ok(nodes_to_read
+            .iter()
+            .map(|node_to_read| {
+                assert_eq!(node_to_read.node_id, self.node_id);
+                AttributeId::from_u32(node_to_read.attribute_id)
+                    .ok()
+                    .and_then(|attribute_id| self.attributes.get(&attribute_id).cloned())
+                    .unwrap_or_else(|| DataValue {
+                        status: Some(StatusCode::BadAttributeIdInvalid),
+                        ..DataValue::null()
+                    })
+            })
+            .collect())
+    }
+
+    fn history_read(
+        &self,
+        _history_read_details: HistoryReadAction,
+        _timestamps_to_return: TimestampsToReturn,
+        _release_continuation_points: bool,
+        _nodes_to_read: &[HistoryReadValueId],
+    ) -> Result<Vec<HistoryReadResult>, StatusCode> {
+        Err(StatusCode::BadNotImplemented)
+    }
+
+    fn write(&self, _nodes_to_write: &[WriteValue]) -> Result<Vec<StatusCode>, StatusCode> {
+        Err(StatusCode::BadNotImplemented)
+    }
+
+    fn history_update(
+        &self,
+        _history_update_details: &[HistoryUpdateAction],
+    ) -> Result<Vec<HistoryUpdateResult>, StatusCode> {
+        Err(StatusCode::BadNotImplemented)
+    }
+}
+
+#[test]
+fn read_all_attributes() {
+    let node_id = NodeId::new(2, "Temperature");
+    let mut attributes = BTreeMap::new();
+    attributes.insert(AttributeId::NodeId, DataValue::from(node_id.clone()));
+    attributes.insert(
+        AttributeId::BrowseName,
+        DataValue::from(QualifiedName::new(2, "Temperature")),
+    );
+    attributes.insert(AttributeId::Value, DataValue::from(21.5f64));
+    attributes.insert(
+        AttributeId::UserAccessLevel,
+        DataValue {
+            status: Some(StatusCode::BadUserAccessDenied),
+            ..DataValue::null()
+        },
+    );
+    let server = MockAttributeServer {
+        node_id: node_id.clone(),
+        attributes: attributes.clone(),
+    };
+
+    // Attributes the node doesn't have are left out, other failures are kept
+    let result = services::read_all_attributes(&server, &node_id).unwrap();
+    assert_eq!(result, attributes);
+    assert_eq!(
+        result.keys().cloned().collect::<Vec<_>>(),
+        vec![
+            AttributeId::NodeId,
+            AttributeId::BrowseName,
+            AttributeId::Value,
+            AttributeId::UserAccessLevel
+        ]
+    );
+}
+
+#[test]
+fn read_service_level() {
+    let node_id: NodeId = VariableId::Server_ServiceLevel.into();
+    let server_with_value = |value: DataValue| {
+        let mut attributes = BTreeMap::new();
+        attributes.insert(AttributeId::Value, value);
+        MockAttributeServer {
+            node_id: node_id.clone(),
+            attributes,
+        }
+    };
+
+    let server = server_with_value(DataValue::from(Variant::Byte(200)));
+    assert_eq!(server.read_service_level().unwrap(), 200);
+
+    // A bad status is returned as the error
+    let server = server_with_value(DataValue {
+        status: Some(StatusCode::BadUserAccessDenied),
+        ..DataValue::null()
+    });
+    assert_eq!(
+        server.read_service_level().unwrap_err(),
+        StatusCode::BadUserAccessDenied
+    );
+
+    // Service level must be a byte
+    let server = server_with_value(DataValue::from(Variant::UInt32(200)));
+    assert_eq!(
+        server.read_service_level().unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+}
@@ -4,7 +4,7 @@
 
 //! Contains code for turning messages into chunks and chunks into messages.
 
-use std::io::Cursor;
+use std::{cell::Cell, io::Cursor};
 
 use crate::{
     core::{
@@ -16,16 +16,60 @@ use crate::{
     },
     crypto::SecurityPolicy,
     types::{
-        encoding::BinaryEncoder, node_id::NodeId, node_ids::ObjectId, status_code::StatusCode,
+        encoding::{size_and_encode, BinaryEncoder},
+        node_id::NodeId,
+        node_ids::ObjectId,
+        status_code::StatusCode,
     },
 };
 
+/// A message together with its encoded byte length, which is computed at most once.
+///
+/// `byte_len()` walks the entire message, so for a large message, e.g. a response holding many
+/// thousands of values, it is expensive. Wrapping the message lets the length be shared between
+/// deciding how many chunks are needed, checking size limits and encoding.
+pub struct SizedMessage<'a, T>
+where
+    T: BinaryEncoder<T>,
+{
+    message: &'a T,
+    byte_len: Cell<Option<usize>>,
+}
+
+impl<'a, T> SizedMessage<'a, T>
+where
+    T: BinaryEncoder<T>,
+{
+    pub fn new(message: &'a T) -> SizedMessage<'a, T> {
+        SizedMessage {
+            message,
+            byte_len: Cell::new(None),
+        }
+    }
+
+    /// Returns the message
+    pub fn message(&self) -> &'a T {
+        self.message
+    }
+
+    /// Returns the encoded byte length of the message, computing it on the first call only
+    pub fn byte_len(&self) -> usize {
+        if let Some(byte_len) = self.byte_len.get() {
+            byte_len
+        } else {
+            let byte_len = self.message.byte_len();
+            self.byte_len.set(Some(byte_len));
+            byte_len
+        }
+    }
+}
+
 /// The Chunker is responsible for turning messages to chunks and chunks into messages.
 pub struct Chunker;
 
 impl Chunker {
     /// Tests what kind of chunk type is used for the supported message.
-    fn message_type(message: &SupportedMessage) -> MessageChunkType {
+    pub(crate) fn message_type(message: &SupportedMessage) -> MessageChunkType {
         match message {
             SupportedMessage::OpenSecureChannelRequest(_)
             | SupportedMessage::OpenSecureChannelResponse(_) => MessageChunkType::OpenSecureChannel,
@@ -112,6 +156,120 @@ impl Chunker {
         secure_channel: &SecureChannel,
         supported_message: &SupportedMessage,
     ) -> std::result::Result<Vec<MessageChunk>, StatusCode> {
+        let data = Self::encode_body(max_message_size, secure_channel, supported_message)?;
+        Self::chunk_body(
+            sequence_number,
+            request_id,
+            Chunker::message_type(supported_message),
+            max_chunk_size,
+            secure_channel,
+            &data,
+        )
+    }
+
+    /// Encodes the node id and message into the body that is split into chunks. The message is
+    /// encoded in a single pass by `size_and_encode()` rather than walked once for its
+    /// `byte_len()` and again to encode it, and its size is checked against the maximum message
+    /// size, or 0 for no limit, once it is known.
+    pub fn encode_body(
+        max_message_size: usize,
+        secure_channel: &SecureChannel,
+        supported_message: &SupportedMessage,
+    ) -> std::result::Result<Vec<u8>, StatusCode> {
+        if secure_channel.security_policy() == SecurityPolicy::Unknown {
+            panic!("Security policy cannot be unknown");
+        }
+
+        let message = size_and_encode(supported_message)?;
+        let message_size = message.len();
+        if max_message_size > 0 && message_size > max_message_size {
+            error!(
+                "Max message size is {} and message {} exceeds that",
+                max_message_size, message_size
+            );
+            Err(Self::message_too_large(secure_channel))
+        } else {
+            let node_id = supported_message.node_id();
+            let mut data = Vec::with_capacity(node_id.byte_len() + message_size);
+            trace!("Encoding node id {:?}", node_id);
+            let _ = node_id.encode(&mut data)?;
+            data.extend_from_slice(&message);
+            Ok(data)
+        }
+    }
+
+    /// Client stack should report a BadRequestTooLarge, server BadResponseTooLarge
+    fn message_too_large(secure_channel: &SecureChannel) -> StatusCode {
+        if secure_channel.is_client_role() {
+            StatusCode::BadRequestTooLarge
+        } else {
+            StatusCode::BadResponseTooLarge
+        }
+    }
+
+    /// Returns the number of chunks that the message will be encoded into for the maximum chunk
+    /// size, or 0 for no limit. The message length is remembered so a subsequent call to
+    /// `encode_sized()` with the same message does not compute it again.
+    pub fn chunk_count(
+        max_chunk_size: usize,
+        secure_channel: &SecureChannel,
+        sized_message: &SizedMessage<SupportedMessage>,
+    ) -> std::result::Result<usize, StatusCode> {
+        if max_chunk_size == 0 {
+            Ok(1)
+        } else {
+            let supported_message = sized_message.message();
+            let body_size = supported_message.node_id().byte_len() + sized_message.byte_len();
+            Self::body_chunk_count(max_chunk_size, secure_channel, supported_message, body_size)
+        }
+    }
+
+    /// Returns the number of chunks that a body of the supplied size from `encode_body()` is
+    /// split into for the maximum chunk size, or 0 for no limit.
+    pub fn body_chunk_count(
+        max_chunk_size: usize,
+        secure_channel: &SecureChannel,
+        supported_message: &SupportedMessage,
+        body_size: usize,
+    ) -> std::result::Result<usize, StatusCode> {
+        if max_chunk_size == 0 || body_size == 0 {
+            Ok(1)
+        } else {
+            let max_body_per_chunk = Self::max_body_per_chunk(
+                Chunker::message_type(supported_message),
+                secure_channel,
+                max_chunk_size,
+            )?;
+            Ok((body_size + max_body_per_chunk - 1) / max_body_per_chunk)
+        }
+    }
+
+    fn max_body_per_chunk(
+        message_type: MessageChunkType,
+        secure_channel: &SecureChannel,
+        max_chunk_size: usize,
+    ) -> std::result::Result<usize, StatusCode> {
+        MessageChunk::body_size_from_message_size(message_type, secure_channel, max_chunk_size)
+            .map_err(|_| {
+                error!(
+                    "body_size_from_message_size error for max_chunk_size = {}",
+                    max_chunk_size
+                );
+                StatusCode::BadTcpInternalError
+            })
+    }
+
+    /// Encodes a message in the same way as `encode()` but using the byte length held by the
+    /// sized message.
+    pub fn encode_sized(
+        sequence_number: u32,
+        request_id: u32,
+        max_message_size: usize,
+        max_chunk_size: usize,
+        secure_channel: &SecureChannel,
+        sized_message: &SizedMessage<SupportedMessage>,
+    ) -> std::result::Result<Vec<MessageChunk>, StatusCode> {
+        let supported_message = sized_message.message();
         let security_policy = secure_channel.security_policy();
         if security_policy == SecurityPolicy::Unknown {
             panic!("Security policy cannot be unknown");
@@ -119,18 +277,13 @@ impl Chunker {
 
         // Client / server stacks should validate the length of a message before sending it and
         // here makes as good a place as any to do that.
-        let mut message_size = supported_message.byte_len();
+        let mut message_size = sized_message.byte_len();
         if max_message_size > 0 && message_size > max_message_size {
             error!(
                 "Max message size is {} and message {} exceeds that",
                 max_message_size, message_size
             );
-            // Client stack should report a BadRequestTooLarge, server BadResponseTooLarge
-            Err(if secure_channel.is_client_role() {
-                StatusCode::BadRequestTooLarge
-            } else {
-                StatusCode::BadResponseTooLarge
-            })
+            Err(Self::message_too_large(secure_channel))
         } else {
             let node_id = supported_message.node_id();
             message_size += node_id.byte_len();
@@ -143,68 +296,106 @@ impl Chunker {
             let _ = supported_message.encode(&mut stream)?;
             let data = stream.into_inner();
 
-            let result = if max_chunk_size > 0 {
-                let max_body_per_chunk = MessageChunk::body_size_from_message_size(
-                    message_type,
-                    secure_channel,
-                    max_chunk_size,
-                )
-                .map_err(|_| {
-                    error!(
-                        "body_size_from_message_size error for max_chunk_size = {}",
-                        max_chunk_size
-                    );
-                    StatusCode::BadTcpInternalError
-                })?;
-
-                // Multiple chunks means breaking the data up into sections. Fortunately
-                // Rust has a nice function to do just that.
-                let data_chunks = data.chunks(max_body_per_chunk);
-                let data_chunks_len = data_chunks.len();
-                let mut chunks = Vec::with_capacity(data_chunks_len);
-                for (i, data_chunk) in data_chunks.enumerate() {
-                    let is_final = if i == data_chunks_len - 1 {
-                        MessageIsFinalType::Final
-                    } else {
-                        MessageIsFinalType::Intermediate
-                    };
-                    let chunk = MessageChunk::new(
-                        sequence_number + i as u32,
-                        request_id,
-                        message_type,
-                        is_final,
-                        secure_channel,
-                        data_chunk,
-                    )?;
-                    chunks.push(chunk);
-                }
-                chunks
-            } else {
-                let chunk = MessageChunk::new(
-                    sequence_number,
+            Self::chunk_body(
+                sequence_number,
+                request_id,
+                message_type,
+                max_chunk_size,
+                secure_channel,
+                &data,
+            )
+        }
+    }
+
+    /// Splits an encoded message body into chunks of the message type. Each chunk, including its
+    /// headers and security, does not exceed the maximum chunk size, or 0 for a single chunk. The
+    /// chunks are numbered consecutively from the sequence number and all but the last are marked
+    /// intermediate.
+    pub fn chunk_body(
+        sequence_number: u32,
+        request_id: u32,
+        message_type: MessageChunkType,
+        max_chunk_size: usize,
+        secure_channel: &SecureChannel,
+        data: &[u8],
+    ) -> std::result::Result<Vec<MessageChunk>, StatusCode> {
+        let data_chunks = if max_chunk_size > 0 && !data.is_empty() {
+            let max_body_per_chunk =
+                Self::max_body_per_chunk(message_type, secure_channel, max_chunk_size)?;
+            data.chunks(max_body_per_chunk).collect::<Vec<_>>()
+        } else {
+            vec![data]
+        };
+        let last = data_chunks.len() - 1;
+        data_chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, data_chunk)| {
+                let is_final = if i == last {
+                    MessageIsFinalType::Final
+                } else {
+                    MessageIsFinalType::Intermediate
+                };
+                MessageChunk::new(
+                    sequence_number + i as u32,
                     request_id,
                     message_type,
-                    MessageIsFinalType::Final,
+                    is_final,
                     secure_channel,
-                    &data,
-                )?;
-                vec![chunk]
-            };
-            Ok(result)
+                    data_chunk,
+                )
+            })
+            .collect()
+    }
+
+    /// Joins the bodies of the chunks of a message back together, the inverse of `chunk_body()`.
+    /// The chunks must belong to the same secure channel and request, have consecutive sequence
+    /// numbers, and only the last may be final.
+    ///
+    /// # Errors
+    ///
+    /// * `BadCommunicationError` if one of the chunks is an abort chunk, i.e. the sender gave up on the message
+    /// * `BadEncodingLimitsExceeded` if there are more chunks than the maximum chunk count, or 0 for no limit
+    /// * `BadDecodingError` if there are no chunks or the final chunk is missing or out of place
+    /// * The errors of `validate_chunks()` if the chunks are out of sequence or don't belong together
+    ///
+    pub fn assemble(
+        chunks: &[MessageChunk],
+        secure_channel: &SecureChannel,
+        max_chunk_count: usize,
+    ) -> std::result::Result<Vec<u8>, StatusCode> {
+        if max_chunk_count > 0 && chunks.len() > max_chunk_count {
+            error!(
+                "Message has {} chunks which exceeds the max chunk count {}",
+                chunks.len(),
+                max_chunk_count
+            );
+            return Err(StatusCode::BadEncodingLimitsExceeded);
         }
+        let data = Self::chunk_bodies(chunks, secure_channel)?;
+        let _ = Self::validate_chunks(0, secure_channel, chunks)?;
+        Ok(data)
     }
 
-    /// Decodes a series of chunks to create a message. The message must be of a `SupportedMessage`
-    /// type otherwise an error will occur.
-    pub fn decode(
+    /// Returns the contiguous bodies of the chunks after checking the last chunk, and only the
+    /// last, is final. The assumption is the data is decrypted / verified by now.
+    fn chunk_bodies(
         chunks: &[MessageChunk],
         secure_channel: &SecureChannel,
-        expected_node_id: Option<NodeId>,
-    ) -> std::result::Result<SupportedMessage, StatusCode> {
+    ) -> std::result::Result<Vec<u8>, StatusCode> {
+        if chunks.is_empty() {
+            error!("There are no chunks to make a message from");
+            return Err(StatusCode::BadDecodingError);
+        }
+
         // Calculate the size of data held in all chunks
         let mut data_size: usize = 0;
         for (i, chunk) in chunks.iter().enumerate() {
             let chunk_info = chunk.chunk_info(secure_channel)?;
+            if chunk_info.message_header.is_final == MessageIsFinalType::FinalError {
+                info!("Message was aborted by the sender");
+                return Err(StatusCode::BadCommunicationError);
+            }
             // The last most chunk is expected to be final, the rest intermediate
             let expected_is_final = if i == chunks.len() - 1 {
                 MessageIsFinalType::Final
@@ -212,15 +403,19 @@ impl Chunker {
                 MessageIsFinalType::Intermediate
             };
             if chunk_info.message_header.is_final != expected_is_final {
+                error!(
+                    "Chunk {} of {} is {:?} but {:?} was expected",
+                    i,
+                    chunks.len(),
+                    chunk_info.message_header.is_final,
+                    expected_is_final
+                );
                 return Err(StatusCode::BadDecodingError);
             }
-            // Calculate how much space data is in the chunk
-            let body_start = chunk_info.body_offset;
-            let body_end = body_start + chunk_info.body_length;
-            data_size += chunk.data[body_start..body_end].len();
+            data_size += chunk_info.body_length;
         }
 
-        // Read the data into a contiguous buffer. The assumption is the data is decrypted / verified by now
+        // Read the data into a contiguous buffer
         // TODO this buffer should be externalized so it is not allocated each time
         let mut data = Vec::with_capacity(data_size);
         for chunk in chunks.iter() {
@@ -231,6 +426,37 @@ impl Chunker {
             let body_data = &chunk.data[body_start..body_end];
             data.extend_from_slice(body_data);
         }
+        Ok(data)
+    }
+
+    /// Decodes a series of chunks to create a message. The message must be of a `SupportedMessage`
+    /// type otherwise an error will occur.
+    pub fn decode(
+        chunks: &[MessageChunk],
+        secure_channel: &SecureChannel,
+        expected_node_id: Option<NodeId>,
+    ) -> std::result::Result<SupportedMessage, StatusCode> {
+        Self::decode_chunks(chunks, secure_channel, expected_node_id, false)
+    }
+
+    /// Decodes a series of chunks to create a message like `decode()`, but additionally requires
+    /// the message to consume every byte of the chunk bodies. Trailing bytes indicate either an
+    /// encoder bug or padding added to the message and are rejected with `BadDecodingError`.
+    pub fn decode_strict(
+        chunks: &[MessageChunk],
+        secure_channel: &SecureChannel,
+        expected_node_id: Option<NodeId>,
+    ) -> std::result::Result<SupportedMessage, StatusCode> {
+        Self::decode_chunks(chunks, secure_channel, expected_node_id, true)
+    }
+
+    fn decode_chunks(
+        chunks: &[MessageChunk],
+        secure_channel: &SecureChannel,
+        expected_node_id: Option<NodeId>,
+        reject_trailing_bytes: bool,
+    ) -> std::result::Result<SupportedMessage, StatusCode> {
+        let data = Self::chunk_bodies(chunks, secure_channel)?;
 
         // Make a stream around the data
         let mut data = Cursor::new(data);
@@ -251,6 +477,15 @@ impl Chunker {
                 if let SupportedMessage::Invalid(_) = decoded_message {
                     debug!("Message {:?} is unsupported", object_id);
                     Err(StatusCode::BadServiceUnsupported)
+                } else if reject_trailing_bytes && data.position() as usize != data.get_ref().len()
+                {
+                    error!(
+                        "Message {:?} was decoded from {} bytes but the chunks hold {} bytes",
+                        object_id,
+                        data.position(),
+                        data.get_ref().len()
+                    );
+                    Err(StatusCode::BadDecodingError)
                 } else {
                     // debug!("Returning decoded msg {:?}", decoded_message);
                     Ok(decoded_message)
@@ -7,6 +7,7 @@
 
 use std::io::{Cursor, Read, Write};
 
+use crate::crypto::SecurityPolicy;
 use crate::types::{status_code::StatusCode, *};
 
 use super::{
@@ -82,7 +83,7 @@ impl BinaryEncoder<MessageChunkHeader> for MessageChunkHeader {
         size += write_u8(stream, is_final)?;
         size += write_u32(stream, self.message_size)?;
         size += write_u32(stream, self.secure_channel_id)?;
-        assert_eq!(size, self.byte_len());
+        debug_assert_eq!(size, self.byte_len());
         Ok(size)
     }
 
@@ -192,7 +193,7 @@ impl MessageChunk {
         data: &[u8],
     ) -> Result<MessageChunk, StatusCode> {
         // security header depends on message type
-        let security_header = secure_channel.make_security_header(message_type);
+        let security_header = secure_channel.make_security_header(message_type)?;
         let sequence_header = SequenceHeader {
             sequence_number,
             request_id,
@@ -246,7 +247,12 @@ impl MessageChunk {
             );
             Err(())
         } else {
-            let security_header = secure_channel.make_security_header(message_type);
+            let security_header =
+                secure_channel
+                    .make_security_header(message_type)
+                    .map_err(|err| {
+                        error!("Cannot make a security header, error {}", err);
+                    })?;
 
             let mut data_size = MESSAGE_CHUNK_HEADER_SIZE;
             data_size += security_header.byte_len();
@@ -270,6 +276,74 @@ impl MessageChunk {
         }
     }
 
+    /// Calculates the maximum number of body bytes that fit inside a chunk of `buffer_size` bytes,
+    /// e.g. the negotiated send buffer size, for the supplied security policy, mode and message
+    /// type. When the chunk is encrypted, everything after the headers, i.e. the sequence header,
+    /// body, padding and signature, is encrypted in whole cipher blocks, so:
+    ///
+    /// ```text
+    /// MaxBodySize = BlockSize * Floor((BufferSize - HeaderSize) / BlockSize)
+    ///               - SequenceHeaderSize - SignatureSize - 1
+    /// ```
+    ///
+    /// `HeaderSize` is the message header plus the security header and the 1 byte is reserved
+    /// for the padding size. Symmetric encryption is AES so the plain text and cipher text blocks
+    /// are the same size.
+    ///
+    /// When the message is only signed there is no padding and the body is what remains after
+    /// the headers and signature. When there is no security the signature is omitted as well.
+    ///
+    /// The size for an `OpenSecureChannel` chunk with security depends on the certificates and
+    /// asymmetric keys of the channel so it cannot be derived from these arguments and is an error.
+    pub fn max_body_size(
+        buffer_size: usize,
+        security_policy: SecurityPolicy,
+        security_mode: MessageSecurityMode,
+        message_type: MessageChunkType,
+    ) -> Result<usize, StatusCode> {
+        let secure =
+            security_policy != SecurityPolicy::None && security_mode != MessageSecurityMode::None;
+        let security_header_size = if message_type.is_open_secure_channel() {
+            if secure {
+                error!("Cannot calculate the max body size of a secure OpenSecureChannel chunk");
+                return Err(StatusCode::BadInvalidArgument);
+            }
+            // Policy uri, null sender certificate, null receiver thumbprint
+            AsymmetricSecurityHeader::none().byte_len()
+        } else {
+            // Token id
+            4
+        };
+        let sequence_header_size = (SequenceHeader {
+            sequence_number: 0,
+            request_id: 0,
+        })
+        .byte_len();
+        let header_size = MESSAGE_CHUNK_HEADER_SIZE + security_header_size;
+
+        let max_body_size = if !secure {
+            buffer_size.checked_sub(header_size + sequence_header_size)
+        } else {
+            let signature_size = security_policy.symmetric_signature_size();
+            if security_mode == MessageSecurityMode::SignAndEncrypt {
+                let block_size = security_policy.plain_block_size();
+                buffer_size
+                    .checked_sub(header_size)
+                    .map(|size| block_size * (size / block_size))
+                    .and_then(|size| size.checked_sub(sequence_header_size + signature_size + 1))
+            } else {
+                buffer_size.checked_sub(header_size + sequence_header_size + signature_size)
+            }
+        };
+        max_body_size.ok_or_else(|| {
+            error!(
+                "Buffer size {} is too small to hold a chunk body",
+                buffer_size
+            );
+            StatusCode::BadInvalidArgument
+        })
+    }
+
     pub fn message_header(
         &self,
         decoding_options: &DecodingOptions,
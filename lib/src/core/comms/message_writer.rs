@@ -2,11 +2,17 @@
 // SPDX-License-Identifier: MPL-2.0
 // Copyright (C) 2017-2022 Adam Lock
 
-use std::io::{Cursor, Write};
+use std::{
+    io::{Cursor, Write},
+    sync::Arc,
+};
 
 use crate::types::{status_code::StatusCode, BinaryEncoder, EncodingResult};
 
-use super::{chunker::Chunker, secure_channel::SecureChannel, tcp_types::AcknowledgeMessage};
+use super::{
+    chunker::Chunker, secure_channel::SecureChannel, tcp_types::AcknowledgeMessage,
+    transport_metrics::TransportCounters,
+};
 
 use crate::core::supported_message::SupportedMessage;
 
@@ -25,7 +31,11 @@ pub struct MessageWriter {
     /// Maximum size of a message, total. Use 0 for no limit
     max_message_size: usize,
     /// Maximum size of a chunk. Use 0 for no limit
+    max_chunk_size: usize,
+    /// Maximum number of chunks in a message. Use 0 for no limit
     max_chunk_count: usize,
+    /// Counters of the messages written
+    transport_counters: Arc<TransportCounters>,
 }
 
 impl MessageWriter {
@@ -39,12 +49,40 @@ impl MessageWriter {
             last_request_id: DEFAULT_REQUEST_ID,
             last_sent_sequence_number: DEFAULT_SENT_SEQUENCE_NUMBER,
             max_message_size,
+            max_chunk_size: 0,
             max_chunk_count,
+            transport_counters: Arc::new(TransportCounters::new()),
         }
     }
 
+    /// Returns the counters of the messages written
+    pub fn transport_counters(&self) -> Arc<TransportCounters> {
+        self.transport_counters.clone()
+    }
+
+    /// Sets the counters that written messages are recorded in, e.g. to share them with the
+    /// reading side of the transport.
+    pub fn set_transport_counters(&mut self, transport_counters: Arc<TransportCounters>) {
+        self.transport_counters = transport_counters;
+    }
+
+    /// Sets the limits that written messages must fit, e.g. those negotiated with the peer
+    /// during the HELLO / ACK exchange. Use 0 for no limit.
+    pub fn set_limits(
+        &mut self,
+        max_message_size: usize,
+        max_chunk_size: usize,
+        max_chunk_count: usize,
+    ) {
+        self.max_message_size = max_message_size;
+        self.max_chunk_size = max_chunk_size;
+        self.max_chunk_count = max_chunk_count;
+    }
+
     pub fn write_ack(&mut self, ack: &AcknowledgeMessage) -> EncodingResult<usize> {
-        ack.encode(&mut self.buffer)
+        let size = ack.encode(&mut self.buffer)?;
+        self.transport_counters.record_sent(size, 1);
+        Ok(size)
     }
 
     /// Encodes the message into a series of chunks, encrypts those chunks and writes the
@@ -56,26 +94,31 @@ impl MessageWriter {
         secure_channel: &SecureChannel,
     ) -> Result<u32, StatusCode> {
         trace!("Writing request to buffer");
-        // Turn message to chunk(s)
-        let chunks = Chunker::encode(
-            self.last_sent_sequence_number + 1,
-            request_id,
-            self.max_message_size,
-            0,
-            secure_channel,
-            &message,
-        )?;
-
-        if self.max_chunk_count > 0 && chunks.len() > self.max_chunk_count {
+        // Encode the message in one pass and check the chunk limit before securing any chunks
+        let data = Chunker::encode_body(self.max_message_size, secure_channel, &message)?;
+        let chunk_count =
+            Chunker::body_chunk_count(self.max_chunk_size, secure_channel, &message, data.len())?;
+        if self.max_chunk_count > 0 && chunk_count > self.max_chunk_count {
             error!(
                 "Cannot write message since {} chunks exceeds {} chunk limit",
-                chunks.len(),
-                self.max_chunk_count
+                chunk_count, self.max_chunk_count
             );
             Err(StatusCode::BadCommunicationError)
         } else {
+            // Turn message to chunk(s)
+            let chunks = Chunker::chunk_body(
+                self.last_sent_sequence_number + 1,
+                request_id,
+                Chunker::message_type(&message),
+                self.max_chunk_size,
+                secure_channel,
+                &data,
+            )?;
+
             // Sequence number monotonically increases per chunk
             self.last_sent_sequence_number += chunks.len() as u32;
+            let chunk_count = chunks.len();
+            let mut message_size = 0;
 
             // Send chunks
 
@@ -86,6 +129,7 @@ impl MessageWriter {
             for chunk in chunks {
                 trace!("Sending chunk {:?}", chunk);
                 let size = secure_channel.apply_security(&chunk, &mut data)?;
+                message_size += size;
                 self.buffer.write(&data[..size]).map_err(|error| {
                     error!(
                         "Error while writing bytes to stream, connection broken, check error {:?}",
@@ -94,6 +138,8 @@ impl MessageWriter {
                     StatusCode::BadCommunicationError
                 })?;
             }
+            self.transport_counters
+                .record_sent(message_size, chunk_count);
             trace!("Message written");
             Ok(request_id)
         }
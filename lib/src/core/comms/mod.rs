@@ -13,6 +13,7 @@ pub mod secure_channel;
 pub mod security_header;
 pub mod tcp_codec;
 pub mod tcp_types;
+pub mod transport_metrics;
 pub mod url;
 
 pub mod prelude {
@@ -14,17 +14,23 @@ use crate::crypto::{
     aeskey::AesKey,
     pkey::{KeySize, PrivateKey, PublicKey},
     random,
+    secret::SecretBytes,
     x509::X509,
     CertificateStore, SecurityPolicy,
 };
 use crate::sync::*;
 use crate::types::{
     service_types::ChannelSecurityToken, status_code::StatusCode, write_bytes, write_u8,
-    BinaryEncoder, ByteString, DateTime, DecodingOptions, MessageSecurityMode,
+    BinaryEncoder, ByteString, DateTime, DecodingOptions, EncodingResult, MessageSecurityMode,
 };
 
+use crate::core::supported_message::SupportedMessage;
+
 use super::{
-    message_chunk::{MessageChunk, MessageChunkHeader, MessageChunkType},
+    chunker::Chunker,
+    message_chunk::{
+        MessageChunk, MessageChunkHeader, MessageChunkType, MESSAGE_CHUNK_HEADER_SIZE,
+    },
     security_header::{AsymmetricSecurityHeader, SecurityHeader, SymmetricSecurityHeader},
 };
 
@@ -58,14 +64,15 @@ pub struct SecureChannel {
     private_key: Option<PrivateKey>,
     /// Their certificate
     remote_cert: Option<X509>,
-    /// Their nonce provided by open secure channel
-    remote_nonce: Vec<u8>,
+    /// Their nonce provided by open secure channel. The nonces are the input to the key
+    /// derivation so they are held as secrets too.
+    remote_nonce: SecretBytes,
     /// Our nonce generated while handling open secure channel
-    local_nonce: Vec<u8>,
+    local_nonce: SecretBytes,
     /// Client (i.e. other end's set of keys) Symmetric Signing Key, Encrypt Key, IV
-    remote_keys: Option<(Vec<u8>, AesKey, Vec<u8>)>,
+    remote_keys: Option<(SecretBytes, AesKey, SecretBytes)>,
     /// Server (i.e. our end's set of keys) Symmetric Signing Key, Decrypt Key, IV
-    local_keys: Option<(Vec<u8>, AesKey, Vec<u8>)>,
+    local_keys: Option<(SecretBytes, AesKey, SecretBytes)>,
     /// Decoding options
     decoding_options: DecodingOptions,
 }
@@ -82,8 +89,8 @@ impl SecureChannel {
             token_id: 0,
             token_created_at: DateTime::now(),
             token_lifetime: 0,
-            local_nonce: Vec::new(),
-            remote_nonce: Vec::new(),
+            local_nonce: SecretBytes::default(),
+            remote_nonce: SecretBytes::default(),
             cert: None,
             private_key: None,
             remote_cert: None,
@@ -115,8 +122,8 @@ impl SecureChannel {
             token_id: 0,
             token_created_at: DateTime::now(),
             token_lifetime: 0,
-            local_nonce: Vec::new(),
-            remote_nonce: Vec::new(),
+            local_nonce: SecretBytes::default(),
+            remote_nonce: SecretBytes::default(),
             cert,
             private_key,
             remote_cert: None,
@@ -234,8 +241,11 @@ impl SecureChannel {
     }
 
     /// Makes a security header according to the type of message being sent, symmetric or asymmetric
-    pub fn make_security_header(&self, message_type: MessageChunkType) -> SecurityHeader {
-        match message_type {
+    pub fn make_security_header(
+        &self,
+        message_type: MessageChunkType,
+    ) -> Result<SecurityHeader, StatusCode> {
+        let security_header = match message_type {
             MessageChunkType::OpenSecureChannel => {
                 let asymmetric_security_header = if self.security_policy == SecurityPolicy::None {
                     trace!("AsymmetricSecurityHeader security policy none");
@@ -251,7 +261,7 @@ impl SecureChannel {
                         self.security_policy,
                         self.cert.as_ref().unwrap(),
                         receiver_certificate_thumbprint,
-                    )
+                    )?
                 };
                 debug!(
                     "AsymmetricSecurityHeader = {:?}",
@@ -262,14 +272,16 @@ impl SecureChannel {
             _ => SecurityHeader::Symmetric(SymmetricSecurityHeader {
                 token_id: self.token_id,
             }),
-        }
+        };
+        Ok(security_header)
     }
 
     /// Creates a nonce for the connection. The nonce should be the same size as the symmetric key
-    pub fn create_random_nonce(&mut self) {
-        self.local_nonce
-            .resize(self.security_policy.secure_channel_nonce_length(), 0);
-        random::bytes(&mut self.local_nonce);
+    pub fn create_random_nonce(&mut self) -> Result<(), StatusCode> {
+        self.local_nonce = random::nonce(self.security_policy.secure_channel_nonce_length())
+            .map(SecretBytes::from)
+            .map_err(|_| StatusCode::BadInternalError)?;
+        Ok(())
     }
 
     /// Sets the remote certificate
@@ -286,11 +298,11 @@ impl SecureChannel {
     }
 
     /// Obtains the remote certificate as a byte string
-    pub fn remote_cert_as_byte_string(&self) -> ByteString {
+    pub fn remote_cert_as_byte_string(&self) -> Result<ByteString, StatusCode> {
         if let Some(ref remote_cert) = self.remote_cert {
-            remote_cert.as_byte_string()
+            Ok(remote_cert.as_byte_string()?)
         } else {
-            ByteString::null()
+            Ok(ByteString::null())
         }
     }
 
@@ -311,7 +323,7 @@ impl SecureChannel {
                 );
                 Err(StatusCode::BadNonceInvalid)
             } else {
-                self.remote_nonce = remote_nonce.to_vec();
+                self.remote_nonce = SecretBytes::from(remote_nonce.as_slice());
                 Ok(())
             }
         } else if self.security_policy != SecurityPolicy::None {
@@ -598,6 +610,110 @@ impl SecureChannel {
         Ok(size)
     }
 
+    /// Returns the size of a message chunk once security is applied to it, i.e. the size of the
+    /// destination buffer that `apply_security()` needs. Padding and a signature are added to the
+    /// chunk, and asymmetric encryption makes the cipher text larger than the plain text.
+    fn secured_chunk_size(&self, message_chunk: &MessageChunk) -> Result<usize, StatusCode> {
+        if self.security_policy == SecurityPolicy::None
+            || (self.security_mode != MessageSecurityMode::Sign
+                && self.security_mode != MessageSecurityMode::SignAndEncrypt)
+        {
+            return Ok(message_chunk.data.len());
+        }
+        let chunk_info = message_chunk.chunk_info(self)?;
+        let security_header = &chunk_info.security_header;
+        let signature_size = self.signature_size(security_header);
+        let (padding_size, _) =
+            self.padding_size(security_header, chunk_info.body_length, signature_size);
+        let plain_text_size = message_chunk.data.len() + padding_size + signature_size;
+        if message_chunk.is_open_secure_channel(&self.decoding_options) {
+            let encryption_key = self
+                .remote_cert
+                .as_ref()
+                .ok_or(StatusCode::BadSecurityChecksFailed)?
+                .public_key()?;
+            let padding = self.security_policy.asymmetric_encryption_padding();
+            let header_size = chunk_info.sequence_header_offset;
+            Ok(header_size
+                + encryption_key.calculate_cipher_text_size(plain_text_size - header_size, padding))
+        } else {
+            // Symmetric encryption does not change the size
+            Ok(plain_text_size)
+        }
+    }
+
+    /// Encodes a message into the bytes that are sent over the wire. The message is broken into
+    /// chunks, security is applied to each chunk and the secured chunks are written one after
+    /// the other. Chunks are numbered from the supplied sequence number.
+    ///
+    /// max_message_size refers to the maximum byte length of a message or 0 for no limit
+    /// max_chunk_size refers to the maximum byte length of a chunk or 0 for no limit
+    ///
+    pub fn encode_message(
+        &self,
+        message: &SupportedMessage,
+        sequence_number: u32,
+        request_id: u32,
+        max_message_size: usize,
+        max_chunk_size: usize,
+    ) -> EncodingResult<Vec<u8>> {
+        let chunks = Chunker::encode(
+            sequence_number,
+            request_id,
+            max_message_size,
+            max_chunk_size,
+            self,
+            message,
+        )?;
+        let mut result = Vec::with_capacity(chunks.iter().map(|c| c.data.len()).sum());
+        for chunk in chunks {
+            let mut data = vec![0u8; self.secured_chunk_size(&chunk)?];
+            let size = self.apply_security(&chunk, &mut data)?;
+            result.extend_from_slice(&data[..size]);
+        }
+        Ok(result)
+    }
+
+    /// Decodes a message from the bytes received over the wire, i.e. the inverse of
+    /// `encode_message()`. The data must hold every chunk of exactly one message. Each chunk is
+    /// verified and decrypted before the chunks are turned back into the message.
+    ///
+    /// When `strict` is set, the message must consume the chunk bodies exactly and any bytes left
+    /// over after decoding are a `BadDecodingError`.
+    pub fn decode_message(
+        &mut self,
+        data: &[u8],
+        strict: bool,
+    ) -> EncodingResult<SupportedMessage> {
+        let mut chunks = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let message_header = {
+                let mut stream = Cursor::new(&data[offset..]);
+                MessageChunkHeader::decode(&mut stream, &self.decoding_options)?
+            };
+            let message_size = message_header.message_size as usize;
+            if message_size < MESSAGE_CHUNK_HEADER_SIZE || offset + message_size > data.len() {
+                error!(
+                    "Chunk at offset {} has a message size {} that does not fit the data",
+                    offset, message_size
+                );
+                return Err(StatusCode::BadDecodingError);
+            }
+            let chunk = self.verify_and_remove_security(&data[offset..offset + message_size])?;
+            chunks.push(chunk);
+            offset += message_size;
+        }
+        if chunks.is_empty() {
+            error!("There are no chunks to decode a message from");
+            Err(StatusCode::BadDecodingError)
+        } else if strict {
+            Chunker::decode_strict(&chunks, self, None)
+        } else {
+            Chunker::decode(&chunks, self, None)
+        }
+    }
+
     /// Decrypts and verifies the body data if the mode / policy requires it
     pub fn verify_and_remove_security(&mut self, src: &[u8]) -> Result<MessageChunk, StatusCode> {
         self.verify_and_remove_security_forensic(src, None)
@@ -688,7 +804,7 @@ impl SecureChannel {
             // This code doesn't *care* if the cert is trusted, merely that it was used to sign the message
             if security_header.sender_certificate.is_null() {
                 error!("Sender certificate is NULL!!!");
-                // TODO return
+                return Err(StatusCode::BadSecurityChecksFailed);
             }
 
             let sender_certificate_len = security_header
@@ -929,7 +1045,7 @@ impl SecureChannel {
 
         let our_cert = self.cert.as_ref().unwrap();
         let our_thumbprint = our_cert.thumbprint();
-        if our_thumbprint.value() != receiver_thumbprint.as_ref() {
+        if !our_thumbprint.matches_byte_string(&receiver_thumbprint) {
             error!("Supplied thumbprint does not match application certificate's thumbprint");
             Err(StatusCode::BadNoValidCertificates)
         } else {
@@ -943,12 +1059,15 @@ impl SecureChannel {
             trace!("Decrypting message range {:?}", encrypted_range);
             let mut decrypted_tmp = vec![0u8; encrypted_size];
 
+            // The signature is inside the encrypted block so decryption with our private key must
+            // come first, only then can the signature be verified with the sender's public key.
             let private_key = self.private_key.as_ref().unwrap();
-            let decrypted_size = security_policy.asymmetric_decrypt(
-                private_key,
-                &src[encrypted_range.clone()],
-                &mut decrypted_tmp,
-            )?;
+            let decrypted_size = security_policy
+                .asymmetric_decrypt(private_key, &src[encrypted_range.clone()], &mut decrypted_tmp)
+                .map_err(|err| {
+                    error!("Asymmetric chunk could not be decrypted with our private key, it may have been tampered with or encrypted for another certificate");
+                    err
+                })?;
             trace!(
                 "Decrypted bytes = {} compared to encrypted range {}",
                 decrypted_size,
@@ -962,6 +1081,14 @@ impl SecureChannel {
                 verification_key_signature_size
             );
 
+            if decrypted_size < verification_key_signature_size {
+                error!(
+                    "Decrypted asymmetric chunk of {} bytes is too small to hold a signature of {} bytes",
+                    decrypted_size, verification_key_signature_size
+                );
+                return Err(StatusCode::BadSecurityChecksFailed);
+            }
+
             // Copy the bytes to dst
             dst[encrypted_range.start..(encrypted_range.start + decrypted_size)]
                 .copy_from_slice(&decrypted_tmp[0..decrypted_size]);
@@ -993,12 +1120,17 @@ impl SecureChannel {
             } else {
                 verification_key.size()
             };
-            security_policy.asymmetric_verify_signature(
-                verification_key,
-                &dst[signed_range_dst],
-                &dst[signature_range_dst.clone()],
-                their_key,
-            )?;
+            security_policy
+                .asymmetric_verify_signature(
+                    verification_key,
+                    &dst[signed_range_dst],
+                    &dst[signature_range_dst.clone()],
+                    their_key,
+                )
+                .map_err(|_| {
+                    error!("Asymmetric chunk was decrypted but its signature could not be verified with the sender's certificate");
+                    StatusCode::BadSecurityChecksFailed
+                })?;
 
             // Verify that the padding is correct
             let padding_range = self.verify_padding(dst, key_size, signature_range_dst.start)?;
@@ -1013,8 +1145,7 @@ impl SecureChannel {
     }
 
     pub fn set_local_nonce(&mut self, local_nonce: &[u8]) {
-        self.local_nonce.clear();
-        self.local_nonce.extend_from_slice(local_nonce);
+        self.local_nonce = SecretBytes::from(local_nonce);
     }
 
     pub fn local_nonce_as_byte_string(&self) -> ByteString {
@@ -1026,8 +1157,7 @@ impl SecureChannel {
     }
 
     pub fn set_remote_nonce(&mut self, remote_nonce: &[u8]) {
-        self.remote_nonce.clear();
-        self.remote_nonce.extend_from_slice(remote_nonce);
+        self.remote_nonce = SecretBytes::from(remote_nonce);
     }
 
     pub fn remote_nonce(&self) -> &[u8] {
@@ -1042,30 +1172,30 @@ impl SecureChannel {
         }
     }
 
-    fn local_keys(&self) -> &(Vec<u8>, AesKey, Vec<u8>) {
+    fn local_keys(&self) -> &(SecretBytes, AesKey, SecretBytes) {
         self.local_keys.as_ref().unwrap()
     }
 
-    fn remote_keys(&self) -> &(Vec<u8>, AesKey, Vec<u8>) {
+    fn remote_keys(&self) -> &(SecretBytes, AesKey, SecretBytes) {
         self.remote_keys.as_ref().unwrap()
     }
 
     fn encryption_keys(&self) -> (&AesKey, &[u8]) {
         let keys = self.local_keys();
-        (&keys.1, &keys.2)
+        (&keys.1, &keys.2[..])
     }
 
     fn signing_key(&self) -> &[u8] {
-        &(self.local_keys()).0
+        &self.local_keys().0[..]
     }
 
     fn decryption_keys(&self) -> (&AesKey, &[u8]) {
         let keys = self.remote_keys();
-        (&keys.1, &keys.2)
+        (&keys.1, &keys.2[..])
     }
 
     fn verification_key(&self) -> &[u8] {
-        &(self.remote_keys()).0
+        &self.remote_keys().0[..]
     }
 
     /// Encode data using security. Destination buffer is expected to be same size as src and expected
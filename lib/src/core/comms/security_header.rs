@@ -77,7 +77,7 @@ impl BinaryEncoder<AsymmetricSecurityHeader> for AsymmetricSecurityHeader {
         size += self.security_policy_uri.encode(stream)?;
         size += self.sender_certificate.encode(stream)?;
         size += self.receiver_certificate_thumbprint.encode(stream)?;
-        assert_eq!(size, self.byte_len());
+        debug_assert_eq!(size, self.byte_len());
         Ok(size)
     }
 
@@ -137,12 +137,12 @@ impl AsymmetricSecurityHeader {
         security_policy: SecurityPolicy,
         sender_certificate: &X509,
         receiver_certificate_thumbprint: ByteString,
-    ) -> AsymmetricSecurityHeader {
-        AsymmetricSecurityHeader {
+    ) -> Result<AsymmetricSecurityHeader, StatusCode> {
+        Ok(AsymmetricSecurityHeader {
             security_policy_uri: UAString::from(security_policy.to_uri()),
-            sender_certificate: sender_certificate.as_byte_string(),
+            sender_certificate: sender_certificate.as_byte_string()?,
             receiver_certificate_thumbprint,
-        }
+        })
     }
 }
 
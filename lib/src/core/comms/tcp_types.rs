@@ -30,6 +30,10 @@ pub const MIN_CHUNK_SIZE: usize = 8196;
 /// Size in bytes of an OPC UA message header
 pub const MESSAGE_HEADER_LEN: usize = 8;
 
+/// The version of the OPC UA TCP protocol implemented here, as exchanged in the HEL / ACK
+/// handshake and the OpenSecureChannel request / response
+pub const PROTOCOL_VERSION: u32 = 0;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum MessageType {
     Invalid,
@@ -233,7 +237,7 @@ impl HelloMessage {
     ) -> HelloMessage {
         let mut msg = HelloMessage {
             message_header: MessageHeader::new(MessageType::Hello),
-            protocol_version: 0,
+            protocol_version: PROTOCOL_VERSION,
             send_buffer_size: send_buffer_size as u32,
             receive_buffer_size: receive_buffer_size as u32,
             max_message_size: max_message_size as u32,
@@ -277,6 +281,12 @@ impl HelloMessage {
         self.receive_buffer_size >= MIN_CHUNK_SIZE as u32
             && self.send_buffer_size >= MIN_CHUNK_SIZE as u32
     }
+
+    /// Tests if the protocol version the client asks for is one the server can speak. A client
+    /// asking for a later version than `PROTOCOL_VERSION` is rejected.
+    pub fn is_protocol_version_supported(&self) -> bool {
+        self.protocol_version <= PROTOCOL_VERSION
+    }
 }
 
 /// Implementation of the ACK message in OPC UA
@@ -324,6 +334,14 @@ impl BinaryEncoder<AcknowledgeMessage> for AcknowledgeMessage {
     }
 }
 
+impl AcknowledgeMessage {
+    /// Tests if the protocol version the server answers with is one the client can speak. A
+    /// server answering with a later version than `PROTOCOL_VERSION` is rejected.
+    pub fn is_protocol_version_supported(&self) -> bool {
+        self.protocol_version <= PROTOCOL_VERSION
+    }
+}
+
 /// Implementation of the ERR message in OPC UA
 #[derive(Debug, Clone, PartialEq)]
 pub struct ErrorMessage {
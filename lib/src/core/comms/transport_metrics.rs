@@ -0,0 +1,95 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+//! Lightweight counters of the messages and chunks passing through a transport, for capacity
+//! planning and diagnostics. Both the server and client TCP transports keep these counters.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Upper bounds in bytes of the message size histogram buckets. A message falls into the first
+/// bucket whose bound is greater than or equal to its size, with the last bucket catching
+/// everything larger.
+pub const MESSAGE_SIZE_BUCKETS: [usize; 6] = [1024, 8192, 65536, 262144, 1048576, usize::MAX];
+
+/// A snapshot of the counters of a transport at a point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TransportMetrics {
+    /// Number of messages sent
+    pub messages_sent: u64,
+    /// Number of bytes sent
+    pub bytes_sent: u64,
+    /// Number of chunks sent
+    pub chunks_sent: u64,
+    /// Number of messages received
+    pub messages_received: u64,
+    /// Number of bytes received
+    pub bytes_received: u64,
+    /// Number of chunks received
+    pub chunks_received: u64,
+    /// Number of messages sent or received in each of the `MESSAGE_SIZE_BUCKETS`
+    pub message_sizes: [u64; MESSAGE_SIZE_BUCKETS.len()],
+}
+
+/// The counters of a transport. These are atomic so they can be shared between the reading and
+/// writing halves of a connection and sampled at any time without locking.
+#[derive(Debug, Default)]
+pub struct TransportCounters {
+    messages_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    chunks_sent: AtomicU64,
+    messages_received: AtomicU64,
+    bytes_received: AtomicU64,
+    chunks_received: AtomicU64,
+    message_sizes: [AtomicU64; MESSAGE_SIZE_BUCKETS.len()],
+}
+
+impl TransportCounters {
+    pub fn new() -> TransportCounters {
+        TransportCounters::default()
+    }
+
+    /// Records a message of the size in bytes that was sent in the number of chunks
+    pub fn record_sent(&self, bytes: usize, chunks: usize) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.chunks_sent.fetch_add(chunks as u64, Ordering::Relaxed);
+        self.record_size(bytes);
+    }
+
+    /// Records a message of the size in bytes that was received in the number of chunks
+    pub fn record_received(&self, bytes: usize, chunks: usize) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        self.chunks_received
+            .fetch_add(chunks as u64, Ordering::Relaxed);
+        self.record_size(bytes);
+    }
+
+    fn record_size(&self, bytes: usize) {
+        let bucket = MESSAGE_SIZE_BUCKETS
+            .iter()
+            .position(|bound| bytes <= *bound)
+            .unwrap_or(MESSAGE_SIZE_BUCKETS.len() - 1);
+        self.message_sizes[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Takes a snapshot of the counters
+    pub fn snapshot(&self) -> TransportMetrics {
+        let mut message_sizes = [0u64; MESSAGE_SIZE_BUCKETS.len()];
+        message_sizes
+            .iter_mut()
+            .zip(self.message_sizes.iter())
+            .for_each(|(size, counter)| *size = counter.load(Ordering::Relaxed));
+        TransportMetrics {
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            chunks_sent: self.chunks_sent.load(Ordering::Relaxed),
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            chunks_received: self.chunks_received.load(Ordering::Relaxed),
+            message_sizes,
+        }
+    }
+}
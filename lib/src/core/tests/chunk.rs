@@ -3,10 +3,13 @@ extern crate rustc_serialize as serialize;
 use std::io::{Cursor, Write};
 
 use crate::crypto::{x509::X509, SecurityPolicy};
-use crate::types::DecodingOptions;
+use crate::types::{DecodingOptions, MessageSecurityMode};
 
 use crate::core::{
-    comms::{chunker::*, message_chunk::*, secure_channel::*, tcp_types::MIN_CHUNK_SIZE},
+    comms::{
+        chunker::*, message_chunk::*, message_writer::MessageWriter, secure_channel::*,
+        tcp_types::MIN_CHUNK_SIZE,
+    },
     supported_message::SupportedMessage,
     tests::*,
 };
@@ -166,6 +169,225 @@ fn chunk_multi_chunk_intermediate_final() {
     }
 }
 
+/// A body that fits in a chunk is assembled from that one final chunk
+#[test]
+fn assemble_single_chunk() {
+    let _ = Test::setup();
+
+    let secure_channel = SecureChannel::new_no_certificate_store();
+    let body = (0..100u8).collect::<Vec<u8>>();
+    for max_chunk_size in &[0, MIN_CHUNK_SIZE] {
+        let chunks = Chunker::chunk_body(
+            1,
+            10,
+            MessageChunkType::Message,
+            *max_chunk_size,
+            &secure_channel,
+            &body,
+        )
+        .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0]
+                .message_header(&DecodingOptions::test())
+                .unwrap()
+                .is_final,
+            MessageIsFinalType::Final
+        );
+        assert_eq!(
+            Chunker::assemble(&chunks, &secure_channel, 1).unwrap(),
+            body
+        );
+    }
+}
+
+/// A body split across three chunks is assembled back together, but not if the chunks are
+/// incomplete, out of order, too many or aborted
+#[test]
+fn assemble_multi_chunk() {
+    let _ = Test::setup();
+
+    let secure_channel = SecureChannel::new_no_certificate_store();
+    let body = (0..(MIN_CHUNK_SIZE * 5 / 2))
+        .map(|i| i as u8)
+        .collect::<Vec<u8>>();
+    let sequence_number = 1000;
+    let request_id = 100;
+    let mut chunks = Chunker::chunk_body(
+        sequence_number,
+        request_id,
+        MessageChunkType::Message,
+        MIN_CHUNK_SIZE,
+        &secure_channel,
+        &body,
+    )
+    .unwrap();
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks.iter().all(|c| c.byte_len() <= MIN_CHUNK_SIZE));
+    assert_eq!(
+        Chunker::assemble(&chunks, &secure_channel, 0).unwrap(),
+        body
+    );
+    assert_eq!(
+        Chunker::assemble(&chunks, &secure_channel, 3).unwrap(),
+        body
+    );
+
+    // Too many chunks
+    assert_eq!(
+        Chunker::assemble(&chunks, &secure_channel, 2).unwrap_err(),
+        StatusCode::BadEncodingLimitsExceeded
+    );
+
+    // The final chunk never arrived
+    assert_eq!(
+        Chunker::assemble(&chunks[..2], &secure_channel, 0).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        Chunker::assemble(&[], &secure_channel, 0).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // Out of order
+    chunks.swap(0, 1);
+    assert_eq!(
+        Chunker::assemble(&chunks, &secure_channel, 0).unwrap_err(),
+        StatusCode::BadSecurityChecksFailed
+    );
+    chunks.swap(0, 1);
+
+    // A chunk from another request
+    let old_request_id = set_chunk_request_id(&mut chunks[1], &secure_channel, request_id + 1);
+    assert_eq!(
+        Chunker::assemble(&chunks, &secure_channel, 0).unwrap_err(),
+        StatusCode::BadSecurityChecksFailed
+    );
+    set_chunk_request_id(&mut chunks[1], &secure_channel, old_request_id);
+
+    // The sender aborted the message
+    chunks[2] = MessageChunk::new(
+        sequence_number + 2,
+        request_id,
+        MessageChunkType::Message,
+        MessageIsFinalType::FinalError,
+        &secure_channel,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        Chunker::assemble(&chunks, &secure_channel, 0).unwrap_err(),
+        StatusCode::BadCommunicationError
+    );
+}
+
+/// A message that counts how many times its byte length is computed
+struct CountingMessage {
+    values: Vec<DataValue>,
+    byte_len_calls: std::cell::Cell<usize>,
+}
+
+impl BinaryEncoder<CountingMessage> for CountingMessage {
+    fn byte_len(&self) -> usize {
+        self.byte_len_calls.set(self.byte_len_calls.get() + 1);
+        self.values.iter().map(|v| v.byte_len()).sum()
+    }
+
+    fn encode<S: Write>(&self, stream: &mut S) -> EncodingResult<usize> {
+        let mut size = 0;
+        for v in &self.values {
+            size += v.encode(stream)?;
+        }
+        Ok(size)
+    }
+
+    fn decode<S: std::io::Read>(_: &mut S, _: &DecodingOptions) -> EncodingResult<Self> {
+        Err(StatusCode::BadDecodingError)
+    }
+}
+
+/// The byte length of a sized message is only computed once however many times it is asked for
+#[test]
+fn sized_message_byte_len() {
+    let message = CountingMessage {
+        values: (0..10000).map(|i| DataValue::new_now(i as u32)).collect(),
+        byte_len_calls: std::cell::Cell::new(0),
+    };
+    let sized_message = SizedMessage::new(&message);
+    let byte_len = sized_message.byte_len();
+    for _ in 0..10 {
+        assert_eq!(sized_message.byte_len(), byte_len);
+    }
+    assert_eq!(message.byte_len_calls.get(), 1);
+    assert_eq!(message.encode_to_vec().len(), byte_len);
+}
+
+/// The chunk count of a large message matches the number of chunks it is encoded into
+#[test]
+fn chunk_count() {
+    let _ = Test::setup();
+
+    let secure_channel = SecureChannel::new_no_certificate_store();
+    let response = make_large_read_response();
+    let sized_message = SizedMessage::new(&response);
+
+    assert_eq!(
+        Chunker::chunk_count(0, &secure_channel, &sized_message).unwrap(),
+        1
+    );
+
+    let chunk_count =
+        Chunker::chunk_count(MIN_CHUNK_SIZE, &secure_channel, &sized_message).unwrap();
+    assert!(chunk_count > 1);
+    let chunks = Chunker::encode_sized(
+        1000,
+        100,
+        0,
+        MIN_CHUNK_SIZE,
+        &secure_channel,
+        &sized_message,
+    )
+    .unwrap();
+    assert_eq!(chunks.len(), chunk_count);
+
+    // The single pass body encodes to the same size and so the same number of chunks
+    let body = Chunker::encode_body(0, &secure_channel, &response).unwrap();
+    assert_eq!(
+        Chunker::body_chunk_count(MIN_CHUNK_SIZE, &secure_channel, &response, body.len()).unwrap(),
+        chunk_count
+    );
+}
+
+/// The message writer chunks messages to its limits and rejects those that need too many chunks
+#[test]
+fn message_writer_chunk_limits() {
+    let _ = Test::setup();
+
+    let secure_channel = SecureChannel::new_no_certificate_store();
+    let chunk_count = Chunker::chunk_count(
+        MIN_CHUNK_SIZE,
+        &secure_channel,
+        &SizedMessage::new(&make_large_read_response()),
+    )
+    .unwrap();
+
+    let mut message_writer = MessageWriter::new(65536, 0, 0);
+    message_writer.set_limits(0, MIN_CHUNK_SIZE, chunk_count);
+    message_writer
+        .write(1, make_large_read_response(), &secure_channel)
+        .unwrap();
+    let metrics = message_writer.transport_counters().snapshot();
+    assert_eq!(metrics.chunks_sent, chunk_count as u64);
+
+    message_writer.set_limits(0, MIN_CHUNK_SIZE, chunk_count - 1);
+    assert_eq!(
+        message_writer
+            .write(2, make_large_read_response(), &secure_channel)
+            .unwrap_err(),
+        StatusCode::BadCommunicationError
+    );
+}
+
 /// Encode a very large message that matches and exceeds a max message size and expect the appropriate response
 #[test]
 fn max_message_size() {
@@ -203,6 +425,103 @@ fn max_message_size() {
     assert_eq!(err, StatusCode::BadResponseTooLarge);
 }
 
+/// Compare the max body size against hand-worked values
+#[test]
+fn max_body_size() {
+    let _ = Test::setup();
+
+    // No security, 8192 - message header (12) - token id (4) - sequence header (8)
+    assert_eq!(
+        MessageChunk::max_body_size(
+            8192,
+            SecurityPolicy::None,
+            MessageSecurityMode::None,
+            MessageChunkType::Message
+        )
+        .unwrap(),
+        8168
+    );
+    // Should be the same as what a chunk from a channel with no security holds
+    let secure_channel = SecureChannel::new_no_certificate_store();
+    assert_eq!(
+        MessageChunk::body_size_from_message_size(MessageChunkType::Message, &secure_channel, 8192)
+            .unwrap(),
+        8168
+    );
+
+    // No security, 8192 - message header (12) - asymmetric header of policy uri (4 + 47),
+    // null certificate (4), null thumbprint (4) - sequence header (8)
+    assert_eq!(
+        MessageChunk::max_body_size(
+            8192,
+            SecurityPolicy::None,
+            MessageSecurityMode::None,
+            MessageChunkType::OpenSecureChannel
+        )
+        .unwrap(),
+        8113
+    );
+
+    // Signed, 8192 - message header (12) - token id (4) - sequence header (8) - signature (32)
+    assert_eq!(
+        MessageChunk::max_body_size(
+            8192,
+            SecurityPolicy::Basic256Sha256,
+            MessageSecurityMode::Sign,
+            MessageChunkType::Message
+        )
+        .unwrap(),
+        8136
+    );
+
+    // Encrypted, 16 * floor((8192 - 16) / 16) - sequence header (8) - signature (32) - 1
+    assert_eq!(
+        MessageChunk::max_body_size(
+            8192,
+            SecurityPolicy::Basic256Sha256,
+            MessageSecurityMode::SignAndEncrypt,
+            MessageChunkType::Message
+        )
+        .unwrap(),
+        8135
+    );
+    // 16 * floor((65535 - 16) / 16) = 65504, - 41
+    assert_eq!(
+        MessageChunk::max_body_size(
+            65535,
+            SecurityPolicy::Basic256Sha256,
+            MessageSecurityMode::SignAndEncrypt,
+            MessageChunkType::CloseSecureChannel
+        )
+        .unwrap(),
+        65463
+    );
+
+    // Secure open secure channel depends on the certificates
+    assert_eq!(
+        MessageChunk::max_body_size(
+            8192,
+            SecurityPolicy::Basic256Sha256,
+            MessageSecurityMode::SignAndEncrypt,
+            MessageChunkType::OpenSecureChannel
+        )
+        .unwrap_err(),
+        StatusCode::BadInvalidArgument
+    );
+
+    // Too small to hold anything
+    assert_eq!(
+        MessageChunk::max_body_size(
+            40,
+            SecurityPolicy::Basic256Sha256,
+            MessageSecurityMode::SignAndEncrypt,
+            MessageChunkType::Message
+        )
+        .unwrap_err(),
+        StatusCode::BadInvalidArgument
+    );
+}
+
 /// Encode a large message and then ensure verification throws error for secure channel id mismatch
 #[test]
 fn validate_chunks_secure_channel_id() {
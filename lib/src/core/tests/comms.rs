@@ -3,7 +3,12 @@ use std::io::*;
 use crate::crypto::SecurityPolicy;
 use crate::types::*;
 
-use crate::core::comms::{secure_channel::*, tcp_types::*};
+use crate::core::comms::{
+    message_writer::MessageWriter, secure_channel::*, tcp_types::*,
+    transport_metrics::MESSAGE_SIZE_BUCKETS,
+};
+
+use super::make_sample_message;
 
 fn hello_data() -> Vec<u8> {
     vec![
@@ -134,3 +139,40 @@ pub fn secure_channel_nonce_none() {
         .set_remote_nonce_from_byte_string(&ByteString::from(b""))
         .is_ok());
 }
+
+#[test]
+pub fn message_writer_metrics() {
+    let secure_channel = SecureChannel::new_no_certificate_store();
+    let mut message_writer = MessageWriter::new(65536, 0, 0);
+    let transport_counters = message_writer.transport_counters();
+    assert_eq!(transport_counters.snapshot().messages_sent, 0);
+
+    message_writer
+        .write(1, make_sample_message(), &secure_channel)
+        .unwrap();
+    let first_size = message_writer.bytes_to_write().len();
+    message_writer
+        .write(2, make_sample_message(), &secure_channel)
+        .unwrap();
+    let second_size = message_writer.bytes_to_write().len();
+
+    let metrics = transport_counters.snapshot();
+    assert_eq!(metrics.messages_sent, 2);
+    assert_eq!(metrics.chunks_sent, 2);
+    assert_eq!(metrics.bytes_sent, (first_size + second_size) as u64);
+    assert_eq!(metrics.messages_received, 0);
+    assert_eq!(metrics.bytes_received, 0);
+
+    // Both messages are small so they land in the first bucket of the histogram
+    assert!(first_size <= MESSAGE_SIZE_BUCKETS[0]);
+    assert_eq!(metrics.message_sizes[0], 2);
+    assert_eq!(metrics.message_sizes.iter().sum::<u64>(), 2);
+
+    // Received messages are counted separately but share the histogram
+    transport_counters.record_received(10000, 3);
+    let metrics = transport_counters.snapshot();
+    assert_eq!(metrics.messages_received, 1);
+    assert_eq!(metrics.bytes_received, 10000);
+    assert_eq!(metrics.chunks_received, 3);
+    assert_eq!(metrics.message_sizes[2], 1);
+}
@@ -4,7 +4,9 @@ use crate::types::{
     string::UAString,
 };
 
-use crate::core::comms::tcp_types::{HelloMessage, MessageHeader, MessageType};
+use crate::core::comms::tcp_types::{
+    AcknowledgeMessage, HelloMessage, MessageHeader, MessageType, PROTOCOL_VERSION,
+};
 
 #[test]
 fn endpoint_url() {
@@ -78,3 +80,28 @@ fn valid_buffer_sizes() {
     h.send_buffer_size = 8196;
     assert!(h.is_valid_buffer_sizes());
 }
+
+#[test]
+fn protocol_version() {
+    // The client asks for the version that is implemented, or an earlier one
+    let mut h = HelloMessage::new("opc.tcp://foo/", 8196, 8196, 0, 0);
+    assert_eq!(h.protocol_version, PROTOCOL_VERSION);
+    assert!(h.is_protocol_version_supported());
+
+    // A later version is rejected
+    h.protocol_version = PROTOCOL_VERSION + 1;
+    assert!(!h.is_protocol_version_supported());
+
+    // As is a server acknowledging with a later version
+    let mut ack = AcknowledgeMessage {
+        message_header: MessageHeader::new(MessageType::Acknowledge),
+        protocol_version: PROTOCOL_VERSION,
+        receive_buffer_size: 8196,
+        send_buffer_size: 8196,
+        max_message_size: 0,
+        max_chunk_count: 0,
+    };
+    assert!(ack.is_protocol_version_supported());
+    ack.protocol_version = PROTOCOL_VERSION + 1;
+    assert!(!ack.is_protocol_version_supported());
+}
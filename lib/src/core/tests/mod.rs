@@ -140,6 +140,7 @@ fn make_test_cert(key_size: u32) -> (X509, PrivateKey) {
         organizational_unit: "x.org ops".to_string(),
         country: "EN".to_string(),
         state: "London".to_string(),
+        application_uri: APPLICATION_URI.to_string(),
         alt_host_names: vec![
             APPLICATION_URI.to_string(),
             "foo".to_string(),
@@ -4,7 +4,7 @@
 use crate::crypto::SecurityPolicy;
 
 use crate::core::{
-    comms::{chunker::*, secure_channel::*},
+    comms::{chunker::*, message_chunk::*, secure_channel::*, tcp_types::MIN_CHUNK_SIZE},
     tests::*,
 };
 
@@ -203,3 +203,154 @@ fn symmetric_sign_and_encrypt_message_chunk_basic256sha256() {
         SecurityPolicy::Basic256Sha256,
     );
 }
+
+/// Encode a read response to wire bytes in several secured chunks and decode it back again
+#[test]
+fn encode_decode_message_sign_and_encrypt() {
+    let _ = Test::setup();
+
+    let (secure_channel1, mut secure_channel2) = make_secure_channels(
+        MessageSecurityMode::SignAndEncrypt,
+        SecurityPolicy::Basic256Sha256,
+    );
+
+    let results = (0..1000).map(|i| DataValue::new_now(i as u32)).collect();
+    let message: SupportedMessage = ReadResponse {
+        response_header: ResponseHeader::null(),
+        results: Some(results),
+        diagnostic_infos: None,
+    }
+    .into();
+
+    let data = secure_channel1
+        .encode_message(&message, 1, 1, 0, MIN_CHUNK_SIZE)
+        .unwrap();
+    // The message is too large for a single chunk
+    assert!(data.len() > MIN_CHUNK_SIZE);
+
+    let message2 = secure_channel2.decode_message(&data, true).unwrap();
+    assert_eq!(message, message2);
+
+    // Tampering with the data fails verification
+    let mut data = data;
+    let last = data.len() - 1;
+    data[last] ^= 0xff;
+    assert!(secure_channel2.decode_message(&data, true).is_err());
+
+    // Truncated data cannot be decoded
+    assert_eq!(
+        secure_channel2
+            .decode_message(&data[..100], true)
+            .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        secure_channel2.decode_message(&[], true).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
+
+/// A message followed by junk inside the chunk body is only rejected when decoding strictly
+#[test]
+fn decode_message_trailing_bytes() {
+    let _ = Test::setup();
+
+    let mut secure_channel = SecureChannel::new_no_certificate_store();
+    secure_channel.set_security_mode(MessageSecurityMode::None);
+    secure_channel.set_security_policy(SecurityPolicy::None);
+
+    // The body is the node id of the message, followed by the message and then the junk
+    let message = make_sample_message();
+    let mut body = message.node_id().encode_to_vec();
+    body.extend_from_slice(&message.encode_to_vec());
+    body.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+    let chunk = MessageChunk::new(
+        1,
+        1,
+        MessageChunkType::Message,
+        MessageIsFinalType::Final,
+        &secure_channel,
+        &body,
+    )
+    .unwrap();
+    let mut data = vec![0u8; chunk.data.len() + 4096];
+    let size = secure_channel.apply_security(&chunk, &mut data).unwrap();
+    let data = &data[..size];
+
+    assert_eq!(secure_channel.decode_message(data, false).unwrap(), message);
+    assert_eq!(
+        secure_channel.decode_message(data, true).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // Without the junk, strict decoding succeeds
+    let data = secure_channel
+        .encode_message(&message, 1, 1, 0, MIN_CHUNK_SIZE)
+        .unwrap();
+    assert_eq!(secure_channel.decode_message(&data, true).unwrap(), message);
+}
+
+/// Makes the wire bytes of a signed and encrypted OpenSecureChannel chunk sent to another party,
+/// along with a secure channel for that party to receive it. The chunk names our certificate as
+/// the sender but is signed with the supplied key, or with our own key if there isn't one.
+fn make_asymmetric_chunk(signing_key: Option<PrivateKey>) -> (Vec<u8>, SecureChannel) {
+    let (our_cert, our_key) = make_test_cert_2048();
+    let (their_cert, their_key) = make_test_cert_2048();
+
+    let mut secure_channel = SecureChannel::new_no_certificate_store();
+    secure_channel.set_security_mode(MessageSecurityMode::SignAndEncrypt);
+    secure_channel.set_security_policy(SecurityPolicy::Basic256Sha256);
+    secure_channel.set_cert(Some(our_cert.clone()));
+    secure_channel.set_remote_cert(Some(their_cert.clone()));
+    secure_channel.set_private_key(Some(signing_key.unwrap_or(our_key)));
+
+    let message: SupportedMessage = make_open_secure_channel_response().into();
+    let chunks = Chunker::encode(1, 1, 0, 0, &secure_channel, &message).unwrap();
+    assert_eq!(chunks.len(), 1);
+    let mut data = vec![0u8; chunks[0].data.len() + 4096];
+    let size = secure_channel
+        .apply_security(&chunks[0], &mut data[..])
+        .unwrap();
+    data.truncate(size);
+
+    let mut receiver = SecureChannel::new_no_certificate_store();
+    receiver.set_security_mode(MessageSecurityMode::SignAndEncrypt);
+    receiver.set_cert(Some(their_cert));
+    receiver.set_remote_cert(Some(our_cert));
+    receiver.set_private_key(Some(their_key));
+
+    (data, receiver)
+}
+
+#[test]
+fn asymmetric_chunk_tampered_body() {
+    let _ = Test::setup();
+
+    // An untouched chunk is decrypted and verified
+    let (data, mut receiver) = make_asymmetric_chunk(None);
+    assert!(receiver.verify_and_remove_security(&data).is_ok());
+
+    // Flip a byte of the encrypted body
+    let (mut data, mut receiver) = make_asymmetric_chunk(None);
+    let idx = data.len() - 100;
+    data[idx] ^= 0xff;
+    assert_eq!(
+        receiver.verify_and_remove_security(&data).unwrap_err(),
+        StatusCode::BadSecurityChecksFailed
+    );
+}
+
+#[test]
+fn asymmetric_chunk_wrong_signer() {
+    let _ = Test::setup();
+
+    // The chunk decrypts fine but the signature is not from the key of the certificate that the
+    // chunk says sent it
+    let (_, wrong_key) = make_test_cert_2048();
+    let (data, mut receiver) = make_asymmetric_chunk(Some(wrong_key));
+    assert_eq!(
+        receiver.verify_and_remove_security(&data).unwrap_err(),
+        StatusCode::BadSecurityChecksFailed
+    );
+}
@@ -22,3 +22,193 @@ fn get_endpoints_request() {
     };
     serialize_test(r);
 }
+
+fn response_header() -> ResponseHeader {
+    ResponseHeader::new_service_result(&request_header(), StatusCode::BadUnexpectedError)
+}
+
+/// Encodes the message and decodes it back again through its encoding id, the same way it
+/// would arrive from the wire, and expects the result to be identical.
+fn supported_message_round_trip(message: SupportedMessage) {
+    let object_id = message.node_id().as_object_id().unwrap();
+    let mut stream = Cursor::new(message.encode_to_vec());
+    let decoding_options = DecodingOptions::test();
+    let new_message =
+        SupportedMessage::decode_by_object_id(&mut stream, object_id, &decoding_options).unwrap();
+    assert_eq!(message, new_message);
+}
+
+/// Declares a test per service type. Each test serializes the populated value on its own and
+/// then as a `SupportedMessage` to ensure both paths agree.
+macro_rules! service_round_trip_tests {
+    ( $( $name:ident => $value:expr ),* $(,)? ) => {
+        $(
+        #[test]
+        fn $name() {
+            let value = serialize_test_and_return($value);
+            supported_message_round_trip(value.into());
+        }
+        )*
+    };
+}
+
+service_round_trip_tests! {
+    round_trip_service_fault => ServiceFault {
+        response_header: response_header(),
+    },
+    round_trip_get_endpoints_request => GetEndpointsRequest {
+        request_header: request_header(),
+        endpoint_url: UAString::from("opc.tcp://localhost/my_path"),
+        locale_ids: Some(vec![UAString::from("en-EN")]),
+        profile_uris: None,
+    },
+    round_trip_activate_session_request => ActivateSessionRequest {
+        request_header: request_header(),
+        client_signature: SignatureData {
+            algorithm: UAString::from("http://www.w3.org/2000/09/xmldsig#rsa-sha1"),
+            signature: ByteString::from(&[1u8, 2, 3, 4]),
+        },
+        client_software_certificates: None,
+        locale_ids: Some(vec![UAString::from("en"), UAString::from("de")]),
+        user_identity_token: ExtensionObject::from_encodable(
+            ObjectId::AnonymousIdentityToken_Encoding_DefaultBinary,
+            &AnonymousIdentityToken {
+                policy_id: UAString::from("anonymous"),
+            },
+        ),
+        user_token_signature: SignatureData::null(),
+    },
+    round_trip_close_session_request => CloseSessionRequest {
+        request_header: request_header(),
+        delete_subscriptions: true,
+    },
+    round_trip_browse_request => BrowseRequest {
+        request_header: request_header(),
+        view: ViewDescription {
+            view_id: NodeId::new(1, "View"),
+            timestamp: DateTime::now(),
+            view_version: 3,
+        },
+        requested_max_references_per_node: 100,
+        nodes_to_browse: Some(vec![BrowseDescription {
+            node_id: ObjectId::RootFolder.into(),
+            browse_direction: BrowseDirection::Both,
+            reference_type_id: ReferenceTypeId::Organizes.into(),
+            include_subtypes: true,
+            node_class_mask: 0xff,
+            result_mask: 0x3f,
+        }]),
+    },
+    round_trip_browse_response => BrowseResponse {
+        response_header: response_header(),
+        results: Some(vec![BrowseResult {
+            status_code: StatusCode::Good,
+            continuation_point: ByteString::from(&[9u8, 8, 7]),
+            references: Some(vec![ReferenceDescription {
+                reference_type_id: ReferenceTypeId::Organizes.into(),
+                is_forward: true,
+                node_id: NodeId::from(ObjectId::ObjectsFolder).into(),
+                browse_name: QualifiedName::new(0, "Objects"),
+                display_name: LocalizedText::new("en", "Objects"),
+                node_class: NodeClass::Object,
+                type_definition: NodeId::from(ObjectTypeId::FolderType).into(),
+            }]),
+        }]),
+        diagnostic_infos: None,
+    },
+    round_trip_translate_browse_paths_request => TranslateBrowsePathsToNodeIdsRequest {
+        request_header: request_header(),
+        browse_paths: Some(vec![BrowsePath {
+            starting_node: ObjectId::ObjectsFolder.into(),
+            relative_path: RelativePath {
+                elements: Some(vec![RelativePathElement {
+                    reference_type_id: ReferenceTypeId::HierarchicalReferences.into(),
+                    is_inverse: false,
+                    include_subtypes: true,
+                    target_name: QualifiedName::new(0, "Server"),
+                }]),
+            },
+        }]),
+    },
+    round_trip_read_request => ReadRequest {
+        request_header: request_header(),
+        max_age: 500.0,
+        timestamps_to_return: TimestampsToReturn::Both,
+        nodes_to_read: Some(vec![
+            ReadValueId::from(NodeId::new(2, "v1")),
+            ReadValueId {
+                node_id: NodeId::new(2, 100),
+                attribute_id: AttributeId::BrowseName as u32,
+                index_range: UAString::from("1:2"),
+                data_encoding: QualifiedName::null(),
+            },
+        ]),
+    },
+    round_trip_read_response => ReadResponse {
+        response_header: response_header(),
+        results: Some(vec![
+            DataValue::new_now(Variant::from(vec![1i32, 2, 3])),
+            DataValue::new_now(UAString::from("Hello")),
+            DataValue::null(),
+        ]),
+        diagnostic_infos: Some(vec![DiagnosticInfo::null()]),
+    },
+    round_trip_write_request => WriteRequest {
+        request_header: request_header(),
+        nodes_to_write: Some(vec![WriteValue {
+            node_id: NodeId::new(2, "v1"),
+            attribute_id: AttributeId::Value as u32,
+            index_range: UAString::null(),
+            value: DataValue::new_now(99.5f64),
+        }]),
+    },
+    round_trip_write_response => WriteResponse {
+        response_header: response_header(),
+        results: Some(vec![StatusCode::Good, StatusCode::BadTypeMismatch]),
+        diagnostic_infos: None,
+    },
+    round_trip_call_request => CallRequest {
+        request_header: request_header(),
+        methods_to_call: Some(vec![CallMethodRequest {
+            object_id: ObjectId::Server.into(),
+            method_id: MethodId::Server_GetMonitoredItems.into(),
+            input_arguments: Some(vec![Variant::from(1u32), Variant::from("arg")]),
+        }]),
+    },
+    round_trip_create_subscription_request => CreateSubscriptionRequest {
+        request_header: request_header(),
+        requested_publishing_interval: 250.0,
+        requested_lifetime_count: 30,
+        requested_max_keep_alive_count: 10,
+        max_notifications_per_publish: 1000,
+        publishing_enabled: true,
+        priority: 5,
+    },
+    round_trip_create_monitored_items_request => CreateMonitoredItemsRequest {
+        request_header: request_header(),
+        subscription_id: 1,
+        timestamps_to_return: TimestampsToReturn::Server,
+        items_to_create: Some(vec![MonitoredItemCreateRequest {
+            item_to_monitor: ReadValueId::from(NodeId::new(2, "v1")),
+            monitoring_mode: MonitoringMode::Reporting,
+            requested_parameters: MonitoringParameters {
+                client_handle: 12,
+                sampling_interval: 100.0,
+                filter: ExtensionObject::null(),
+                queue_size: 5,
+                discard_oldest: true,
+            },
+        }]),
+    },
+    round_trip_publish_request => PublishRequest {
+        request_header: request_header(),
+        subscription_acknowledgements: Some(vec![SubscriptionAcknowledgement {
+            subscription_id: 1,
+            sequence_number: 44,
+        }]),
+    },
+    round_trip_delete_subscriptions_request => DeleteSubscriptionsRequest {
+        request_header: request_header(),
+        subscription_ids: Some(vec![1, 2, 3]),
+    },
+}
@@ -8,19 +8,21 @@ use std::result::Result;
 
 use openssl::symm::{Cipher, Crypter, Mode};
 
-use crate::types::status_code::StatusCode;
+use super::{error::CryptoError, secret::SecretBytes, SecurityPolicy};
 
-use super::SecurityPolicy;
+/// Size of an AES block, and of the IV, in bytes
+const AES_BLOCK_SIZE: usize = 16;
 
+/// A symmetric key. The key bytes are zeroed when the key is dropped.
 #[derive(Debug)]
 pub struct AesKey {
-    value: Vec<u8>,
+    value: SecretBytes,
     security_policy: SecurityPolicy,
 }
 impl AesKey {
     pub fn new(security_policy: SecurityPolicy, value: &[u8]) -> AesKey {
         AesKey {
-            value: value.to_vec(),
+            value: SecretBytes::from(value),
             security_policy,
         }
     }
@@ -29,12 +31,17 @@ impl AesKey {
         &self.value
     }
 
+    /// Returns the security policy that the key was derived for
+    pub fn security_policy(&self) -> SecurityPolicy {
+        self.security_policy
+    }
+
     fn validate_aes_args(
         cipher: &Cipher,
         src: &[u8],
         iv: &[u8],
         dst: &mut [u8],
-    ) -> Result<(), StatusCode> {
+    ) -> Result<(), CryptoError> {
         if dst.len() < src.len() + cipher.block_size() {
             error!(
                 "Dst buffer is too small {} vs {} + {}",
@@ -42,14 +49,14 @@ impl AesKey {
                 dst.len(),
                 cipher.block_size()
             );
-            Err(StatusCode::BadUnexpectedError)
-        } else if iv.len() != 16 && iv.len() != 32 {
-            // ... It would be nice to compare iv size to be exact to the key size here (should be the
-            // same) but AesKey doesn't tell us that info. Have to check elsewhere
+            Err(CryptoError::InvalidLength)
+        } else if iv.len() != AES_BLOCK_SIZE {
+            // AES has a 128-bit block size whatever the key size, and so does the IV
             error!("IV is not an expected size, len = {}", iv.len());
-            Err(StatusCode::BadUnexpectedError)
-        } else if src.len() % 16 != 0 {
-            panic!("Block size {} is wrong, check stack", src.len());
+            Err(CryptoError::InvalidIv)
+        } else if src.len() % AES_BLOCK_SIZE != 0 {
+            error!("Data of size {} is not a whole number of blocks", src.len());
+            Err(CryptoError::InvalidLength)
         } else {
             Ok(())
         }
@@ -80,36 +87,28 @@ impl AesKey {
         src: &[u8],
         iv: &[u8],
         dst: &mut [u8],
-    ) -> Result<usize, StatusCode> {
+    ) -> Result<usize, CryptoError> {
         let cipher = self.cipher();
 
         Self::validate_aes_args(&cipher, src, iv, dst)?;
 
         trace!("Encrypting block of size {}", src.len());
 
-        let crypter = Crypter::new(cipher, mode, &self.value, Some(iv));
-        if let Ok(mut crypter) = crypter {
-            crypter.pad(false);
-            let result = crypter.update(src, dst);
-            if let Ok(count) = result {
-                crypter
-                    .finalize(&mut dst[count..])
-                    .map(|rest| {
-                        trace!("do cipher size {}", count + rest);
-                        count + rest
-                    })
-                    .map_err(|e| {
-                        error!("Encryption error during finalize {:?}", e);
-                        StatusCode::BadUnexpectedError
-                    })
-            } else {
-                error!("Encryption error during update {:?}", result.unwrap_err());
-                Err(StatusCode::BadUnexpectedError)
-            }
-        } else {
-            error!("Encryption Error");
-            Err(StatusCode::BadUnexpectedError)
-        }
+        let mut crypter = Crypter::new(cipher, mode, &self.value, Some(iv)).map_err(|e| {
+            error!("Encryption error during setup {:?}", e);
+            CryptoError::OpenSsl(e)
+        })?;
+        crypter.pad(false);
+        let count = crypter.update(src, dst).map_err(|e| {
+            error!("Encryption error during update {:?}", e);
+            CryptoError::OpenSsl(e)
+        })?;
+        let rest = crypter.finalize(&mut dst[count..]).map_err(|e| {
+            error!("Encryption error during finalize {:?}", e);
+            CryptoError::OpenSsl(e)
+        })?;
+        trace!("do cipher size {}", count + rest);
+        Ok(count + rest)
     }
 
     pub fn block_size(&self) -> usize {
@@ -124,12 +123,14 @@ impl AesKey {
         self.cipher().key_len()
     }
 
-    pub fn encrypt(&self, src: &[u8], iv: &[u8], dst: &mut [u8]) -> Result<usize, StatusCode> {
+    /// Encrypts data using AES in CBC mode. The data must be a whole number of 16 byte blocks,
+    /// the IV must be 16 bytes and the destination must have room for the data plus a block.
+    pub fn encrypt(&self, src: &[u8], iv: &[u8], dst: &mut [u8]) -> Result<usize, CryptoError> {
         self.do_cipher(Mode::Encrypt, src, iv, dst)
     }
 
     /// Decrypts data using AES. The initialization vector is the nonce generated for the secure channel
-    pub fn decrypt(&self, src: &[u8], iv: &[u8], dst: &mut [u8]) -> Result<usize, StatusCode> {
+    pub fn decrypt(&self, src: &[u8], iv: &[u8], dst: &mut [u8]) -> Result<usize, CryptoError> {
         self.do_cipher(Mode::Decrypt, src, iv, dst)
     }
 }
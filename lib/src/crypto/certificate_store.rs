@@ -9,6 +9,7 @@ use std::fs::{metadata, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
 use openssl::{pkey, x509};
 
 use crate::types::status_code::StatusCode;
@@ -137,6 +138,45 @@ impl CertificateStore {
         Err(format!("Cannot read pkey from path {:?}", path))
     }
 
+    /// Parses a certificate and its private key from a single PEM buffer which contains both a
+    /// `CERTIFICATE` block and a `PRIVATE KEY` block, e.g. a file that bundles the two together.
+    /// The passphrase is only required if the private key is encrypted. The private key must
+    /// match the public key held by the certificate.
+    ///
+    /// # Errors
+    ///
+    /// `BadCertificateInvalid` if the certificate cannot be parsed, `BadDecodingError` if the
+    /// private key cannot be parsed, and `BadSecurityChecksFailed` if the private key does not
+    /// belong to the certificate.
+    ///
+    pub fn load_cert_and_key(
+        pem: &[u8],
+        passphrase: Option<&str>,
+    ) -> Result<(X509, PrivateKey), StatusCode> {
+        let cert = x509::X509::from_pem(pem).map_err(|_| {
+            error!("Cannot read a certificate from the PEM data supplied");
+            StatusCode::BadCertificateInvalid
+        })?;
+        let pkey = if let Some(passphrase) = passphrase {
+            pkey::PKey::private_key_from_pem_passphrase(pem, passphrase.as_bytes())
+        } else {
+            pkey::PKey::private_key_from_pem(pem)
+        }
+        .map_err(|_| {
+            error!("Cannot read a private key from the PEM data supplied");
+            StatusCode::BadDecodingError
+        })?;
+        let public_key = cert.public_key().map_err(|_| {
+            error!("Cannot obtain public key from certificate");
+            StatusCode::BadCertificateInvalid
+        })?;
+        if !public_key.public_eq(&pkey) {
+            error!("Private key does not match the public key of the certificate");
+            return Err(StatusCode::BadSecurityChecksFailed);
+        }
+        Ok((X509::from(cert), PrivateKey::wrap_private_key(pkey)))
+    }
+
     /// Reads the store's own certificate and private key
     pub fn read_own_cert_and_pkey(&self) -> Result<(X509, PrivateKey), String> {
         if let Ok(cert) = CertificateStore::read_cert(&self.own_certificate_path()) {
@@ -172,7 +212,8 @@ impl CertificateStore {
         cert_path: &Path,
         pkey_path: &Path,
     ) -> Result<(X509, PrivateKey), String> {
-        let (cert, pkey) = X509::cert_and_pkey(args)?;
+        let (cert, pkey) = X509::cert_and_pkey(args)
+            .map_err(|err| format!("Cannot create certificate and key, error {}", err))?;
 
         // Write the public cert
         let _ = CertificateStore::store_cert(&cert, cert_path, overwrite)?;
@@ -200,6 +241,47 @@ impl CertificateStore {
         )
     }
 
+    /// Returns the application instance certificate and private key held in the PKI directory,
+    /// creating and storing a new self-signed pair from the supplied arguments if there isn't one
+    /// yet. The application URI should be the first of the arguments' alt host names so the new
+    /// cert carries it as a subject alt name.
+    ///
+    /// A cert or key that exists but cannot be read is never overwritten.
+    ///
+    /// # Errors
+    ///
+    /// `BadConfigurationError` if the PKI directory cannot be created or the pair can be neither
+    /// read nor created.
+    ///
+    pub fn ensure_application_certificate(
+        pki_path: &Path,
+        x509_data: &X509Data,
+    ) -> Result<(X509, PrivateKey), StatusCode> {
+        let certificate_store = CertificateStore::new(pki_path);
+        certificate_store.ensure_pki_path().map_err(|err| {
+            error!("Cannot create the PKI directory, error = {}", err);
+            StatusCode::BadConfigurationError
+        })?;
+        match certificate_store.read_own_cert_and_pkey() {
+            Ok(result) => Ok(result),
+            Err(err) => {
+                info!(
+                    "No application instance certificate could be read ({}) so one will be created",
+                    err
+                );
+                certificate_store
+                    .create_and_store_application_instance_cert(x509_data, false)
+                    .map_err(|err| {
+                        error!(
+                            "Application instance certificate could not be created, error = {}",
+                            err
+                        );
+                        StatusCode::BadConfigurationError
+                    })
+            }
+        }
+    }
+
     /// Validates the cert as trusted and valid. If the cert is unknown, it will be written to
     /// the rejected folder so that the administrator can manually move it to the trusted folder.
     ///
@@ -262,6 +344,22 @@ impl CertificateStore {
         }
     }
 
+    /// Tests if the cert is trusted, i.e. a file named from its thumbprint resides in the trusted
+    /// folder and holds exactly the same cert. Unlike validation, an unknown cert is not written
+    /// to either folder and no other checks are made on the cert.
+    pub fn is_trusted(&self, cert: &X509) -> bool {
+        let cert_file_name = CertificateStore::cert_file_name(cert);
+        let mut cert_path = self.rejected_certs_dir();
+        cert_path.push(&cert_file_name);
+        if cert_path.exists() {
+            false
+        } else {
+            let mut cert_path = self.trusted_certs_dir();
+            cert_path.push(&cert_file_name);
+            CertificateStore::ensure_cert_and_file_are_the_same(cert, &cert_path)
+        }
+    }
+
     /// Validates the certificate according to the strictness set in the CertificateStore itself.
     /// Validation might include checking the issue time, expiration time, revocation, trust chain
     /// etc. In the first instance this function will only check if the cert is recognized
@@ -365,7 +463,6 @@ impl CertificateStore {
 
             // Now inspect the cert not before / after values to ensure its validity
             if self.check_time {
-                use chrono::Utc;
                 let now = Utc::now();
                 let status_code = cert.is_time_valid(&now);
                 if status_code.is_bad() {
@@ -409,6 +506,79 @@ impl CertificateStore {
         StatusCode::Good
     }
 
+    /// Validates an application instance certificate by running the checks described in OPC UA
+    /// Part 4 6.1.3 in order and returning the status code of the first one that fails.
+    ///
+    /// 1. Signature and trust - the cert must reside in the trusted folder, match the copy on
+    ///    disk and if self-signed, carry a valid signature.
+    /// 2. Validity time - `now` must lie between the cert's not before / after values.
+    /// 3. Revocation - the store does not hold revocation lists so there is nothing to check.
+    /// 4. Host name - if supplied, it must match one of the cert's alt names.
+    /// 5. Application uri - must match the uri alt name on the cert.
+    /// 6. Key usage - the cert must permit signing and encipherment.
+    ///
+    /// Unlike `validate_application_instance_cert`, this function ignores the store's leniency
+    /// settings and never moves unknown certs into the trusted or rejected folders.
+    pub fn validate_application_certificate(
+        &self,
+        cert: &X509,
+        expected_uri: &str,
+        hostname: Option<&str>,
+        now: &DateTime<Utc>,
+    ) -> StatusCode {
+        let cert_file_name = CertificateStore::cert_file_name(cert);
+
+        // Signature and trust
+        let mut rejected_path = self.rejected_certs_dir();
+        rejected_path.push(&cert_file_name);
+        if rejected_path.exists() {
+            warn!(
+                "Certificate {} is untrusted because it resides in the rejected directory",
+                cert_file_name
+            );
+            return StatusCode::BadSecurityChecksFailed;
+        }
+        let mut trusted_path = self.trusted_certs_dir();
+        trusted_path.push(&cert_file_name);
+        if !CertificateStore::ensure_cert_and_file_are_the_same(cert, &trusted_path) {
+            warn!(
+                "Certificate {} is not in the trusted directory",
+                cert_file_name
+            );
+            return StatusCode::BadCertificateUntrusted;
+        }
+
+        let status_code = cert.is_self_signature_valid();
+        if status_code.is_bad() {
+            return status_code;
+        }
+
+        // Validity time
+        let status_code = cert.is_time_valid(now);
+        if status_code.is_bad() {
+            return status_code;
+        }
+
+        // Revocation - nothing to check until the store holds revocation lists
+
+        // Host name
+        if let Some(hostname) = hostname {
+            let status_code = cert.is_hostname_valid(hostname);
+            if status_code.is_bad() {
+                return status_code;
+            }
+        }
+
+        // Application uri
+        let status_code = cert.is_application_uri_valid(expected_uri);
+        if status_code.is_bad() {
+            return status_code;
+        }
+
+        // Key usage
+        cert.is_key_usage_valid()
+    }
+
     /// Returns a certificate file name from the cert's issuer and thumbprint fields.
     /// File name is either "prefix - [thumbprint].der" or "thumbprint.der" depending on
     /// the cert's common name being empty or not
@@ -0,0 +1,110 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+//! The error returned by fallible crypto operations.
+
+use std::fmt;
+
+use openssl::error::ErrorStack;
+
+use crate::types::status_code::StatusCode;
+
+/// Describes why a crypto operation failed so that, for example, a certificate that fails to load
+/// can be told apart from one whose dates are unreadable.
+#[derive(Debug)]
+pub enum CryptoError {
+    /// The data is not valid DER
+    InvalidDer,
+    /// The data is not valid PEM
+    InvalidPem,
+    /// A null byte string was supplied where data was expected
+    NullByteString,
+    /// An ASN.1 date could not be parsed
+    DateParse(String),
+    /// A certificate subject has no entry of the requested kind, or it is not UTF-8
+    MissingSubjectEntry,
+    /// The padding cannot be used for the operation, e.g. a signature padding used to encrypt
+    UnsupportedPadding,
+    /// The data is not a valid length for the operation
+    InvalidLength,
+    /// The initialization vector is not the size the cipher requires
+    InvalidIv,
+    /// A certificate was not issued by the certificate it was checked against, e.g. because its
+    /// issuer name is not the other certificate's subject name
+    IssuerMismatch,
+    /// A signature does not verify against the key it was checked with
+    InvalidSignature,
+    /// Decrypted data is not padded as expected, e.g. it is not a whole number of blocks or its
+    /// padding bytes do not hold the padding size
+    InvalidPadding,
+    /// A certificate's public key cannot be obtained, e.g. because it is of an unsupported kind
+    InvalidPublicKey,
+    /// The security policy is unknown, or has no algorithm for the operation
+    UnsupportedSecurityPolicy,
+    /// A signature could not be created with the signing key
+    SigningFailed,
+    /// OpenSSL reported an error
+    OpenSsl(ErrorStack),
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::InvalidDer => write!(f, "data is not valid DER"),
+            CryptoError::InvalidPem => write!(f, "data is not valid PEM"),
+            CryptoError::NullByteString => write!(f, "byte string is null"),
+            CryptoError::DateParse(date) => write!(f, "cannot parse ASN.1 date \"{}\"", date),
+            CryptoError::MissingSubjectEntry => write!(f, "subject entry is missing or invalid"),
+            CryptoError::UnsupportedPadding => write!(f, "padding is not supported"),
+            CryptoError::InvalidLength => write!(f, "data is not a valid length"),
+            CryptoError::InvalidIv => write!(f, "initialization vector is not a valid length"),
+            CryptoError::IssuerMismatch => write!(f, "certificate was not issued by the issuer"),
+            CryptoError::InvalidSignature => write!(f, "signature is invalid"),
+            CryptoError::InvalidPadding => write!(f, "padding is invalid"),
+            CryptoError::InvalidPublicKey => write!(f, "certificate public key is invalid"),
+            CryptoError::UnsupportedSecurityPolicy => write!(f, "security policy is not supported"),
+            CryptoError::SigningFailed => write!(f, "data could not be signed"),
+            CryptoError::OpenSsl(err) => write!(f, "OpenSSL error {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CryptoError::OpenSsl(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorStack> for CryptoError {
+    fn from(err: ErrorStack) -> Self {
+        CryptoError::OpenSsl(err)
+    }
+}
+
+impl From<CryptoError> for StatusCode {
+    /// Maps a crypto error onto the status code a service reports for it. Anything wrong with a
+    /// certificate is `BadCertificateInvalid`, while errors from OpenSSL itself are unexpected.
+    fn from(err: CryptoError) -> Self {
+        match err {
+            CryptoError::InvalidDer
+            | CryptoError::InvalidPem
+            | CryptoError::NullByteString
+            | CryptoError::DateParse(_)
+            | CryptoError::MissingSubjectEntry
+            | CryptoError::IssuerMismatch
+            | CryptoError::InvalidPublicKey => StatusCode::BadCertificateInvalid,
+            CryptoError::InvalidSignature | CryptoError::InvalidPadding => {
+                StatusCode::BadSecurityChecksFailed
+            }
+            CryptoError::UnsupportedSecurityPolicy => StatusCode::BadSecurityPolicyRejected,
+            CryptoError::UnsupportedPadding
+            | CryptoError::InvalidLength
+            | CryptoError::InvalidIv => StatusCode::BadInvalidArgument,
+            CryptoError::SigningFailed | CryptoError::OpenSsl(_) => StatusCode::BadUnexpectedError,
+        }
+    }
+}
@@ -10,7 +10,10 @@ use openssl::{hash, pkey, sign};
 
 use crate::types::status_code::StatusCode;
 
-use super::{SHA1_SIZE, SHA256_SIZE};
+use super::{
+    secret::{constant_time_eq, SecretBytes, ZeroOnDrop},
+    SHA1_SIZE, SHA256_SIZE,
+};
 
 /// Pseudo random `P_SHA` implementation for creating pseudo random range of bytes from an input
 ///
@@ -30,41 +33,36 @@ pub fn p_sha(
     secret: &[u8],
     seed: &[u8],
     length: usize,
-) -> Vec<u8> {
-    let mut result = Vec::with_capacity(length);
-
-    let mut hmac = Vec::with_capacity(seed.len() * 2);
-
-    let mut a_last = Vec::with_capacity(seed.len());
-    a_last.extend_from_slice(seed); // A(0) = seed
+) -> SecretBytes {
+    let digest_size = message_digest.size();
+
+    // The scratch buffers hold key material so they are zeroed when dropped. They are sized up
+    // front so that growing them does not leave a stale copy behind in freed memory.
+    let mut result = ZeroOnDrop::new(vec![0u8; length]);
+    let mut hmac = ZeroOnDrop::new(vec![0u8; digest_size + seed.len()]);
+    hmac[digest_size..].copy_from_slice(seed);
+
+    // A(1) = HMAC_SHA1(secret, A(0))
+    let mut a = hmac_vec(message_digest, secret, seed);
+    for chunk in result.chunks_mut(digest_size) {
+        // Append a slice of random data
+        hmac[..digest_size].copy_from_slice(&a);
+        let bytes = hmac_vec(message_digest, secret, &hmac);
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
 
-    while result.len() < length {
         // A(n) = HMAC_SHA1(secret, A(n-1))
-        let a_next = hmac_vec(message_digest, secret, &a_last);
-
-        // Append a slice of random data
-        let bytes = {
-            hmac.clear();
-            hmac.extend(&a_next);
-            hmac.extend_from_slice(seed);
-            hmac_vec(message_digest, secret, &hmac)
-        };
-        result.extend(&bytes);
-
-        a_last.clear();
-        a_last.extend(&a_next);
+        a = hmac_vec(message_digest, secret, &a);
     }
 
-    result.truncate(length);
-    result
+    SecretBytes::from(&result[..])
 }
 
-fn hmac_vec(digest: hash::MessageDigest, key: &[u8], data: &[u8]) -> Vec<u8> {
+fn hmac_vec(digest: hash::MessageDigest, key: &[u8], data: &[u8]) -> SecretBytes {
     // Compute a signature
     let pkey = pkey::PKey::hmac(key).unwrap();
     let mut signer = sign::Signer::new(digest, &pkey).unwrap();
     signer.update(data).unwrap();
-    signer.sign_to_vec().unwrap()
+    SecretBytes::from(signer.sign_to_vec().unwrap())
 }
 
 fn hmac(
@@ -91,20 +89,10 @@ pub fn hmac_sha1(key: &[u8], data: &[u8], signature: &mut [u8]) -> Result<(), St
     }
 }
 
-/// Verify that the HMAC for the data block matches the supplied signature
+/// Verify that the HMAC for the data block matches the supplied signature. The comparison takes
+/// constant time so it cannot be used as a timing oracle to forge a signature.
 pub fn verify_hmac_sha1(key: &[u8], data: &[u8], signature: &[u8]) -> bool {
-    if signature.len() != SHA1_SIZE {
-        false
-    } else {
-        let mut tmp_signature = [0u8; SHA1_SIZE];
-        if hmac_sha1(key, data, &mut tmp_signature).is_err() {
-            false
-        } else {
-            trace!("Original signature = {:?}", signature);
-            trace!("Calculated signature = {:?}", tmp_signature);
-            openssl::memcmp::eq(signature, &tmp_signature[..])
-        }
-    }
+    verify_hmac(hash::MessageDigest::sha1(), key, data, signature)
 }
 
 pub fn hmac_sha256(key: &[u8], data: &[u8], signature: &mut [u8]) -> Result<(), StatusCode> {
@@ -119,16 +107,15 @@ pub fn hmac_sha256(key: &[u8], data: &[u8], signature: &mut [u8]) -> Result<(),
     }
 }
 
-/// Verify that the HMAC for the data block matches the supplied signature
+/// Verify that the HMAC for the data block matches the supplied signature. The comparison takes
+/// constant time so it cannot be used as a timing oracle to forge a signature.
 pub fn verify_hmac_sha256(key: &[u8], data: &[u8], signature: &[u8]) -> bool {
-    if signature.len() != SHA256_SIZE {
-        false
-    } else {
-        let mut tmp_signature = [0u8; SHA256_SIZE];
-        if hmac_sha256(key, data, &mut tmp_signature).is_err() {
-            false
-        } else {
-            openssl::memcmp::eq(signature, &tmp_signature[..])
-        }
-    }
+    verify_hmac(hash::MessageDigest::sha256(), key, data, signature)
+}
+
+/// Both HMAC verifications go through here so the expected signature, which is as good as a forged
+/// one to an attacker, is only compared by `constant_time_eq` and is zeroed afterwards.
+fn verify_hmac(digest: hash::MessageDigest, key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+    let expected = hmac_vec(digest, key, data);
+    constant_time_eq(signature, &expected)
 }
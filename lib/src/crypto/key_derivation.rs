@@ -0,0 +1,104 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+//! Key derivation for secure channels. OPC UA Part 6 6.7.5 derives the keys that secure each
+//! direction of a channel from the nonces exchanged by OpenSecureChannel, using the P_SHA1 or
+//! P_SHA256 pseudo random function of the security policy.
+
+use openssl::hash::MessageDigest;
+
+use crate::types::status_code::StatusCode;
+
+use super::{aeskey::AesKey, hash, secret::SecretBytes, security_policy::SecurityPolicy};
+
+/// Derives `length` bytes of key material from a secret and seed with the `P_SHA` pseudo random
+/// function of the digest, i.e. P_SHA1 for `MessageDigest::sha1()` and P_SHA256 for
+/// `MessageDigest::sha256()`.
+///
+/// The HMAC rounds `A(i)` are computed until `length` bytes are produced, as described by
+/// `hash::p_sha()`. The key material is zeroed when it is dropped.
+pub fn derive_keys(
+    secret: &[u8],
+    seed: &[u8],
+    length: usize,
+    digest: MessageDigest,
+) -> SecretBytes {
+    hash::p_sha(digest, secret, seed, length)
+}
+
+/// The keys that secure the messages travelling in one direction of a secure channel.
+///
+/// Each side of a channel holds two sets. Following Part 6 Table 33, the keys for messages a side
+/// sends (its local keys) are derived with the remote nonce as the secret and the local nonce as
+/// the seed, while the keys for messages it receives (its remote keys) are derived with the local
+/// nonce as the secret and the remote nonce as the seed. So the client's local keys are the
+/// server's remote keys and vice versa.
+///
+/// All of the key material is zeroed when the keys are dropped.
+#[derive(Debug)]
+pub struct DerivedKeys {
+    pub signing_key: SecretBytes,
+    pub encrypting_key: AesKey,
+    pub initialization_vector: SecretBytes,
+}
+
+impl DerivedKeys {
+    /// Derives the keys for one direction from a secret and seed with the key derivation function
+    /// of the security policy, slicing the signing key, encrypting key and initialization vector
+    /// out of the derived bytes according to the policy's key sizes.
+    ///
+    /// An error is returned if the policy has no keys to derive.
+    pub fn derive(
+        policy: SecurityPolicy,
+        secret: &[u8],
+        seed: &[u8],
+    ) -> Result<DerivedKeys, StatusCode> {
+        let digest = policy.key_derivation_digest().ok_or_else(|| {
+            error!("Keys cannot be derived for security policy {}", policy);
+            StatusCode::BadSecurityPolicyRejected
+        })?;
+        let (signing_key_length, encrypting_key_length, encrypting_block_size) =
+            policy.derived_key_lengths();
+        let length = signing_key_length + encrypting_key_length + encrypting_block_size;
+        split_derived(&derive_keys(secret, seed, length, digest), policy)
+    }
+}
+
+/// Splits the output of the key derivation function into the signing key, encrypting key and
+/// initialization vector for one direction. The lengths of each come from the security policy and
+/// they occur in that order at the start of the bytes. Any bytes beyond the last key are ignored.
+///
+/// An error is returned if the policy has no keys to derive or the input is too short.
+pub fn split_derived(bytes: &[u8], policy: SecurityPolicy) -> Result<DerivedKeys, StatusCode> {
+    match policy {
+        SecurityPolicy::Unknown | SecurityPolicy::None => {
+            error!("Keys cannot be derived for security policy {}", policy);
+            Err(StatusCode::BadSecurityPolicyRejected)
+        }
+        _ => {
+            let (signing_key_length, encrypting_key_length, encrypting_block_size) =
+                policy.derived_key_lengths();
+            let encrypting_key_end = signing_key_length + encrypting_key_length;
+            let iv_end = encrypting_key_end + encrypting_block_size;
+            if bytes.len() < iv_end {
+                error!(
+                    "Derived key data is {} bytes but {} bytes are needed for security policy {}",
+                    bytes.len(),
+                    iv_end,
+                    policy
+                );
+                Err(StatusCode::BadInvalidArgument)
+            } else {
+                Ok(DerivedKeys {
+                    signing_key: SecretBytes::from(&bytes[..signing_key_length]),
+                    encrypting_key: AesKey::new(
+                        policy,
+                        &bytes[signing_key_length..encrypting_key_end],
+                    ),
+                    initialization_vector: SecretBytes::from(&bytes[encrypting_key_end..iv_end]),
+                })
+            }
+        }
+    }
+}
@@ -10,8 +10,8 @@ use std::fmt;
 
 use crate::types::{service_types::SignatureData, status_code::StatusCode, ByteString, UAString};
 pub use {
-    aeskey::*, certificate_store::*, hash::*, pkey::*, security_policy::*, thumbprint::*,
-    user_identity::*, x509::*,
+    aeskey::*, certificate_store::*, error::*, hash::*, key_derivation::*, pkey::*, secret::*,
+    security_policy::*, thumbprint::*, user_identity::*, x509::*,
 };
 
 #[cfg(test)]
@@ -19,9 +19,12 @@ mod tests;
 
 pub mod aeskey;
 pub mod certificate_store;
+pub mod error;
 pub mod hash;
+pub mod key_derivation;
 pub mod pkey;
 pub mod random;
+pub mod secret;
 pub mod security_policy;
 pub mod thumbprint;
 pub mod user_identity;
@@ -81,6 +84,39 @@ fn concat_data_and_nonce(data: &[u8], nonce: &[u8]) -> Vec<u8> {
     buffer
 }
 
+/// Signs the certificate followed by the nonce with the asymmetric signature algorithm of the
+/// security policy. The certificate is signed exactly as supplied, which may be a chain of
+/// certificates rather than just one. Under the `None` security policy, or when there is no
+/// certificate or nonce to sign, the signature data is null.
+fn sign_certificate_and_nonce(
+    signing_key: &PrivateKey,
+    security_policy: SecurityPolicy,
+    certificate: &[u8],
+    nonce: &[u8],
+) -> Result<SignatureData, CryptoError> {
+    let signature_data = match security_policy {
+        SecurityPolicy::None => SignatureData::null(),
+        SecurityPolicy::Unknown => {
+            error!("Signature creation failed, the security policy is unknown");
+            return Err(CryptoError::UnsupportedSecurityPolicy);
+        }
+        _ if certificate.is_empty() || nonce.is_empty() => SignatureData::null(),
+        security_policy => {
+            let data = concat_data_and_nonce(certificate, nonce);
+            let mut signature = vec![0u8; signing_key.size()];
+            let _ = security_policy
+                .asymmetric_sign(signing_key, &data, &mut signature)
+                .map_err(|_| CryptoError::SigningFailed)?;
+            SignatureData {
+                algorithm: UAString::from(security_policy.asymmetric_signature_algorithm()),
+                signature: ByteString::from(&signature),
+            }
+        }
+    };
+    trace!("Creating signature data = {:?}", signature_data);
+    Ok(signature_data)
+}
+
 /// Creates a `SignatureData` object by signing the supplied certificate and nonce with a pkey
 pub fn create_signature_data(
     signing_key: &PrivateKey,
@@ -88,44 +124,102 @@ pub fn create_signature_data(
     contained_cert: &ByteString,
     nonce: &ByteString,
 ) -> Result<SignatureData, StatusCode> {
-    // TODO this function should be refactored to return an error if the contained cert or nonce is incorrect, not a blank signature. That
-    //  very much depends on reading the spec to see what should happen if its not possible to create a signature, e.g. because
-    //  policy is None.
+    Ok(sign_certificate_and_nonce(
+        signing_key,
+        security_policy,
+        contained_cert.as_ref(),
+        nonce.as_ref(),
+    )?)
+}
+
+/// Creates the server signature of a `CreateSessionResponse`. The server proves it holds the private
+/// key of its certificate by signing the client's certificate and nonce with it.
+pub fn create_server_signature(
+    signing_key: &PrivateKey,
+    client_certificate: &ByteString,
+    client_nonce: &[u8],
+    security_policy: SecurityPolicy,
+) -> Result<SignatureData, StatusCode> {
+    Ok(sign_certificate_and_nonce(
+        signing_key,
+        security_policy,
+        client_certificate.as_ref(),
+        client_nonce,
+    )?)
+}
 
-    let (algorithm, signature) = if contained_cert.is_null() || nonce.is_null() {
-        (UAString::null(), ByteString::null())
+/// Verifies the server signature of a `CreateSessionResponse` was produced by the server's cert from
+/// the client certificate and nonce in the request. A signature that does not verify yields
+/// `BadApplicationSignatureInvalid`.
+pub fn verify_server_signature(
+    signature: &SignatureData,
+    security_policy: SecurityPolicy,
+    server_cert: &X509,
+    client_certificate: &ByteString,
+    client_nonce: &[u8],
+) -> StatusCode {
+    if security_policy == SecurityPolicy::None {
+        StatusCode::Good
+    } else if signature.signature.is_null() {
+        error!("Server did not supply a signature");
+        StatusCode::BadApplicationSignatureInvalid
     } else {
-        let data = concat_data_and_nonce(contained_cert.as_ref(), nonce.as_ref());
-        // Sign the bytes and return the algorithm, signature
-        match security_policy {
-            SecurityPolicy::None => (UAString::null(), ByteString::null()),
-            SecurityPolicy::Unknown => {
-                error!(
-                    "An unknown security policy was passed to create_signature_data and rejected"
-                );
-                (UAString::null(), ByteString::null())
-            }
-            security_policy => {
-                let signing_key_size = signing_key.size();
-                let mut signature = vec![0u8; signing_key_size];
-                let _ = security_policy.asymmetric_sign(signing_key, &data, &mut signature)?;
-                (
-                    UAString::from(security_policy.asymmetric_signature_algorithm()),
-                    ByteString::from(&signature),
-                )
-            }
+        let data = concat_data_and_nonce(client_certificate.as_ref(), client_nonce);
+        if verify_signature(signature, security_policy, server_cert, &data).is_good() {
+            StatusCode::Good
+        } else {
+            StatusCode::BadApplicationSignatureInvalid
         }
-    };
-    let signature_data = SignatureData {
-        algorithm,
-        signature,
-    };
-    trace!("Creating signature contained_cert = {:?}", signature_data);
-    Ok(signature_data)
+    }
+}
+
+/// Creates the client signature of an `ActivateSessionRequest`. The client proves it holds the private
+/// key of its certificate by signing the server's certificate and nonce with it. The server certificate
+/// is signed exactly as the server sent it, which may be a chain of certificates rather than just one.
+pub fn create_client_signature(
+    signing_key: &PrivateKey,
+    server_certificate: &ByteString,
+    server_nonce: &[u8],
+    security_policy: SecurityPolicy,
+) -> Result<SignatureData, StatusCode> {
+    Ok(sign_certificate_and_nonce(
+        signing_key,
+        security_policy,
+        server_certificate.as_ref(),
+        server_nonce,
+    )?)
+}
+
+/// Verifies the client signature of an `ActivateSessionRequest` was produced by the client's cert from
+/// the server certificate, as sent to the client, and the server nonce. A signature that does not verify
+/// yields `BadApplicationSignatureInvalid`.
+pub fn verify_client_signature(
+    signature: &SignatureData,
+    security_policy: SecurityPolicy,
+    client_cert: &X509,
+    server_certificate: &ByteString,
+    server_nonce: &[u8],
+) -> StatusCode {
+    if security_policy == SecurityPolicy::None {
+        StatusCode::Good
+    } else if signature.signature.is_null() {
+        error!("Client did not supply a signature");
+        StatusCode::BadApplicationSignatureInvalid
+    } else {
+        let data = concat_data_and_nonce(server_certificate.as_ref(), server_nonce);
+        if verify_signature(signature, security_policy, client_cert, &data).is_good() {
+            StatusCode::Good
+        } else {
+            StatusCode::BadApplicationSignatureInvalid
+        }
+    }
 }
 
 /// Verifies that the supplied signature data was produced by the signing cert. The contained cert and nonce are supplied so
 /// the signature can be verified against the expected data.
+///
+/// Under the `None` security policy there is nothing to verify and the signature data is expected to be null, so the result
+/// is `Good`. Under any other policy a null signature yields `BadApplicationSignatureInvalid`.
 pub fn verify_signature_data(
     signature: &SignatureData,
     security_policy: SecurityPolicy,
@@ -133,25 +227,101 @@ pub fn verify_signature_data(
     contained_cert: &X509,
     contained_nonce: &[u8],
 ) -> StatusCode {
-    if let Ok(verification_key) = signing_cert.public_key() {
+    if security_policy == SecurityPolicy::None {
+        StatusCode::Good
+    } else if signature.signature.is_null() {
+        error!("Signature verification failed, no signature was supplied");
+        StatusCode::BadApplicationSignatureInvalid
+    } else {
         // This is the data that the should have been signed
-        let contained_cert = contained_cert.as_byte_string();
-        let data = concat_data_and_nonce(contained_cert.as_ref(), contained_nonce);
+        match contained_cert.as_byte_string() {
+            Ok(contained_cert) => {
+                let data = concat_data_and_nonce(contained_cert.as_ref(), contained_nonce);
+                verify_signature(signature, security_policy, signing_cert, &data)
+            }
+            Err(_) => StatusCode::BadCertificateInvalid,
+        }
+    }
+}
 
+impl SignatureData {
+    /// Creates the signature data that proves possession of the signing key, as a `CreateSessionResponse`
+    /// or `ActivateSessionRequest` does, by signing the peer's cert followed by its nonce with the
+    /// asymmetric signature algorithm of the security policy. Under the `None` security policy the
+    /// signature data is null, and an `Unknown` security policy is rejected.
+    pub fn sign(
+        signing_key: &PrivateKey,
+        cert: &X509,
+        nonce: &[u8],
+        security_policy: SecurityPolicy,
+    ) -> Result<SignatureData, CryptoError> {
+        let cert = cert.as_byte_string()?;
+        sign_certificate_and_nonce(signing_key, security_policy, cert.as_ref(), nonce)
+    }
+
+    /// Verifies the signature data was produced by the signing cert over the cert followed by the
+    /// nonce. The algorithm named in the signature data must be the one the security policy
+    /// dictates rather than whichever one the signer chose, so a signature with a weaker digest
+    /// is rejected with `CryptoError::InvalidSignature`.
+    ///
+    /// Under the `None` security policy there is nothing to verify and the result is `Ok`.
+    pub fn verify(
+        &self,
+        security_policy: SecurityPolicy,
+        signing_cert: &X509,
+        cert: &X509,
+        nonce: &[u8],
+    ) -> Result<(), CryptoError> {
+        match security_policy {
+            SecurityPolicy::None => Ok(()),
+            SecurityPolicy::Unknown => {
+                error!("Signature verification failed, the security policy is unknown");
+                Err(CryptoError::UnsupportedSecurityPolicy)
+            }
+            security_policy => {
+                let expected_algorithm = security_policy.asymmetric_signature_algorithm();
+                if self.algorithm.as_ref() != expected_algorithm {
+                    error!(
+                        "Signature verification failed, algorithm {} is not the expected {}",
+                        self.algorithm, expected_algorithm
+                    );
+                    return Err(CryptoError::InvalidSignature);
+                }
+                let cert = cert.as_byte_string()?;
+                let data = concat_data_and_nonce(cert.as_ref(), nonce);
+                let verification_key = signing_cert.public_key()?;
+                security_policy
+                    .asymmetric_verify_signature(
+                        &verification_key,
+                        &data,
+                        self.signature.as_ref(),
+                        None,
+                    )
+                    .map_err(|_| CryptoError::InvalidSignature)
+            }
+        }
+    }
+}
+
+/// Verifies that the supplied signature data was produced by the signing cert over the data
+fn verify_signature(
+    signature: &SignatureData,
+    security_policy: SecurityPolicy,
+    signing_cert: &X509,
+    data: &[u8],
+) -> StatusCode {
+    if let Ok(verification_key) = signing_cert.public_key() {
         // Verify the signature
         let result = security_policy.asymmetric_verify_signature(
             &verification_key,
-            &data,
+            data,
             signature.signature.as_ref(),
             None,
         );
         match result {
             Ok(_) => StatusCode::Good,
             Err(result) => {
-                error!(
-                    "Client signature verification failed, status code = {}",
-                    result
-                );
+                error!("Signature verification failed, status code = {}", result);
                 result
             }
         }
@@ -8,15 +8,41 @@ use openssl::rand;
 
 use crate::types::byte_string::ByteString;
 
+use super::error::CryptoError;
+
 /// Fills the slice with cryptographically strong pseudo-random bytes
-pub fn bytes(bytes: &mut [u8]) {
-    let _ = rand::rand_bytes(bytes);
+///
+/// # Errors
+///
+/// `CryptoError::OpenSsl` if the random number generator fails, e.g. because it could not be
+/// seeded, in which case the slice must not be used.
+///
+pub fn bytes(bytes: &mut [u8]) -> Result<(), CryptoError> {
+    rand::rand_bytes(bytes).map_err(|err| {
+        error!("Cannot generate random bytes, err = {}", err);
+        CryptoError::from(err)
+    })
+}
+
+/// Creates a nonce of the requested length from cryptographically strong pseudo-random bytes
+///
+/// # Errors
+///
+/// `CryptoError::OpenSsl` if the random number generator fails.
+///
+pub fn nonce(len: usize) -> Result<Vec<u8>, CryptoError> {
+    let mut nonce = vec![0u8; len];
+    bytes(&mut nonce)?;
+    Ok(nonce)
 }
 
 /// Create a byte string with a number of random characters. Can be used to create a nonce or
 /// a similar reason.
+///
+/// # Panics
+///
+/// Panics if the random number generator fails, since a predictable value must never be used.
+///
 pub fn byte_string(number_of_bytes: usize) -> ByteString {
-    let mut data = vec![0u8; number_of_bytes];
-    bytes(&mut data);
-    ByteString::from(data)
+    ByteString::from(nonce(number_of_bytes).expect("Random number generator failed"))
 }
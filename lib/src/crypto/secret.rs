@@ -0,0 +1,133 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+//! Holder for secret key material.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+/// Zeroes the bytes of the buffer it holds when it is dropped. `SecretBytes` holds its bytes in one
+/// and it is also used for scratch buffers holding key material, e.g. the intermediate values of
+/// the key derivation function.
+#[derive(Clone, Default)]
+pub struct ZeroOnDrop<B: AsMut<[u8]>>(B);
+
+impl<B: AsMut<[u8]>> ZeroOnDrop<B> {
+    pub fn new(buffer: B) -> ZeroOnDrop<B> {
+        ZeroOnDrop(buffer)
+    }
+}
+
+impl<B: AsMut<[u8]>> Drop for ZeroOnDrop<B> {
+    fn drop(&mut self) {
+        zeroize(self.0.as_mut());
+    }
+}
+
+impl<B: AsMut<[u8]>> Deref for ZeroOnDrop<B> {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        &self.0
+    }
+}
+
+impl<B: AsMut<[u8]>> DerefMut for ZeroOnDrop<B> {
+    fn deref_mut(&mut self) -> &mut B {
+        &mut self.0
+    }
+}
+
+/// Bytes of secret key material, e.g. one of the symmetric keys derived for a secure channel.
+///
+/// The bytes are overwritten with zeroes when the holder is dropped so they do not linger in freed
+/// memory. Holders compare in constant time so a comparison does not leak how much of a secret
+/// matched. The `Debug` output only shows the length.
+#[derive(Clone, Default)]
+pub struct SecretBytes {
+    value: ZeroOnDrop<Vec<u8>>,
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes({} bytes)", self.value.len())
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+impl AsRef<[u8]> for SecretBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+impl From<&[u8]> for SecretBytes {
+    fn from(value: &[u8]) -> Self {
+        SecretBytes {
+            value: ZeroOnDrop(value.to_vec()),
+        }
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(value: Vec<u8>) -> Self {
+        SecretBytes {
+            value: ZeroOnDrop(value),
+        }
+    }
+}
+
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(&self.value, &other.value)
+    }
+}
+
+impl Eq for SecretBytes {}
+
+impl PartialEq<[u8]> for SecretBytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        constant_time_eq(&self.value, other)
+    }
+}
+
+impl PartialEq<Vec<u8>> for SecretBytes {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        constant_time_eq(&self.value, other)
+    }
+}
+
+impl SecretBytes {
+    /// Overwrites the bytes with zeroes. The length is unchanged.
+    pub fn zeroize(&mut self) {
+        zeroize(&mut self.value);
+    }
+}
+
+/// Overwrites the bytes with zeroes in a way the compiler will not optimize away, even though
+/// nothing reads the bytes afterwards.
+pub fn zeroize(bytes: &mut [u8]) {
+    bytes.iter_mut().for_each(|b| {
+        // Safe because the pointer comes from a valid mutable reference
+        unsafe { std::ptr::write_volatile(b, 0) };
+    });
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Compares the bytes in constant time, e.g. a received signature against the expected one, so
+/// the time taken does not reveal how many leading bytes matched. Lengths are public knowledge
+/// so only the contents are compared in constant time.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && openssl::memcmp::eq(a, b)
+}
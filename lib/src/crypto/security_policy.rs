@@ -13,9 +13,13 @@ use crate::types::{constants, status_code::StatusCode, ByteString};
 
 use super::{
     aeskey::AesKey,
+    error::CryptoError,
     hash,
+    key_derivation::DerivedKeys,
     pkey::{KeySize, PrivateKey, PublicKey, RsaPadding},
-    random, SHA1_SIZE, SHA256_SIZE,
+    random,
+    secret::SecretBytes,
+    SHA1_SIZE, SHA256_SIZE,
 };
 
 // These are constants that govern the different encryption / signing modes for OPC UA. In some
@@ -360,6 +364,25 @@ impl SecurityPolicy {
         length / 8
     }
 
+    /// Returns the size in bytes of the derived symmetric key used to encrypt messages
+    pub fn derived_key_size(&self) -> usize {
+        self.derived_key_lengths().1
+    }
+
+    /// Returns the message digest that asymmetric signatures are computed over, or `None` if the
+    /// policy does not sign, i.e. it is `None` or `Unknown`.
+    pub fn asymmetric_signature_digest(&self) -> Option<openssl_hash::MessageDigest> {
+        match self {
+            SecurityPolicy::Basic128Rsa15 | SecurityPolicy::Basic256 => {
+                Some(openssl_hash::MessageDigest::sha1())
+            }
+            SecurityPolicy::Basic256Sha256
+            | SecurityPolicy::Aes128Sha256RsaOaep
+            | SecurityPolicy::Aes256Sha256RsaPss => Some(openssl_hash::MessageDigest::sha256()),
+            SecurityPolicy::None | SecurityPolicy::Unknown => None,
+        }
+    }
+
     /// Returns the min and max (inclusive) key length in bits
     pub fn min_max_asymmetric_keylength(&self) -> (usize, usize) {
         match self {
@@ -420,23 +443,18 @@ impl SecurityPolicy {
         }
     }
 
-    /// Pseudo random function is used as a key derivation algorithm. It creates pseudo random bytes
-    /// from a secret and seed specified by the parameters.
-    fn prf(&self, secret: &[u8], seed: &[u8], length: usize, offset: usize) -> Vec<u8> {
-        // P_SHA1 or P_SHA256
-        let message_digest = match self {
+    /// Returns the message digest of the pseudo random function that is used as the key
+    /// derivation algorithm, i.e. P_SHA1 or P_SHA256, or `None` if the policy derives no keys.
+    pub fn key_derivation_digest(&self) -> Option<openssl_hash::MessageDigest> {
+        match self {
             SecurityPolicy::Basic128Rsa15 | SecurityPolicy::Basic256 => {
-                openssl_hash::MessageDigest::sha1()
+                Some(openssl_hash::MessageDigest::sha1())
             }
             SecurityPolicy::Basic256Sha256
             | SecurityPolicy::Aes128Sha256RsaOaep
-            | SecurityPolicy::Aes256Sha256RsaPss => openssl_hash::MessageDigest::sha256(),
-            _ => {
-                panic!("Invalid policy");
-            }
-        };
-        let result = hash::p_sha(message_digest, secret, seed, offset + length);
-        result[offset..(offset + length)].to_vec()
+            | SecurityPolicy::Aes256Sha256RsaPss => Some(openssl_hash::MessageDigest::sha256()),
+            _ => None,
+        }
     }
 
     /// Part 6
@@ -478,8 +496,20 @@ impl SecurityPolicy {
         &self,
         secret: &[u8],
         seed: &[u8],
-    ) -> (Vec<u8>, AesKey, Vec<u8>) {
-        // Work out the length of stuff
+    ) -> (SecretBytes, AesKey, SecretBytes) {
+        // The keys are consecutive ranges of the same pseudo random sequence so produce it in one
+        // go and then carve it up.
+        let keys = DerivedKeys::derive(*self, secret, seed).expect("Invalid policy");
+        (
+            keys.signing_key,
+            keys.encrypting_key,
+            keys.initialization_vector,
+        )
+    }
+
+    /// Returns the lengths in bytes of the signing key, encrypting key and initialization vector
+    /// that are derived for each direction of a secure channel.
+    pub(crate) fn derived_key_lengths(&self) -> (usize, usize, usize) {
         let signing_key_length = self.derived_signature_key_size();
         let (encrypting_key_length, encrypting_block_size) = match self {
             SecurityPolicy::Basic128Rsa15 | SecurityPolicy::Aes128Sha256RsaOaep => (16, 16),
@@ -490,18 +520,11 @@ impl SecurityPolicy {
                 panic!("Invalid policy");
             }
         };
-
-        let signing_key = self.prf(secret, seed, signing_key_length, 0);
-        let encrypting_key = self.prf(secret, seed, encrypting_key_length, signing_key_length);
-        let encrypting_key = AesKey::new(*self, &encrypting_key);
-        let iv = self.prf(
-            secret,
-            seed,
+        (
+            signing_key_length,
+            encrypting_key_length,
             encrypting_block_size,
-            signing_key_length + encrypting_key_length,
-        );
-
-        (signing_key, encrypting_key, iv)
+        )
     }
 
     /// Produce a signature of the data using an asymmetric key. Stores the signature in the supplied
@@ -681,6 +704,7 @@ impl SecurityPolicy {
         dst: &mut [u8],
     ) -> Result<usize, StatusCode> {
         key.encrypt(src, iv, dst)
+            .map_err(|_| StatusCode::BadUnexpectedError)
     }
 
     /// Decrypts the supplied data using the supplied key storing the result in the destination.
@@ -692,5 +716,192 @@ impl SecurityPolicy {
         dst: &mut [u8],
     ) -> Result<usize, StatusCode> {
         key.decrypt(src, iv, dst)
+            .map_err(|_| StatusCode::BadUnexpectedError)
+    }
+}
+
+/// Returns the size of the cipher text that `sign_and_encrypt` produces for a body of the
+/// supplied size, i.e. the padded body plus the signature. A chunk's message header holds the size
+/// of the whole chunk and is signed, so this is how the size is known before signing.
+pub fn sign_and_encrypt_size(body_size: usize, policy: SecurityPolicy) -> usize {
+    let (padding_size, signature_size) = padding_and_signature_size(body_size, policy);
+    body_size + padding_size + 1 + signature_size
+}
+
+fn padding_and_signature_size(body_size: usize, policy: SecurityPolicy) -> (usize, usize) {
+    let block_size = policy.plain_block_size();
+    let signature_size = policy.symmetric_signature_size();
+    let unpadded_size = body_size + 1 + signature_size;
+    let padding_size = if unpadded_size % block_size != 0 {
+        block_size - (unpadded_size % block_size)
+    } else {
+        0
+    };
+    (padding_size, signature_size)
+}
+
+/// Signs and encrypts a message chunk with one direction's derived keys as the SignAndEncrypt
+/// mode of the security policy requires. The header is the message header and security header
+/// of the chunk, which are signed but not encrypted. The body is the sequence header and the
+/// body, which are signed and encrypted.
+///
+/// The body is padded to a multiple of the cipher block size and signed with an HMAC over the
+/// header, body and padding. The body, padding and signature are then encrypted together and
+/// returned, to follow the header in the chunk.
+///
+/// ```text
+/// Header | Body | Padding | PaddingSize | Signature
+/// <---------------- signed -------------->
+///          <--------------- encrypted ----------->
+/// ```
+///
+/// Each padding byte holds the padding size, as does the `PaddingSize` byte that follows them.
+/// The message size in the header must already be that of the whole chunk, i.e. the header plus
+/// `sign_and_encrypt_size()` of the body, or `InvalidLength` is returned since the signature
+/// would not match the chunk that is sent.
+pub fn sign_and_encrypt(
+    header: &[u8],
+    body: &[u8],
+    keys: &DerivedKeys,
+    policy: SecurityPolicy,
+) -> Result<Vec<u8>, CryptoError> {
+    expect_symmetric_policy(keys, policy)?;
+
+    let message_size = header.len() + sign_and_encrypt_size(body.len(), policy);
+    if header_message_size(header) != Some(message_size) {
+        error!(
+            "Header message size {:?} is not the chunk size {}",
+            header_message_size(header),
+            message_size
+        );
+        return Err(CryptoError::InvalidLength);
+    }
+
+    let (padding_size, signature_size) = padding_and_signature_size(body.len(), policy);
+
+    // The header is only signed, so it is dropped from the front before encrypting
+    let mut signed = Vec::with_capacity(message_size);
+    signed.extend_from_slice(header);
+    signed.extend_from_slice(body);
+    signed.resize(
+        header.len() + body.len() + padding_size + 1,
+        padding_size as u8,
+    );
+
+    let mut signature = vec![0u8; signature_size];
+    policy
+        .symmetric_sign(&keys.signing_key, &signed, &mut signature)
+        .map_err(|_| CryptoError::SigningFailed)?;
+    signed.extend_from_slice(&signature);
+
+    let plaintext = &signed[header.len()..];
+    let mut ciphertext = vec![0u8; plaintext.len() + policy.plain_block_size()];
+    let size =
+        keys.encrypting_key
+            .encrypt(plaintext, &keys.initialization_vector, &mut ciphertext)?;
+    ciphertext.truncate(size);
+    Ok(ciphertext)
+}
+
+/// Returns the message size held by a message header, which follows its 3 byte message type and
+/// 1 byte chunk type.
+fn header_message_size(header: &[u8]) -> Option<usize> {
+    header
+        .get(4..8)
+        .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize)
+}
+
+/// Decrypts a message chunk produced by `sign_and_encrypt`, verifies its signature over the
+/// header and the decrypted data and strips the padding, returning the body.
+///
+/// The signature is verified before the padding is inspected so that the padding of a forged
+/// message is never examined. A bad signature yields `InvalidSignature`, while bad padding or a
+/// ciphertext that is not a whole number of blocks yields `InvalidPadding`. Both map onto
+/// `BadSecurityChecksFailed`.
+pub fn decrypt_and_verify(
+    header: &[u8],
+    ciphertext: &[u8],
+    keys: &DerivedKeys,
+    policy: SecurityPolicy,
+) -> Result<Vec<u8>, CryptoError> {
+    expect_symmetric_policy(keys, policy)?;
+
+    let block_size = policy.plain_block_size();
+    let signature_size = policy.symmetric_signature_size();
+    if ciphertext.is_empty()
+        || ciphertext.len() % block_size != 0
+        || ciphertext.len() < signature_size + 1
+    {
+        error!(
+            "Cipher text of {} bytes cannot hold a padded and signed body",
+            ciphertext.len()
+        );
+        return Err(CryptoError::InvalidPadding);
+    }
+
+    // The header is signed along with the decrypted data so it goes in front of it
+    let mut signed = vec![0u8; header.len() + ciphertext.len() + block_size];
+    signed[..header.len()].copy_from_slice(header);
+    let size = keys.encrypting_key.decrypt(
+        ciphertext,
+        &keys.initialization_vector,
+        &mut signed[header.len()..],
+    )?;
+    signed.truncate(header.len() + size);
+
+    // Verify the signature over the header, body and padding
+    let signed_end = signed.len() - signature_size;
+    policy
+        .symmetric_verify_signature(
+            &keys.signing_key,
+            &signed[..signed_end],
+            &signed[signed_end..],
+        )
+        .map_err(|_| CryptoError::InvalidSignature)?;
+
+    // Strip the padding
+    let padding_size = signed[signed_end - 1] as usize;
+    if header.len() + padding_size + 1 > signed_end {
+        error!(
+            "Padding size {} exceeds the {} bytes of signed data",
+            padding_size,
+            signed_end - header.len()
+        );
+        return Err(CryptoError::InvalidPadding);
+    }
+    let body_end = signed_end - padding_size - 1;
+    if signed[body_end..signed_end]
+        .iter()
+        .any(|b| *b as usize != padding_size)
+    {
+        error!(
+            "Padding bytes do not all hold the padding size {}",
+            padding_size
+        );
+        return Err(CryptoError::InvalidPadding);
+    }
+    Ok(signed[header.len()..body_end].to_vec())
+}
+
+/// Ensures the policy is one that signs and encrypts with symmetric keys, and is the policy
+/// that the keys were derived for.
+fn expect_symmetric_policy(keys: &DerivedKeys, policy: SecurityPolicy) -> Result<(), CryptoError> {
+    match policy {
+        SecurityPolicy::Unknown | SecurityPolicy::None => {
+            error!(
+                "Security policy {} does not sign or encrypt messages",
+                policy
+            );
+            Err(CryptoError::UnsupportedSecurityPolicy)
+        }
+        _ if keys.encrypting_key.security_policy() != policy => {
+            error!(
+                "Keys were derived for security policy {} but are used with {}",
+                keys.encrypting_key.security_policy(),
+                policy
+            );
+            Err(CryptoError::UnsupportedSecurityPolicy)
+        }
+        _ => Ok(()),
     }
 }
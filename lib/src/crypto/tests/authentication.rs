@@ -1,6 +1,6 @@
 use crate::crypto::{
-    self as crypto, decrypt_user_identity_token_password, make_user_name_identity_token, random,
-    tests::*, SecurityPolicy,
+    self as crypto, decrypt_user_identity_token_password, make_user_name_identity_token,
+    make_x509_identity_token, random, tests::*, verify_x509_identity_token, SecurityPolicy,
 };
 
 #[test]
@@ -185,3 +185,169 @@ fn user_name_identity_token_encrypted() {
     let password1 = decrypt_user_identity_token_password(&token, nonce.as_ref(), &pkey).unwrap();
     assert_eq!(password, password1);
 }
+
+#[test]
+fn user_name_identity_token_nonce() {
+    let password = String::from("abcdef123456");
+    let nonce = random::byte_string(32);
+    let (cert, pkey) = make_test_cert_1024();
+    let cert = Some(cert);
+
+    let user_token_policy = crate::types::service_types::UserTokenPolicy {
+        policy_id: UAString::from("x"),
+        token_type: UserTokenType::UserName,
+        issued_token_type: UAString::null(),
+        issuer_endpoint_url: UAString::null(),
+        security_policy_uri: UAString::null(),
+    };
+    let make_token = |nonce: &[u8]| {
+        make_user_name_identity_token(
+            SecurityPolicy::Basic128Rsa15,
+            &user_token_policy,
+            nonce,
+            &cert,
+            "user1",
+            &password,
+        )
+        .unwrap()
+    };
+
+    // Token encrypted with the current server nonce is accepted
+    let token = make_token(nonce.as_ref());
+    assert_eq!(
+        decrypt_user_identity_token_password(&token, nonce.as_ref(), &pkey).unwrap(),
+        password
+    );
+
+    // Token encrypted with a stale nonce, e.g. replayed from an earlier activation, is rejected
+    let stale_nonce = random::byte_string(32);
+    let token = make_token(stale_nonce.as_ref());
+    assert_eq!(
+        decrypt_user_identity_token_password(&token, nonce.as_ref(), &pkey).unwrap_err(),
+        StatusCode::BadIdentityTokenRejected
+    );
+
+    // Token encrypted without a nonce is rejected
+    let token = make_token(&[]);
+    assert_eq!(
+        decrypt_user_identity_token_password(&token, nonce.as_ref(), &pkey).unwrap_err(),
+        StatusCode::BadIdentityTokenRejected
+    );
+}
+
+#[test]
+fn x509_identity_token() {
+    let nonce = random::byte_string(32);
+    let (server_cert, _) = make_test_cert_2048();
+    let (user_cert, user_pkey) = make_test_cert_2048();
+    let (_, other_pkey) = make_test_cert_2048();
+
+    let mut user_token_policy = crate::types::service_types::UserTokenPolicy {
+        policy_id: UAString::from("x509"),
+        token_type: UserTokenType::Certificate,
+        issued_token_type: UAString::null(),
+        issuer_endpoint_url: UAString::null(),
+        security_policy_uri: UAString::from(SecurityPolicy::Basic256Sha256.to_uri()),
+    };
+
+    // Token signed with the user cert's key verifies
+    let (token, signature) = make_x509_identity_token(
+        SecurityPolicy::None,
+        &user_token_policy,
+        &server_cert,
+        nonce.as_ref(),
+        &user_cert,
+        &user_pkey,
+    )
+    .unwrap();
+    assert_eq!(token.policy_id.as_ref(), "x509");
+    assert_eq!(token.certificate_data, user_cert.as_byte_string().unwrap());
+    assert_eq!(
+        signature.algorithm.as_ref(),
+        SecurityPolicy::Basic256Sha256.asymmetric_signature_algorithm()
+    );
+    assert!(verify_x509_identity_token(
+        &token,
+        &signature,
+        SecurityPolicy::Basic256Sha256,
+        &server_cert,
+        nonce.as_ref()
+    )
+    .is_ok());
+
+    // But not against another nonce or server cert
+    let stale_nonce = random::byte_string(32);
+    assert!(verify_x509_identity_token(
+        &token,
+        &signature,
+        SecurityPolicy::Basic256Sha256,
+        &server_cert,
+        stale_nonce.as_ref()
+    )
+    .is_err());
+    assert!(verify_x509_identity_token(
+        &token,
+        &signature,
+        SecurityPolicy::Basic256Sha256,
+        &user_cert,
+        nonce.as_ref()
+    )
+    .is_err());
+
+    // Token signed with a key that doesn't belong to the user cert fails
+    let (token, signature) = make_x509_identity_token(
+        SecurityPolicy::None,
+        &user_token_policy,
+        &server_cert,
+        nonce.as_ref(),
+        &user_cert,
+        &other_pkey,
+    )
+    .unwrap();
+    assert!(verify_x509_identity_token(
+        &token,
+        &signature,
+        SecurityPolicy::Basic256Sha256,
+        &server_cert,
+        nonce.as_ref()
+    )
+    .is_err());
+
+    // Without a security policy on the token policy, the channel's is used
+    user_token_policy.security_policy_uri = UAString::null();
+    let (token, signature) = make_x509_identity_token(
+        SecurityPolicy::Basic128Rsa15,
+        &user_token_policy,
+        &server_cert,
+        nonce.as_ref(),
+        &user_cert,
+        &user_pkey,
+    )
+    .unwrap();
+    assert_eq!(
+        signature.algorithm.as_ref(),
+        SecurityPolicy::Basic128Rsa15.asymmetric_signature_algorithm()
+    );
+    assert!(verify_x509_identity_token(
+        &token,
+        &signature,
+        SecurityPolicy::Basic128Rsa15,
+        &server_cert,
+        nonce.as_ref()
+    )
+    .is_ok());
+
+    // A token cannot be signed without security
+    assert_eq!(
+        make_x509_identity_token(
+            SecurityPolicy::None,
+            &user_token_policy,
+            &server_cert,
+            nonce.as_ref(),
+            &user_cert,
+            &user_pkey,
+        )
+        .unwrap_err(),
+        StatusCode::BadSecurityPolicyRejected
+    );
+}
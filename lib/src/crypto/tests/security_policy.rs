@@ -123,6 +123,91 @@ fn to_uri() {
     );
 }
 
+#[test]
+fn uri_roundtrip() {
+    [
+        SecurityPolicy::None,
+        SecurityPolicy::Basic128Rsa15,
+        SecurityPolicy::Basic256,
+        SecurityPolicy::Basic256Sha256,
+        SecurityPolicy::Aes128Sha256RsaOaep,
+        SecurityPolicy::Aes256Sha256RsaPss,
+    ]
+    .iter()
+    .for_each(|security_policy| {
+        let uri = security_policy.to_uri();
+        assert_eq!(SecurityPolicy::from_uri(uri), *security_policy);
+        assert_eq!(SecurityPolicy::from_str(uri).unwrap(), *security_policy);
+    });
+    assert_eq!(
+        SecurityPolicy::from_uri("http://opcfoundation.org/UA/SecurityPolicy#Basic128"),
+        SecurityPolicy::Unknown
+    );
+}
+
+#[test]
+fn algorithm_parameters() {
+    use openssl::hash::MessageDigest;
+
+    let security_policy = SecurityPolicy::Basic128Rsa15;
+    assert!(
+        security_policy
+            .asymmetric_signature_digest()
+            .unwrap()
+            .type_()
+            == MessageDigest::sha1().type_()
+    );
+    assert_eq!(security_policy.symmetric_signature_size(), 20);
+    assert_eq!(security_policy.derived_signature_key_size(), 16);
+    assert_eq!(security_policy.derived_key_size(), 16);
+    assert_eq!(security_policy.secure_channel_nonce_length(), 16);
+
+    let security_policy = SecurityPolicy::Basic256;
+    assert!(
+        security_policy
+            .asymmetric_signature_digest()
+            .unwrap()
+            .type_()
+            == MessageDigest::sha1().type_()
+    );
+    assert_eq!(security_policy.symmetric_signature_size(), 20);
+    assert_eq!(security_policy.derived_signature_key_size(), 24);
+    assert_eq!(security_policy.derived_key_size(), 32);
+    assert_eq!(security_policy.secure_channel_nonce_length(), 32);
+
+    let security_policy = SecurityPolicy::Basic256Sha256;
+    assert!(
+        security_policy
+            .asymmetric_signature_digest()
+            .unwrap()
+            .type_()
+            == MessageDigest::sha256().type_()
+    );
+    assert_eq!(security_policy.symmetric_signature_size(), 32);
+    assert_eq!(security_policy.derived_signature_key_size(), 32);
+    assert_eq!(security_policy.derived_key_size(), 32);
+    assert_eq!(security_policy.secure_channel_nonce_length(), 32);
+
+    let security_policy = SecurityPolicy::Aes128Sha256RsaOaep;
+    assert!(
+        security_policy
+            .asymmetric_signature_digest()
+            .unwrap()
+            .type_()
+            == MessageDigest::sha256().type_()
+    );
+    assert_eq!(security_policy.symmetric_signature_size(), 32);
+    assert_eq!(security_policy.derived_signature_key_size(), 32);
+    assert_eq!(security_policy.derived_key_size(), 16);
+    assert_eq!(security_policy.secure_channel_nonce_length(), 32);
+
+    // Policies that don't sign have no digest
+    assert!(SecurityPolicy::None.asymmetric_signature_digest().is_none());
+    assert!(SecurityPolicy::Unknown
+        .asymmetric_signature_digest()
+        .is_none());
+}
+
 #[test]
 fn is_valid_keylength() {
     assert!(SecurityPolicy::Basic128Rsa15.is_valid_keylength(1024));
@@ -5,16 +5,28 @@
 //! Functionality for holding a message digest.
 use crate::types::ByteString;
 
-/// The thumbprint holds a 20 byte representation of a certificate that can be used as a hash,
-/// handshake comparison, a filename hint or similar purpose where a shortened representation
-/// of a cert is required. Thumbprint size is dictated by the OPC UA spec
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+use super::secret::constant_time_eq;
+
+/// The thumbprint holds a digest of a certificate that can be used as a hash, handshake
+/// comparison, a filename hint or similar purpose where a shortened representation of a cert is
+/// required. It is normally the 20 byte SHA1 digest dictated by the OPC UA spec, but may be the
+/// 32 byte SHA256 digest.
+///
+/// Thumbprints are compared in constant time so that comparing a received thumbprint against a
+/// trusted one does not leak how much of it matched.
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Thumbprint {
-    /// Thumbprint is relatively small and fixed size, so use array to hold value instead of a vec
-    /// just to save heap
-    value: [u8; Thumbprint::THUMBPRINT_SIZE],
+    value: Vec<u8>,
 }
 
+impl PartialEq for Thumbprint {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(&self.value, &other.value)
+    }
+}
+
+impl Eq for Thumbprint {}
+
 impl Into<ByteString> for Thumbprint {
     fn into(self) -> ByteString {
         ByteString::from(&self.value)
@@ -22,16 +34,30 @@ impl Into<ByteString> for Thumbprint {
 }
 
 impl Thumbprint {
+    /// Size of a SHA1 thumbprint, which is what the spec uses
     pub const THUMBPRINT_SIZE: usize = 20;
+    /// Size of a SHA256 thumbprint
+    pub const THUMBPRINT_SHA256_SIZE: usize = 32;
 
     /// Constructs a thumbprint from a message digest which is expected to be the proper length
     pub fn new(digest: &[u8]) -> Thumbprint {
-        if digest.len() != Thumbprint::THUMBPRINT_SIZE {
+        if digest.len() != Thumbprint::THUMBPRINT_SIZE
+            && digest.len() != Thumbprint::THUMBPRINT_SHA256_SIZE
+        {
             panic!("Thumbprint is the wrong length, {}", digest.len());
         }
-        let mut value = [0u8; Thumbprint::THUMBPRINT_SIZE];
-        value.clone_from_slice(digest);
-        Thumbprint { value }
+        Thumbprint {
+            value: digest.to_vec(),
+        }
+    }
+
+    /// Tests if the byte string, e.g. a received `SenderCertificateThumbprint`, holds this
+    /// thumbprint. A null or differently sized byte string never matches.
+    pub fn matches_byte_string(&self, byte_string: &ByteString) -> bool {
+        match byte_string.value {
+            Some(ref value) => constant_time_eq(&self.value, value),
+            None => false,
+        }
     }
 
     pub fn as_byte_string(&self) -> ByteString {
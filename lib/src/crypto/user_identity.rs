@@ -10,6 +10,8 @@
 use std::io::{Cursor, Write};
 use std::str::FromStr;
 
+use chrono::Utc;
+
 use crate::types::{
     encoding::{read_u32, write_u32},
     service_types::{SignatureData, UserNameIdentityToken, UserTokenPolicy, X509IdentityToken},
@@ -152,6 +154,11 @@ pub fn legacy_password_encrypt(
 
 /// Decrypt the client's password using the server's nonce and private key. This function is prefixed
 /// "legacy" because 1.04 describes another way of encrypting passwords.
+///
+/// The client appends the server nonce to the password before encrypting it. The nonce is
+/// stripped from the decrypted password and must match the current server nonce, otherwise the
+/// token is a replay or was made for another session and is rejected with
+/// `BadIdentityTokenRejected`.
 pub fn legacy_password_decrypt(
     secret: &ByteString,
     server_nonce: &[u8],
@@ -175,10 +182,15 @@ pub fn legacy_password_decrypt(
         } else {
             let dst = dst.into_inner();
             let nonce_len = server_nonce.len();
+            if actual_size < 4 + nonce_len {
+                error!("Decrypted password is too short to contain the server nonce");
+                return Err(StatusCode::BadIdentityTokenRejected);
+            }
             let nonce_begin = actual_size - nonce_len;
             let nonce = &dst[nonce_begin..(nonce_begin + nonce_len)];
             if nonce != server_nonce {
-                Err(StatusCode::BadDecodingError)
+                error!("Decrypted password does not end with the server nonce");
+                Err(StatusCode::BadIdentityTokenRejected)
             } else {
                 let password = &dst[4..nonce_begin];
                 let password = String::from_utf8(password.to_vec())
@@ -189,7 +201,53 @@ pub fn legacy_password_decrypt(
     }
 }
 
-/// Verify that the X509 identity token supplied to a server contains a valid signature.
+/// Create a filled in X509IdentityToken holding the user's certificate, together with the user
+/// token signature that proves the user holds the certificate's private key. The signature is made
+/// with the user's key over the server's certificate and nonce, using the user token policy's
+/// security policy or the channel security policy when the token policy has none.
+///
+/// The security policy must be one that can sign, so `None` yields `BadSecurityPolicyRejected`.
+pub fn make_x509_identity_token(
+    channel_security_policy: SecurityPolicy,
+    user_token_policy: &UserTokenPolicy,
+    server_cert: &X509,
+    server_nonce: &[u8],
+    cert: &X509,
+    private_key: &PrivateKey,
+) -> Result<(X509IdentityToken, SignatureData), StatusCode> {
+    let security_policy = if user_token_policy.security_policy_uri.is_empty() {
+        channel_security_policy
+    } else {
+        SecurityPolicy::from_uri(user_token_policy.security_policy_uri.as_ref())
+    };
+    match security_policy {
+        SecurityPolicy::None | SecurityPolicy::Unknown => {
+            error!(
+                "An X509IdentityToken cannot be signed under the security policy {}",
+                security_policy
+            );
+            Err(StatusCode::BadSecurityPolicyRejected)
+        }
+        security_policy => {
+            let server_cert = server_cert.as_byte_string()?;
+            let user_token_signature = super::create_signature_data(
+                private_key,
+                security_policy,
+                &server_cert,
+                &ByteString::from(server_nonce),
+            )?;
+            let identity_token = X509IdentityToken {
+                policy_id: user_token_policy.policy_id.clone(),
+                certificate_data: cert.as_byte_string()?,
+            };
+            Ok((identity_token, user_token_signature))
+        }
+    }
+}
+
+/// Verify that the X509 identity token supplied to a server holds a certificate that is valid for
+/// the current time and a valid signature. An expired or not yet valid certificate yields
+/// `BadIdentityTokenRejected`.
 pub fn verify_x509_identity_token(
     token: &X509IdentityToken,
     user_token_signature: &SignatureData,
@@ -210,6 +268,14 @@ pub fn verify_x509_identity_token(
     // if the spec actually said this.
 
     let signing_cert = super::x509::X509::from_byte_string(&token.certificate_data)?;
+    let result = signing_cert.is_time_valid(&Utc::now());
+    if result.is_bad() {
+        error!(
+            "X509IdentityToken certificate is not valid for the current time, result = {}",
+            result
+        );
+        return Err(StatusCode::BadIdentityTokenRejected);
+    }
     let result = super::verify_signature_data(
         user_token_signature,
         security_policy,
@@ -7,7 +7,7 @@
 use std::{
     self,
     collections::HashSet,
-    fmt::{self, Debug, Formatter},
+    fmt::{Debug, Formatter},
     net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
     result::Result,
 };
@@ -15,6 +15,7 @@ use std::{
 use chrono::{DateTime, TimeZone, Utc};
 use openssl::{
     asn1::*,
+    error::ErrorStack,
     hash,
     nid::Nid,
     pkey,
@@ -25,6 +26,7 @@ use openssl::{
 use crate::types::{service_types::ApplicationDescription, status_code::StatusCode, ByteString};
 
 use super::{
+    error::CryptoError,
     hostname,
     pkey::{PrivateKey, PublicKey},
     thumbprint::Thumbprint,
@@ -43,8 +45,11 @@ pub struct X509Data {
     pub organizational_unit: String,
     pub country: String,
     pub state: String,
-    /// A list of alternate host names as text. The first entry is expected to be the application uri.
-    /// The remainder are treated as IP addresses or DNS names depending on whether they parse as IPv4, IPv6 or neither.
+    /// The application uri, which is written to the subject alt names as a URI. If it is empty, the
+    /// first of the alt host names is taken to be the application uri instead.
+    pub application_uri: String,
+    /// A list of alternate host names as text. Entries other than the application uri are treated
+    /// as IP addresses or DNS names depending on whether they parse as IPv4, IPv6 or neither.
     /// IP addresses are expected to be in their canonical form and you will run into trouble
     /// especially in IPv6 if they are not because string comparison may be used during validation.
     /// e.g. IPv6 canonical format shortens addresses by stripping leading zeros, sequences of zeros
@@ -61,6 +66,7 @@ impl From<(ApplicationDescription, Option<Vec<String>>)> for X509Data {
         let alt_host_names = Self::alt_host_names(application_uri, addresses, false, true, true);
         X509Data {
             key_size: DEFAULT_KEYSIZE,
+            application_uri: application_uri.to_string(),
             common_name: application_description.application_name.to_string(),
             organization: application_description.application_name.to_string(),
             organizational_unit: application_description.application_name.to_string(),
@@ -163,9 +169,11 @@ impl X509Data {
 
     /// Creates a sample certificate for testing, sample purposes only
     pub fn sample_cert() -> X509Data {
-        let alt_host_names = Self::alt_host_names("urn:OPCUADemo", None, false, true, true);
+        let application_uri = "urn:OPCUADemo";
+        let alt_host_names = Self::alt_host_names(application_uri, None, false, true, true);
         X509Data {
             key_size: 2048,
+            application_uri: application_uri.to_string(),
             common_name: "OPC UA Demo Key".to_string(),
             organization: "OPC UA for Rust".to_string(),
             organizational_unit: "OPC UA for Rust".to_string(),
@@ -175,19 +183,31 @@ impl X509Data {
             certificate_duration_days: 365,
         }
     }
-}
 
-#[derive(Debug)]
-pub struct X509Error;
+    /// Returns the application uri the cert is made for, i.e. `application_uri` if it is set,
+    /// otherwise the first alt host name.
+    pub fn application_uri(&self) -> Option<&str> {
+        if !self.application_uri.is_empty() {
+            Some(self.application_uri.as_str())
+        } else {
+            self.alt_host_names
+                .first()
+                .map(|s| s.as_str())
+                .filter(|s| !s.is_empty())
+        }
+    }
 
-impl fmt::Display for X509Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "X509Error")
+    /// Creates a self-signed application instance certificate and a matching private key from
+    /// this data. The cert is valid from now for `certificate_duration_days` and its subject alt
+    /// names hold the application uri followed by the alt host names.
+    pub fn create_self_signed(&self) -> Result<(X509, PrivateKey), CryptoError> {
+        let rsa = Rsa::generate(self.key_size)?;
+        let pkey = PrivateKey::wrap_private_key(pkey::PKey::from_rsa(rsa)?);
+        let cert = X509::build_self_signed(&pkey, self)?;
+        Ok((cert, pkey))
     }
 }
 
-impl std::error::Error for X509Error {}
-
 /// This is a wrapper around the `OpenSSL` `X509` cert
 #[derive(Clone)]
 pub struct X509 {
@@ -209,10 +229,13 @@ impl From<x509::X509> for X509 {
 }
 
 impl X509 {
-    pub fn from_der(der: &[u8]) -> Result<Self, X509Error> {
-        x509::X509::from_der(der).map(X509::from).map_err(|_| {
-            error!("Cannot produce an x509 cert from the data supplied");
-            X509Error
+    pub fn from_der(der: &[u8]) -> Result<Self, CryptoError> {
+        x509::X509::from_der(der).map(X509::from).map_err(|err| {
+            error!(
+                "Cannot produce an x509 cert from the data supplied, err = {}",
+                err
+            );
+            CryptoError::InvalidDer
         })
     }
 
@@ -226,16 +249,19 @@ impl X509 {
     ///
     /// In particular, application instance cert requires subjectAltName to specify alternate
     /// hostnames / ip addresses that the host runs on.
-    pub fn cert_and_pkey(x509_data: &X509Data) -> Result<(Self, PrivateKey), String> {
+    pub fn cert_and_pkey(x509_data: &X509Data) -> Result<(Self, PrivateKey), CryptoError> {
         // Create a key pair
         let rsa = Rsa::generate(x509_data.key_size).map_err(|err| {
-            format!(
+            error!(
                 "Cannot create key pair check error {} and key size {}",
                 err, x509_data.key_size
-            )
+            );
+            CryptoError::OpenSsl(err)
+        })?;
+        let pkey = pkey::PKey::from_rsa(rsa).map_err(|err| {
+            error!("Cannot create key pair check error {}", err);
+            CryptoError::OpenSsl(err)
         })?;
-        let pkey = pkey::PKey::from_rsa(rsa)
-            .map_err(|err| format!("Cannot create key pair check error {}", err))?;
         let pkey = PrivateKey::wrap_private_key(pkey);
 
         // Create an X509 cert to hold the public key
@@ -244,30 +270,35 @@ impl X509 {
         Ok((cert, pkey))
     }
 
-    pub fn from_pkey(pkey: &PrivateKey, x509_data: &X509Data) -> Result<Self, String> {
-        let mut builder = x509::X509Builder::new().unwrap();
+    pub fn from_pkey(pkey: &PrivateKey, x509_data: &X509Data) -> Result<Self, CryptoError> {
+        Self::build_self_signed(pkey, x509_data).map_err(|err| {
+            error!("Cannot create certificate, error {}", err);
+            CryptoError::OpenSsl(err)
+        })
+    }
+
+    /// Builds a self-signed X509v3 Application Instance Certificate holding the public key.
+    fn build_self_signed(pkey: &PrivateKey, x509_data: &X509Data) -> Result<Self, ErrorStack> {
+        let mut builder = x509::X509Builder::new()?;
         // value 2 == version 3 (go figure)
-        let _ = builder.set_version(2);
+        builder.set_version(2)?;
         let issuer_name = {
-            let mut name = x509::X509NameBuilder::new().unwrap();
+            let mut name = x509::X509NameBuilder::new()?;
             // Common name
-            name.append_entry_by_text("CN", &x509_data.common_name)
-                .unwrap();
+            name.append_entry_by_text("CN", &x509_data.common_name)?;
             // Organization
-            name.append_entry_by_text("O", &x509_data.organization)
-                .unwrap();
+            name.append_entry_by_text("O", &x509_data.organization)?;
             // Organizational Unit
-            name.append_entry_by_text("OU", &x509_data.organizational_unit)
-                .unwrap();
+            name.append_entry_by_text("OU", &x509_data.organizational_unit)?;
             // Country
-            name.append_entry_by_text("C", &x509_data.country).unwrap();
+            name.append_entry_by_text("C", &x509_data.country)?;
             // State
-            name.append_entry_by_text("ST", &x509_data.state).unwrap();
+            name.append_entry_by_text("ST", &x509_data.state)?;
             name.build()
         };
         // Issuer and subject shall be the same for self-signed cert
-        let _ = builder.set_subject_name(&issuer_name);
-        let _ = builder.set_issuer_name(&issuer_name);
+        builder.set_subject_name(&issuer_name)?;
+        builder.set_issuer_name(&issuer_name)?;
 
         // For Application Instance Certificate specifies how cert may be used
         let key_usage = KeyUsage::new()
@@ -276,107 +307,124 @@ impl X509 {
             .key_encipherment()
             .data_encipherment()
             .key_cert_sign()
-            .build()
-            .unwrap();
-        let _ = builder.append_extension(key_usage);
+            .build()?;
+        builder.append_extension(key_usage)?;
         let extended_key_usage = ExtendedKeyUsage::new()
             .client_auth()
             .server_auth()
-            .build()
-            .unwrap();
-        let _ = builder.append_extension(extended_key_usage);
-
-        builder
-            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
-            .unwrap();
-        builder
-            .set_not_after(&Asn1Time::days_from_now(x509_data.certificate_duration_days).unwrap())
-            .unwrap();
-        builder.set_pubkey(&pkey.value).unwrap();
+            .build()?;
+        builder.append_extension(extended_key_usage)?;
+
+        builder.set_not_before(&Asn1Time::days_from_now(0)?)?;
+        builder.set_not_after(&Asn1Time::days_from_now(
+            x509_data.certificate_duration_days,
+        )?)?;
+        builder.set_pubkey(&pkey.value)?;
 
         // Random serial number
         {
             use openssl::bn::BigNum;
             use openssl::bn::MsbOption;
-            let mut serial = BigNum::new().unwrap();
-            serial.rand(128, MsbOption::MAYBE_ZERO, false).unwrap();
-            let serial = serial.to_asn1_integer().unwrap();
-            let _ = builder.set_serial_number(&serial);
+            let mut serial = BigNum::new()?;
+            serial.rand(128, MsbOption::MAYBE_ZERO, false)?;
+            let serial = serial.to_asn1_integer()?;
+            builder.set_serial_number(&serial)?;
         }
 
-        // Subject alt names - The first is assumed to be the application uri. The remainder
-        // are either IP or DNS entries.
-        if !x509_data.alt_host_names.is_empty() {
+        // Subject alt names - The application uri followed by the alt host names as IP or DNS
+        // entries.
+        let application_uri = x509_data.application_uri();
+        if application_uri.is_some() || !x509_data.alt_host_names.is_empty() {
             let subject_alternative_name = {
                 let mut subject_alternative_name = SubjectAlternativeName::new();
+                if let Some(application_uri) = application_uri {
+                    subject_alternative_name.uri(application_uri);
+                }
                 x509_data
                     .alt_host_names
                     .iter()
-                    .enumerate()
-                    .for_each(|(i, alt_host_name)| {
-                        if !alt_host_name.is_empty() {
-                            if i == 0 {
-                                // The first entry is the application uri
-                                subject_alternative_name.uri(alt_host_name);
-                            } else if alt_host_name.parse::<Ipv4Addr>().is_ok()
-                                || alt_host_name.parse::<Ipv6Addr>().is_ok()
-                            {
-                                // Treat this as an IPv4/IPv6 address
-                                subject_alternative_name.ip(alt_host_name);
-                            } else {
-                                // Treat this as a DNS entry
-                                subject_alternative_name.dns(alt_host_name);
-                            }
+                    .filter(|alt_host_name| {
+                        !alt_host_name.is_empty() && Some(alt_host_name.as_str()) != application_uri
+                    })
+                    .for_each(|alt_host_name| {
+                        if alt_host_name.parse::<Ipv4Addr>().is_ok()
+                            || alt_host_name.parse::<Ipv6Addr>().is_ok()
+                        {
+                            // Treat this as an IPv4/IPv6 address
+                            subject_alternative_name.ip(alt_host_name);
+                        } else {
+                            // Treat this as a DNS entry
+                            subject_alternative_name.dns(alt_host_name);
                         }
                     });
-                subject_alternative_name
-                    .build(&builder.x509v3_context(None, None))
-                    .unwrap()
+                subject_alternative_name.build(&builder.x509v3_context(None, None))?
             };
-            builder.append_extension(subject_alternative_name).unwrap();
+            builder.append_extension(subject_alternative_name)?;
         }
 
         // Self-sign
-        let _ = builder.sign(&pkey.value, hash::MessageDigest::sha256());
+        builder.sign(&pkey.value, hash::MessageDigest::sha256())?;
 
         Ok(X509::from(builder.build()))
     }
 
-    pub fn from_byte_string(data: &ByteString) -> Result<X509, StatusCode> {
-        if data.is_null() {
-            error!("Cannot make certificate from null bytestring");
-            Err(StatusCode::BadCertificateInvalid)
-        } else if let Ok(cert) = x509::X509::from_der(data.value.as_ref().unwrap()) {
-            Ok(X509::from(cert))
+    pub fn from_byte_string(data: &ByteString) -> Result<X509, CryptoError> {
+        if let Some(ref der) = data.value {
+            x509::X509::from_der(der).map(X509::from).map_err(|_| {
+                error!("Cannot make certificate, does bytestring contain .der?");
+                CryptoError::InvalidDer
+            })
         } else {
-            error!("Cannot make certificate, does bytestring contain .der?");
-            Err(StatusCode::BadCertificateInvalid)
+            error!("Cannot make certificate from null bytestring");
+            Err(CryptoError::NullByteString)
         }
     }
 
+    /// Creates a certificate from PEM encoded data, i.e. a `-----BEGIN CERTIFICATE-----` block.
+    pub fn from_pem(pem: &[u8]) -> Result<X509, CryptoError> {
+        x509::X509::from_pem(pem).map(X509::from).map_err(|_| {
+            error!("Cannot make certificate, does data contain .pem?");
+            CryptoError::InvalidPem
+        })
+    }
+
+    /// Creates a certificate from data that is either PEM or DER encoded. PEM is detected by the
+    /// presence of a `-----BEGIN CERTIFICATE-----` header, otherwise the data is treated as DER.
+    pub fn from_bytes_auto(data: &[u8]) -> Result<X509, StatusCode> {
+        const PEM_HEADER: &[u8] = b"-----BEGIN CERTIFICATE-----";
+        let is_pem = data
+            .windows(PEM_HEADER.len())
+            .any(|window| window == PEM_HEADER);
+        let cert = if is_pem {
+            Self::from_pem(data)?
+        } else {
+            Self::from_der(data)?
+        };
+        Ok(cert)
+    }
+
     /// Returns a ByteString representation of the cert which is DER encoded form of X509v3
-    pub fn as_byte_string(&self) -> ByteString {
-        let der = self.value.to_der().unwrap();
-        ByteString::from(&der)
+    pub fn as_byte_string(&self) -> Result<ByteString, CryptoError> {
+        self.to_der().map(|der| ByteString::from(&der))
     }
 
-    pub fn public_key(&self) -> Result<PublicKey, StatusCode> {
+    pub fn public_key(&self) -> Result<PublicKey, CryptoError> {
         self.value
             .public_key()
             .map(PublicKey::wrap_public_key)
-            .map_err(|_| {
-                error!("Cannot obtain public key from certificate");
-                StatusCode::BadCertificateInvalid
+            .map_err(|err| {
+                error!("Cannot obtain public key from certificate, error {}", err);
+                CryptoError::InvalidPublicKey
             })
     }
 
     /// Returns the key length in bits (if possible)
-    pub fn key_length(&self) -> Result<usize, X509Error> {
-        let pub_key = self.value.public_key().map_err(|_| X509Error)?;
+    pub fn key_length(&self) -> Result<usize, CryptoError> {
+        let pub_key = self.value.public_key()?;
         Ok(pub_key.size() * 8)
     }
 
-    fn get_subject_entry(&self, nid: Nid) -> Result<String, X509Error> {
+    fn get_subject_entry(&self, nid: Nid) -> Result<String, CryptoError> {
         let subject_name = self.value.subject_name();
         let mut entries = subject_name.entries_by_nid(nid);
         if let Some(entry) = entries.next() {
@@ -386,10 +434,10 @@ impl X509 {
                 // Value is an OpensslString type here so it has to be converted
                 Ok(value.deref().to_string())
             } else {
-                Err(X509Error)
+                Err(CryptoError::MissingSubjectEntry)
             }
         } else {
-            Err(X509Error)
+            Err(CryptoError::MissingSubjectEntry)
         }
     }
 
@@ -412,7 +460,7 @@ impl X509 {
     }
 
     /// Gets the common name out of the cert
-    pub fn common_name(&self) -> Result<String, X509Error> {
+    pub fn common_name(&self) -> Result<String, CryptoError> {
         self.get_subject_entry(Nid::COMMONNAME)
     }
 
@@ -449,33 +497,65 @@ impl X509 {
         StatusCode::Good
     }
 
-    fn subject_alt_names(&self) -> Option<Vec<String>> {
+    /// Returns the subject alt names on the cert in the order they appear. Uri and dns entries
+    /// are returned as they are and ip entries as the textual form of the address. Entries of
+    /// any other kind are skipped.
+    pub fn subject_alt_names(&self) -> Vec<String> {
         if let Some(ref alt_names) = self.value.subject_alt_names() {
-            // Skip the application uri
-            let subject_alt_names = alt_names
+            alt_names
                 .iter()
-                .skip(1)
-                .map(|n| {
-                    if let Some(dnsname) = n.dnsname() {
-                        dnsname.to_string()
-                    } else if let Some(ip) = n.ipaddress() {
-                        if ip.len() == 4 {
-                            let mut addr = [0u8; 4];
-                            addr[..].clone_from_slice(ip);
-                            Ipv4Addr::from(addr).to_string()
-                        } else if ip.len() == 16 {
-                            let mut addr = [0u8; 16];
-                            addr[..].clone_from_slice(ip);
-                            Ipv6Addr::from(addr).to_string()
-                        } else {
-                            "".to_string()
-                        }
+                .filter_map(|n| {
+                    if let Some(uri) = n.uri() {
+                        Some(uri.to_string())
                     } else {
-                        "".to_string()
+                        Self::host_name(n)
                     }
                 })
-                .collect();
-            Some(subject_alt_names)
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Returns the application uri of the cert, which OPC UA requires to be the first subject
+    /// alt name. If the first subject alt name is not a uri, the cert has no application uri.
+    pub fn application_uri(&self) -> Option<String> {
+        self.value.subject_alt_names().and_then(|alt_names| {
+            alt_names
+                .iter()
+                .next()
+                .and_then(|n| n.uri().map(|uri| uri.to_string()))
+        })
+    }
+
+    /// Returns the subject alt names following the application uri as host names
+    fn alt_host_names(&self) -> Option<Vec<String>> {
+        self.value.subject_alt_names().map(|alt_names| {
+            // Skip the application uri
+            alt_names
+                .iter()
+                .skip(1)
+                .map(|n| Self::host_name(n).unwrap_or_default())
+                .collect()
+        })
+    }
+
+    /// Returns the host name of a dns or ip subject alt name
+    fn host_name(name: &x509::GeneralNameRef) -> Option<String> {
+        if let Some(dnsname) = name.dnsname() {
+            Some(dnsname.to_string())
+        } else if let Some(ip) = name.ipaddress() {
+            if ip.len() == 4 {
+                let mut addr = [0u8; 4];
+                addr[..].clone_from_slice(ip);
+                Some(Ipv4Addr::from(addr).to_string())
+            } else if ip.len() == 16 {
+                let mut addr = [0u8; 16];
+                addr[..].clone_from_slice(ip);
+                Some(Ipv6Addr::from(addr).to_string())
+            } else {
+                None
+            }
         } else {
             None
         }
@@ -488,7 +568,7 @@ impl X509 {
         if hostname.is_empty() {
             error!("Hostname is empty");
             StatusCode::BadCertificateHostNameInvalid
-        } else if let Some(subject_alt_names) = self.subject_alt_names() {
+        } else if let Some(subject_alt_names) = self.alt_host_names() {
             let found = subject_alt_names
                 .iter()
                 .any(|n| n.eq_ignore_ascii_case(hostname));
@@ -522,34 +602,114 @@ impl X509 {
         );
         // Expecting the first subject alternative name to be a uri that matches with the supplied
         // application uri
-        if let Some(ref alt_names) = self.value.subject_alt_names() {
-            if alt_names.len() > 0 {
-                if let Some(cert_application_uri) = alt_names[0].uri() {
-                    if cert_application_uri == application_uri {
-                        info!("Certificate application uri {} is good", application_uri);
-                        StatusCode::Good
-                    } else {
-                        error!(
-                            "Cert application uri {} does not match supplied uri {}",
-                            cert_application_uri, application_uri
-                        );
-                        StatusCode::BadCertificateUriInvalid
-                    }
-                } else {
-                    error!("Cert's first subject alt name is not a uri and cannot be compared");
-                    StatusCode::BadCertificateUriInvalid
-                }
+        if let Some(cert_application_uri) = self.application_uri() {
+            if cert_application_uri == application_uri {
+                info!("Certificate application uri {} is good", application_uri);
+                StatusCode::Good
             } else {
-                error!("Cert has zero subject alt names");
+                error!(
+                    "Cert application uri {} does not match supplied uri {}",
+                    cert_application_uri, application_uri
+                );
                 StatusCode::BadCertificateUriInvalid
             }
         } else {
-            error!("Cert has no subject alt names at all");
-            // No alt names
+            error!("Cert's first subject alt name is not a uri and cannot be compared");
             StatusCode::BadCertificateUriInvalid
         }
     }
 
+    /// Tests if the signature on a self-signed cert was made by the private key matching its own
+    /// public key. A cert issued by some other party cannot be verified without its issuer so
+    /// it is not checked here.
+    pub fn is_self_signature_valid(&self) -> StatusCode {
+        if self.value.issued(&self.value) != x509::X509VerifyResult::OK {
+            trace!("Cert is not self-signed so its signature is not checked");
+            return StatusCode::Good;
+        }
+        match self.value.public_key() {
+            Ok(public_key) => match self.value.verify(&public_key) {
+                Ok(true) => StatusCode::Good,
+                Ok(false) => {
+                    error!("Self-signed cert has an invalid signature");
+                    StatusCode::BadCertificateInvalid
+                }
+                Err(err) => {
+                    error!("Cannot verify cert signature, err = {:?}", err);
+                    StatusCode::BadCertificateInvalid
+                }
+            },
+            Err(_) => {
+                error!("Cannot obtain public key from certificate");
+                StatusCode::BadCertificateInvalid
+            }
+        }
+    }
+
+    /// Verifies this cert was issued by the issuer, i.e. that its issuer name is the issuer's
+    /// subject name and that its signature was made by the private key of the issuer. A
+    /// self-signed cert is its own issuer.
+    ///
+    /// # Errors
+    ///
+    /// `CryptoError::IssuerMismatch` if the cert names a different issuer and
+    /// `CryptoError::InvalidSignature` if the issuer's public key does not verify the signature.
+    ///
+    pub fn verify_signed_by(&self, issuer: &X509) -> Result<(), CryptoError> {
+        let result = issuer.value.issued(&self.value);
+        if result != x509::X509VerifyResult::OK {
+            error!(
+                "Cert was not issued by {}, {}",
+                issuer.subject_name(),
+                result.error_string()
+            );
+            return Err(CryptoError::IssuerMismatch);
+        }
+        let public_key = issuer.value.public_key()?;
+        if self.value.verify(&public_key)? {
+            Ok(())
+        } else {
+            error!(
+                "Cert signature does not verify against issuer {}",
+                issuer.subject_name()
+            );
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+
+    /// Tests if the key usage extension on the cert permits it to be used as an application
+    /// instance certificate, i.e. for digital signatures, key encipherment and data encipherment.
+    pub fn is_key_usage_valid(&self) -> StatusCode {
+        use foreign_types::ForeignType;
+        use openssl_sys::{
+            X509_get_extension_flags, X509_get_key_usage, EXFLAG_KUSAGE, KU_DATA_ENCIPHERMENT,
+            KU_DIGITAL_SIGNATURE, KU_KEY_ENCIPHERMENT,
+        };
+        const REQUIRED_KEY_USAGES: [(u32, &str); 3] = [
+            (KU_DIGITAL_SIGNATURE, "Digital Signature"),
+            (KU_KEY_ENCIPHERMENT, "Key Encipherment"),
+            (KU_DATA_ENCIPHERMENT, "Data Encipherment"),
+        ];
+        // The OpenSSL crate has no accessor for the key usage bits so they are read through
+        // openssl-sys. Both calls only read the cached extensions of a valid X509 pointer.
+        let (flags, key_usage) = unsafe {
+            let x509 = self.value.as_ptr();
+            (X509_get_extension_flags(x509), X509_get_key_usage(x509))
+        };
+        if flags & EXFLAG_KUSAGE == 0 {
+            error!("Cert has no key usage extension");
+            StatusCode::BadCertificateUseNotAllowed
+        } else if let Some((_, missing)) = REQUIRED_KEY_USAGES
+            .iter()
+            .find(|(bit, _)| key_usage & *bit == 0)
+        {
+            error!("Cert key usage does not include {}", missing);
+            StatusCode::BadCertificateUseNotAllowed
+        } else {
+            StatusCode::Good
+        }
+    }
+
     /// OPC UA Part 6 MessageChunk structure
     ///
     /// The thumbprint is the SHA1 digest of the DER form of the certificate. The hash is 160 bits
@@ -563,26 +723,43 @@ impl X509 {
         Thumbprint::new(&digest)
     }
 
+    /// Creates a thumbprint like `thumbprint` but from the SHA256 digest of the certificate, which
+    /// is 256 bits (32 bytes) in length.
+    pub fn thumbprint_sha256(&self) -> Thumbprint {
+        use openssl::hash::{hash, MessageDigest};
+        let der = self.value.to_der().unwrap();
+        let digest = hash(MessageDigest::sha256(), &der).unwrap();
+        Thumbprint::new(&digest)
+    }
+
     /// Turn the Asn1 values into useful portable types
-    pub fn not_before(&self) -> Result<DateTime<Utc>, X509Error> {
+    pub fn not_before(&self) -> Result<DateTime<Utc>, CryptoError> {
         let date = self.value.not_before().to_string();
         Self::parse_asn1_date(&date)
     }
 
     /// Turn the Asn1 values into useful portable types
-    pub fn not_after(&self) -> Result<DateTime<Utc>, X509Error> {
+    pub fn not_after(&self) -> Result<DateTime<Utc>, CryptoError> {
         let date = self.value.not_after().to_string();
         Self::parse_asn1_date(&date)
     }
 
-    pub fn to_der(&self) -> Result<Vec<u8>, X509Error> {
+    pub fn to_der(&self) -> Result<Vec<u8>, CryptoError> {
         self.value.to_der().map_err(|e| {
             error!("Cannot turn X509 cert to DER, err = {:?}", e);
-            X509Error
+            CryptoError::OpenSsl(e)
+        })
+    }
+
+    /// Returns the cert PEM encoded, i.e. as a `-----BEGIN CERTIFICATE-----` block.
+    pub fn to_pem(&self) -> Result<Vec<u8>, CryptoError> {
+        self.value.to_pem().map_err(|e| {
+            error!("Cannot turn X509 cert to PEM, err = {:?}", e);
+            CryptoError::OpenSsl(e)
         })
     }
 
-    fn parse_asn1_date(date: &str) -> Result<DateTime<Utc>, X509Error> {
+    fn parse_asn1_date(date: &str) -> Result<DateTime<Utc>, CryptoError> {
         const SUFFIX: &str = " GMT";
         // Parse ASN1 time format
         // MMM DD HH:MM:SS YYYY [GMT]
@@ -596,7 +773,7 @@ impl X509 {
         Utc.datetime_from_str(date, "%b %d %H:%M:%S %Y")
             .map_err(|e| {
                 error!("Cannot parse ASN1 date, err = {:?}", e);
-                X509Error
+                CryptoError::DateParse(date.to_string())
             })
     }
 }
@@ -610,7 +787,10 @@ mod tests {
         use chrono::{Datelike, Timelike};
 
         assert!(X509::parse_asn1_date("").is_err());
-        assert!(X509::parse_asn1_date("Jan 69 00:00:00 1970").is_err());
+        assert!(matches!(
+            X509::parse_asn1_date("Jan 69 00:00:00 1970").unwrap_err(),
+            CryptoError::DateParse(date) if date == "Jan 69 00:00:00 1970"
+        ));
         assert!(X509::parse_asn1_date("Feb 21 00:00:00 1970").is_ok());
         assert!(X509::parse_asn1_date("Feb 21 00:00:00 1970 GMT").is_ok());
 
@@ -638,6 +818,7 @@ mod tests {
             organizational_unit: "x.org ops".to_string(),
             country: "EN".to_string(),
             state: "London".to_string(),
+            application_uri: String::new(),
             alt_host_names: alt_host_names.iter().map(|h| h.to_string()).collect(),
             certificate_duration_days: 60,
         };
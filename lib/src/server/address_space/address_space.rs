@@ -273,6 +273,24 @@ impl AddressSpace {
         self.set_variable_value(Server_ServiceLevel, service_level, now, now);
     }
 
+    /// Sets a provider that is called for the service level 0-255 each time it is read, e.g. so
+    /// that a redundant server can report its current health or load to clients choosing between
+    /// servers.
+    pub fn set_service_level_provider<F>(&mut self, mut provider: F)
+    where
+        F: FnMut() -> u8 + Send + 'static,
+    {
+        self.set_variable_getter(
+            Server_ServiceLevel,
+            move |_, timestamps_to_return, _, _, _, _| {
+                let now = DateTime::now();
+                let mut value = DataValue::from(provider());
+                value.set_timestamps(timestamps_to_return, now, now);
+                Ok(Some(value))
+            },
+        );
+    }
+
     /// Sets values for nodes representing the server.
     pub fn set_server_state(&mut self, server_state: Arc<RwLock<ServerState>>) {
         // Server state requires the generated address space, otherwise nothing
@@ -604,16 +622,57 @@ impl AddressSpace {
 
             // State OPC UA Part 5 12.6, Valid states are
             //     State (Server_ServerStatus_State)
-            self.set_variable_getter(
-                Server_ServerStatus_State,
-                move |_, timestamps_to_return, _, _, _, _| {
-                    // let server_state =  trace_read_lock!(server_state);
-                    let now = DateTime::now();
-                    let mut value = DataValue::from(0i32);
-                    value.set_timestamps(timestamps_to_return, now, now);
-                    Ok(Some(value))
-                },
-            );
+            {
+                let server_state = server_state.clone();
+                self.set_variable_getter(
+                    Server_ServerStatus_State,
+                    move |_, timestamps_to_return, _, _, _, _| {
+                        let now = DateTime::now();
+                        let state = Self::server_state_type(&server_state);
+                        let mut value = DataValue::from(state as i32);
+                        value.set_timestamps(timestamps_to_return, now, now);
+                        Ok(Some(value))
+                    },
+                );
+            }
+
+            // Server_ServerStatus - synthesized on each read so the current time is live
+            {
+                let build_info = {
+                    let server_state = trace_read_lock!(server_state);
+                    BuildInfo {
+                        product_uri: server_state.product_uri.clone(),
+                        manufacturer_name: UAString::null(),
+                        product_name: server_state.application_name.text.clone(),
+                        software_version: UAString::from(env!("CARGO_PKG_VERSION")),
+                        build_number: UAString::null(),
+                        build_date: now,
+                    }
+                };
+                let start_time = now;
+                let server_state = server_state.clone();
+                self.set_variable_getter(
+                    Server_ServerStatus,
+                    move |_, timestamps_to_return, _, _, _, _| {
+                        let now = DateTime::now();
+                        let server_status = ServerStatusDataType {
+                            start_time,
+                            current_time: now,
+                            state: Self::server_state_type(&server_state),
+                            build_info: build_info.clone(),
+                            seconds_till_shutdown: 0,
+                            shutdown_reason: LocalizedText::null(),
+                        };
+                        let mut value =
+                            DataValue::from(Variant::from(ExtensionObject::from_encodable(
+                                ObjectId::ServerStatusDataType_Encoding_DefaultBinary,
+                                &server_status,
+                            )));
+                        value.set_timestamps(timestamps_to_return, now, now);
+                        Ok(Some(value))
+                    },
+                );
+            }
 
             // ServerStatus_BuildInfo
             {
@@ -638,6 +697,16 @@ impl AddressSpace {
         }
     }
 
+    /// Returns the current state of the server, e.g. for the ServerStatus variables. Values are
+    /// read while a service such as read holds a read lock on the server state, so the lock is
+    /// taken recursively in order not to deadlock behind a writer that is waiting for it.
+    #[cfg(feature = "generated-address-space")]
+    fn server_state_type(
+        server_state: &Arc<RwLock<ServerState>>,
+    ) -> crate::types::service_types::ServerState {
+        server_state.read_recursive().state()
+    }
+
     /// Sets the history server capabilities based on the supplied flags
     pub fn set_history_server_capabilities(&mut self, capabilities: &HistoryServerCapabilities) {
         let now = DateTime::now();
@@ -968,6 +1037,28 @@ impl AddressSpace {
         self.node_map.get(node_id)
     }
 
+    /// Reads an attribute of the node with the node id, see `NodeType::read_attribute()`. Fails
+    /// with `BadNodeIdUnknown` if there is no such node.
+    pub fn read_attribute(
+        &self,
+        node_id: &NodeId,
+        timestamps_to_return: TimestampsToReturn,
+        attribute_id: AttributeId,
+        index_range: NumericRange,
+        data_encoding: &QualifiedName,
+        max_age: f64,
+    ) -> Result<DataValue, StatusCode> {
+        self.find_node(node_id)
+            .ok_or(StatusCode::BadNodeIdUnknown)?
+            .read_attribute(
+                timestamps_to_return,
+                attribute_id,
+                index_range,
+                data_encoding,
+                max_age,
+            )
+    }
+
     /// Finds a node by its node id and returns a mutable reference to it.
     pub fn find_node_mut(&mut self, node_id: &NodeId) -> Option<&mut NodeType> {
         self.node_map.get_mut(node_id)
@@ -1205,6 +1296,15 @@ impl AddressSpace {
             }
         }
     }
+
+    /// Finds the data type that the specified encoding node, e.g. the node id of an
+    /// `ExtensionObject`, is an encoding of. The data type references the encoding with
+    /// a `HasEncoding` reference.
+    pub fn data_type_of_encoding(&self, encoding_id: &NodeId) -> Option<NodeId> {
+        self.find_inverse_references(encoding_id, Some((ReferenceTypeId::HasEncoding, false)))
+            .and_then(|references| references.first().map(|r| r.target_node.clone()))
+    }
+
     /// Finds objects by a specified type.
     fn find_nodes_by_type<T>(
         &self,
@@ -74,6 +74,84 @@ impl NodeType {
             NodeType::Method(_) => NodeClass::Method,
         }
     }
+
+    /// Tests if the attribute is defined for the node class of this node, as described in
+    /// OPC UA Part 3. Every node class has the base attributes, e.g. `BrowseName`, and each adds
+    /// its own, e.g. only variables and variable types have a `Value`.
+    pub fn supports_attribute(&self, attribute_id: AttributeId) -> bool {
+        match attribute_id {
+            AttributeId::NodeId
+            | AttributeId::NodeClass
+            | AttributeId::BrowseName
+            | AttributeId::DisplayName
+            | AttributeId::Description
+            | AttributeId::WriteMask
+            | AttributeId::UserWriteMask
+            | AttributeId::RolePermissions
+            | AttributeId::UserRolePermissions
+            | AttributeId::AccessRestrictions => true,
+            AttributeId::IsAbstract => matches!(
+                self,
+                NodeType::ObjectType(_)
+                    | NodeType::VariableType(_)
+                    | NodeType::ReferenceType(_)
+                    | NodeType::DataType(_)
+            ),
+            AttributeId::Symmetric | AttributeId::InverseName => {
+                matches!(self, NodeType::ReferenceType(_))
+            }
+            AttributeId::ContainsNoLoops => matches!(self, NodeType::View(_)),
+            AttributeId::EventNotifier => matches!(self, NodeType::Object(_) | NodeType::View(_)),
+            AttributeId::Value
+            | AttributeId::DataType
+            | AttributeId::ValueRank
+            | AttributeId::ArrayDimensions => {
+                matches!(self, NodeType::Variable(_) | NodeType::VariableType(_))
+            }
+            AttributeId::AccessLevel
+            | AttributeId::UserAccessLevel
+            | AttributeId::MinimumSamplingInterval
+            | AttributeId::Historizing
+            | AttributeId::AccessLevelEx => matches!(self, NodeType::Variable(_)),
+            AttributeId::Executable | AttributeId::UserExecutable => {
+                matches!(self, NodeType::Method(_))
+            }
+            AttributeId::DataTypeDefinition => matches!(self, NodeType::DataType(_)),
+        }
+    }
+
+    /// Reads an attribute of the node. The value comes from the data source for a variable's
+    /// `Value`, and otherwise from the node's metadata.
+    ///
+    /// Fails with `BadAttributeIdInvalid` if the attribute is not defined for the node class,
+    /// e.g. the `Value` of an object, or if it is an optional attribute the node does not have.
+    pub fn read_attribute(
+        &self,
+        timestamps_to_return: TimestampsToReturn,
+        attribute_id: AttributeId,
+        index_range: NumericRange,
+        data_encoding: &QualifiedName,
+        max_age: f64,
+    ) -> Result<DataValue, StatusCode> {
+        if !self.supports_attribute(attribute_id) {
+            debug!(
+                "Attribute {:?} is not defined for node class {:?}",
+                attribute_id,
+                self.node_class()
+            );
+            Err(StatusCode::BadAttributeIdInvalid)
+        } else {
+            self.as_node()
+                .get_attribute_max_age(
+                    timestamps_to_return,
+                    attribute_id,
+                    index_range,
+                    data_encoding,
+                    max_age,
+                )
+                .ok_or(StatusCode::BadAttributeIdInvalid)
+        }
+    }
 }
 
 /// Implemented within a macro for all Node types. Functions that return a result in an Option
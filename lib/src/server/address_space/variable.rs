@@ -569,7 +569,8 @@ impl Variable {
                 self.value.source_timestamp = Some(*source_timestamp);
                 Ok(())
             }
-            None => Err(StatusCode::BadIndexRangeInvalid),
+            // There is nothing to splice the range into
+            None => Err(StatusCode::BadIndexRangeNoData),
         }
     }
 
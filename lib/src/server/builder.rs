@@ -7,7 +7,9 @@ use std::path::PathBuf;
 use crate::core::config::Config;
 
 use super::{
-    config::{ServerConfig, ServerEndpoint, ServerUserToken, ANONYMOUS_USER_TOKEN_ID},
+    config::{
+        OperationalLimits, ServerConfig, ServerEndpoint, ServerUserToken, ANONYMOUS_USER_TOKEN_ID,
+    },
     constants,
     server::Server,
 };
@@ -362,6 +364,12 @@ impl ServerBuilder {
         self
     }
 
+    /// Set the maximum number of operations that clients may supply in a single service call
+    pub fn operational_limits(mut self, operational_limits: OperationalLimits) -> Self {
+        self.config.limits.operational_limits = operational_limits;
+        self
+    }
+
     /// Sets the server to automatically trust client certs. This subverts the
     /// authentication during handshake, so only do this if you understand the risks.
     pub fn trust_client_certs(mut self) -> Self {
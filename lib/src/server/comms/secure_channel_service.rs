@@ -18,6 +18,10 @@ struct SecureChannelState {
     last_secure_channel_id: u32,
     /// Last token id number
     last_token_id: u32,
+    /// Security mode the channel was issued with
+    security_mode: MessageSecurityMode,
+    /// Security policy the channel was issued with
+    security_policy: SecurityPolicy,
 }
 
 impl SecureChannelState {
@@ -27,6 +31,8 @@ impl SecureChannelState {
             issued: false,
             renew_count: 0,
             last_token_id: 0,
+            security_mode: MessageSecurityMode::Invalid,
+            security_policy: SecurityPolicy::Unknown,
         }
     }
 
@@ -53,10 +59,16 @@ impl SecureChannelService {
         }
     }
 
+    /// Handles an OpenSecureChannel request. The `request_channel_id` is the channel id from the
+    /// header of the chunk the request arrived in.
+    ///
+    /// An Issue creates the channel and may only happen once per connection. A Renew must arrive on
+    /// the channel being renewed and use the same security mode and policy that it was issued with.
     pub fn open_secure_channel(
         &mut self,
         secure_channel: &mut SecureChannel,
         security_header: &SecurityHeader,
+        request_channel_id: u32,
         client_protocol_version: u32,
         message: &SupportedMessage,
     ) -> Result<SupportedMessage, StatusCode> {
@@ -99,9 +111,17 @@ impl SecureChannelService {
         let secure_channel_id = match request.request_type {
             SecurityTokenRequestType::Issue => {
                 trace!("Request type == Issue");
-                // check to see if renew has been called before or not
-                if self.secure_channel_state.renew_count > 0 {
-                    error!("Asked to issue token on session that has called renew before");
+                // A channel is only issued once, after that it may only be renewed
+                if self.secure_channel_state.issued {
+                    error!(
+                        "Asked to issue token on channel {} that has been issued before, renew count {}",
+                        request_channel_id, self.secure_channel_state.renew_count
+                    );
+                    return Ok(ServiceFault::new(
+                        &request.request_header,
+                        StatusCode::BadSecurityChecksFailed,
+                    )
+                    .into());
                 }
                 self.secure_channel_state.create_secure_channel_id()
             }
@@ -126,6 +146,45 @@ impl SecureChannelService {
                     error!("Asked to renew token on session that has never issued token");
                     return Err(StatusCode::BadUnexpectedError);
                 }
+
+                // The renew must arrive on the channel being renewed
+                if request_channel_id != secure_channel.secure_channel_id() {
+                    error!(
+                        "Asked to renew token on channel {} but the channel is {}",
+                        request_channel_id,
+                        secure_channel.secure_channel_id()
+                    );
+                    return Ok(ServiceFault::new(
+                        &request.request_header,
+                        StatusCode::BadSecurityChecksFailed,
+                    )
+                    .into());
+                }
+
+                // The renew must not change the security of the channel
+                if request.security_mode != self.secure_channel_state.security_mode {
+                    error!(
+                        "Renew asked to change security mode from {:?} to {:?}",
+                        self.secure_channel_state.security_mode, request.security_mode
+                    );
+                    return Ok(ServiceFault::new(
+                        &request.request_header,
+                        StatusCode::BadSecurityModeRejected,
+                    )
+                    .into());
+                }
+                if secure_channel.security_policy() != self.secure_channel_state.security_policy {
+                    error!(
+                        "Renew asked to change security policy from {} to {}",
+                        self.secure_channel_state.security_policy,
+                        secure_channel.security_policy()
+                    );
+                    return Ok(ServiceFault::new(
+                        &request.request_header,
+                        StatusCode::BadSecurityChecksFailed,
+                    )
+                    .into());
+                }
                 self.secure_channel_state.renew_count += 1;
                 secure_channel.secure_channel_id()
             }
@@ -151,6 +210,8 @@ impl SecureChannelService {
 
         // Process the request
         self.secure_channel_state.issued = true;
+        self.secure_channel_state.security_mode = request.security_mode;
+        self.secure_channel_state.security_policy = secure_channel.security_policy();
 
         // Create a new secure channel info
         let security_mode = request.security_mode;
@@ -160,7 +221,12 @@ impl SecureChannelService {
         secure_channel.set_remote_cert_from_byte_string(&security_header.sender_certificate)?;
 
         match secure_channel.set_remote_nonce_from_byte_string(&request.client_nonce) {
-            Ok(_) => secure_channel.create_random_nonce(),
+            Ok(_) => {
+                if let Err(err) = secure_channel.create_random_nonce() {
+                    error!("Was unable to create our nonce");
+                    return Ok(ServiceFault::new(&request.request_header, err).into());
+                }
+            }
             Err(err) => {
                 error!("Was unable to set their nonce, check logic");
                 return Ok(ServiceFault::new(&request.request_header, err).into());
@@ -177,7 +243,7 @@ impl SecureChannelService {
 
         let response = OpenSecureChannelResponse {
             response_header: ResponseHeader::new_good(&request.request_header),
-            server_protocol_version: 0,
+            server_protocol_version: PROTOCOL_VERSION,
             security_token: ChannelSecurityToken {
                 channel_id: secure_channel.secure_channel_id(),
                 token_id: secure_channel.token_id(),
@@ -31,6 +31,7 @@ use crate::core::{
         message_writer::MessageWriter,
         secure_channel::SecureChannel,
         tcp_codec::{self, TcpCodec},
+        transport_metrics::{TransportCounters, TransportMetrics},
     },
     prelude::*,
 };
@@ -80,6 +81,8 @@ struct ReadState {
     pub hello_timeout: u32,
     /// Reader from which messages will be decoded
     pub reader: OwnedReadHalf,
+    /// Write buffer, whose limits are set from the HELLO
+    pub send_buffer: Arc<Mutex<MessageWriter>>,
 }
 
 struct WriteState {
@@ -118,6 +121,10 @@ pub struct TcpTransport {
     last_received_sequence_number: u32,
     /// A message may consist of one or more chunks which are stored here until complete.
     pending_chunks: Vec<MessageChunk>,
+    /// Size in bytes of the pending chunks as they were received
+    pending_chunks_size: usize,
+    /// Counters of the messages sent and received
+    transport_counters: Arc<TransportCounters>,
     /// Sessions associated with this connection. Normally there would be one, but potentially there could be more
     session_manager: Arc<RwLock<SessionManager>>,
 }
@@ -193,10 +200,17 @@ impl TcpTransport {
             client_protocol_version: 0,
             last_received_sequence_number: 0,
             pending_chunks: Vec::with_capacity(2),
+            pending_chunks_size: 0,
+            transport_counters: Arc::new(TransportCounters::new()),
             session_manager,
         }
     }
 
+    /// Returns a snapshot of the counters of messages sent and received by the transport
+    pub fn metrics(&self) -> TransportMetrics {
+        self.transport_counters.snapshot()
+    }
+
     /// This is the entry point for the session. This function is asynchronous - it spawns tokio
     /// tasks to handle the session execution loop so this function will returns immediately.
     pub fn run(connection: Arc<RwLock<TcpTransport>>, socket: TcpStream, looping_interval_ms: f64) {
@@ -262,11 +276,12 @@ impl TcpTransport {
     ) {
         // The reader task will send responses, the writer task will receive responses
         let (tx, rx) = unbounded_channel();
-        let send_buffer = Arc::new(Mutex::new(MessageWriter::new(send_buffer_size, 0, 0)));
+        let mut message_writer = MessageWriter::new(send_buffer_size, 0, 0);
 
         let (reader, writer) = socket.into_split();
         let (hello_timeout, secure_channel) = {
             let transport = trace_read_lock!(transport);
+            message_writer.set_transport_counters(transport.transport_counters.clone());
             let server_state = trace_read_lock!(transport.server_state);
             let server_config = trace_read_lock!(server_state.config);
             info!(
@@ -279,12 +294,14 @@ impl TcpTransport {
                 transport.secure_channel.clone(),
             )
         };
+        let send_buffer = Arc::new(Mutex::new(message_writer));
 
         let read_state = ReadState {
             reader,
             hello_timeout,
             transport: transport.clone(),
             sender: tx.clone(),
+            send_buffer: send_buffer.clone(),
         };
 
         // Spawn all the tasks that monitor the session - the subscriptions, finished state,
@@ -413,6 +430,11 @@ impl TcpTransport {
             FramedRead::new(read_state.reader, TcpCodec::new(decoding_options.clone()));
 
         let hello = Self::wait_for_hello(&mut framed_read, read_state.hello_timeout).await?;
+
+        // Responses are chunked to fit what the client says it can receive
+        let max_message_size = hello.max_message_size as usize;
+        let max_chunk_size = (hello.receive_buffer_size as usize).min(send_buffer_size);
+        let max_chunk_count = hello.max_chunk_count as usize;
         trace_write_lock!(transport).process_hello(
             hello,
             &mut sender,
@@ -420,6 +442,11 @@ impl TcpTransport {
             send_buffer_size,
             receive_buffer_size,
         )?;
+        trace_lock!(read_state.send_buffer).set_limits(
+            max_message_size,
+            max_chunk_size,
+            max_chunk_count,
+        );
 
         while let Some(next_msg) = framed_read.next().await {
             match next_msg {
@@ -513,11 +540,10 @@ impl TcpTransport {
         send_buffer_size: usize,
         receive_buffer_size: usize,
     ) -> std::result::Result<(), StatusCode> {
-        let server_protocol_version = 0;
         let endpoints = {
             let server_state = trace_read_lock!(self.server_state);
             server_state.endpoints(&hello.endpoint_url, &None)
-        }
+        }?
         .unwrap();
 
         trace!("Server received HELLO {:?}", hello);
@@ -531,7 +557,11 @@ impl TcpTransport {
         }
 
         // Validate protocol version
-        if hello.protocol_version > server_protocol_version {
+        if !hello.is_protocol_version_supported() {
+            error!(
+                "HELLO protocol version {} is unsupported, server supports {}",
+                hello.protocol_version, PROTOCOL_VERSION
+            );
             return Err(StatusCode::BadProtocolVersionUnsupported);
         }
 
@@ -540,7 +570,7 @@ impl TcpTransport {
         // Send acknowledge
         let mut acknowledge = AcknowledgeMessage {
             message_header: MessageHeader::new(MessageType::Acknowledge),
-            protocol_version: server_protocol_version,
+            protocol_version: PROTOCOL_VERSION,
             receive_buffer_size: receive_buffer_size as u32,
             send_buffer_size: send_buffer_size as u32,
             max_message_size: decoding_options.max_message_size as u32,
@@ -588,8 +618,11 @@ impl TcpTransport {
         if message_header.is_final == MessageIsFinalType::FinalError {
             info!("Discarding chunks as after receiving one marked as final error");
             self.pending_chunks.clear();
+            self.pending_chunks_size = 0;
             Ok(())
         } else {
+            self.pending_chunks_size += chunk.data.len();
+
             // Decrypt / verify chunk if necessary
             let chunk = {
                 let mut secure_channel = trace_write_lock!(self.secure_channel);
@@ -618,6 +651,9 @@ impl TcpTransport {
     ) -> Result<(), StatusCode> {
         // Drain pending chunks and turn them into a message
         let chunks: Vec<MessageChunk> = self.pending_chunks.drain(..).collect();
+        self.transport_counters
+            .record_received(self.pending_chunks_size, chunks.len());
+        self.pending_chunks_size = 0;
         let chunk_info = {
             let secure_channel = trace_read_lock!(self.secure_channel);
             chunks[0].chunk_info(&secure_channel)?
@@ -636,6 +672,7 @@ impl TcpTransport {
                 request_id,
                 &request,
                 &chunk_info.security_header,
+                message_header.secure_channel_id,
                 &sender,
             ),
             MessageChunkType::CloseSecureChannel => {
@@ -650,12 +687,14 @@ impl TcpTransport {
         request_id: u32,
         request: &SupportedMessage,
         security_header: &SecurityHeader,
+        secure_channel_id: u32,
         sender: &MessageSender,
     ) -> Result<(), StatusCode> {
         let mut secure_channel = trace_write_lock!(self.secure_channel);
         let response = self.secure_channel_service.open_secure_channel(
             &mut secure_channel,
             security_header,
+            secure_channel_id,
             self.client_protocol_version,
             request,
         )?;
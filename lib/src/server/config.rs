@@ -155,6 +155,9 @@ pub struct Limits {
     pub send_buffer_size: usize,
     /// Receive buffer size in bytes
     pub receive_buffer_size: usize,
+    /// Maximum number of operations in a single service call
+    #[serde(default)]
+    pub operational_limits: OperationalLimits,
 }
 
 impl Default for Limits {
@@ -174,6 +177,47 @@ impl Default for Limits {
             min_publishing_interval: constants::MIN_PUBLISHING_INTERVAL,
             send_buffer_size: SEND_BUFFER_SIZE,
             receive_buffer_size: RECEIVE_BUFFER_SIZE,
+            operational_limits: OperationalLimits::default(),
+        }
+    }
+}
+
+/// The maximum number of operations, e.g. nodes or monitored items, that a client may supply in
+/// a single call to a service. A request exceeding a limit is rejected with `BadTooManyOperations`.
+/// The limits are advertised to clients through the `Server.ServerCapabilities.OperationLimits`
+/// object.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct OperationalLimits {
+    pub max_nodes_per_translate_browse_paths_to_node_ids: usize,
+    pub max_nodes_per_read: usize,
+    pub max_nodes_per_write: usize,
+    pub max_nodes_per_method_call: usize,
+    pub max_nodes_per_browse: usize,
+    pub max_nodes_per_register_nodes: usize,
+    pub max_nodes_per_node_management: usize,
+    pub max_monitored_items_per_call: usize,
+    pub max_nodes_per_history_read_data: usize,
+    pub max_nodes_per_history_read_events: usize,
+    pub max_nodes_per_history_update_data: usize,
+    pub max_nodes_per_history_update_events: usize,
+}
+
+impl Default for OperationalLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes_per_translate_browse_paths_to_node_ids:
+                constants::MAX_NODES_PER_TRANSLATE_BROWSE_PATHS_TO_NODE_IDS,
+            max_nodes_per_read: constants::MAX_NODES_PER_READ,
+            max_nodes_per_write: constants::MAX_NODES_PER_WRITE,
+            max_nodes_per_method_call: constants::MAX_NODES_PER_METHOD_CALL,
+            max_nodes_per_browse: constants::MAX_NODES_PER_BROWSE,
+            max_nodes_per_register_nodes: constants::MAX_NODES_PER_REGISTER_NODES,
+            max_nodes_per_node_management: constants::MAX_NODES_PER_NODE_MANAGEMENT,
+            max_monitored_items_per_call: constants::MAX_MONITORED_ITEMS_PER_CALL,
+            max_nodes_per_history_read_data: constants::MAX_NODES_PER_HISTORY_READ_DATA,
+            max_nodes_per_history_read_events: constants::MAX_NODES_PER_HISTORY_READ_EVENTS,
+            max_nodes_per_history_update_data: constants::MAX_NODES_PER_HISTORY_UPDATE_DATA,
+            max_nodes_per_history_update_events: constants::MAX_NODES_PER_HISTORY_UPDATE_EVENTS,
         }
     }
 }
@@ -195,7 +239,10 @@ pub struct ServerEndpoint {
     pub security_policy: String,
     /// Security mode
     pub security_mode: String,
-    /// Security level, higher being more secure
+    /// Security level, higher being more secure, which is advertised in the endpoint description.
+    /// Endpoints made with the constructors are given the level computed from their security
+    /// policy and mode by `ServerEndpoint::security_level()` so they are ranked consistently. A
+    /// different value overrides that, e.g. to steer clients away from an endpoint.
     pub security_level: u8,
     /// Password security policy when a client supplies a user name identity token
     pub password_security_policy: Option<String>,
@@ -237,20 +284,28 @@ impl ServerEndpoint {
         }
     }
 
-    /// Recommends a security level for the supplied security policy
-    fn security_level(security_policy: SecurityPolicy, security_mode: MessageSecurityMode) -> u8 {
+    /// Computes the security level of an endpoint with the supplied security policy and mode, so
+    /// clients can pick the strongest endpoint. An endpoint without security is 0. Otherwise the
+    /// policies are ranked from the deprecated `Basic128Rsa15` lowest to `Aes256Sha256RsaPss`
+    /// highest, and any policy with `SignAndEncrypt` ranks above every policy with `Sign`.
+    pub fn security_level(
+        security_policy: SecurityPolicy,
+        security_mode: MessageSecurityMode,
+    ) -> u8 {
         let security_level = match security_policy {
             SecurityPolicy::Basic128Rsa15 => 1,
-            SecurityPolicy::Aes128Sha256RsaOaep => 2,
-            SecurityPolicy::Basic256 => 3,
+            SecurityPolicy::Basic256 => 2,
+            SecurityPolicy::Aes128Sha256RsaOaep => 3,
             SecurityPolicy::Basic256Sha256 => 4,
             SecurityPolicy::Aes256Sha256RsaPss => 5,
             _ => 0,
         };
-        if security_mode == MessageSecurityMode::SignAndEncrypt {
-            security_level + 10
-        } else {
-            security_level
+        match security_mode {
+            // A secure policy without signing or encryption offers no security, and vice versa
+            _ if security_level == 0 => 0,
+            MessageSecurityMode::Sign => security_level,
+            MessageSecurityMode::SignAndEncrypt => security_level + 10,
+            _ => 0,
         }
     }
 
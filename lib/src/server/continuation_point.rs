@@ -2,28 +2,212 @@
 // SPDX-License-Identifier: MPL-2.0
 // Copyright (C) 2017-2022 Adam Lock
 
-//! Provides a browse continuation point type for tracking a browse operation initiated by a client.
+//! Provides a browse continuation point type for tracking a browse operation initiated by a client,
+//! a manager of the continuation points held by a session, and an opaque continuation point token
+//! that can be used by any service that returns results in batches.
 
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
+use crate::crypto::random;
 use crate::sync::*;
-use crate::types::{service_types::ReferenceDescription, ByteString, DateTimeUtc};
+use crate::types::{
+    service_types::ReferenceDescription, status_code::StatusCode, ByteString, DateTimeUtc,
+};
 
 use crate::prelude::AddressSpace;
 
+/// An opaque continuation point issued by the server for Browse, HistoryRead or Query. It holds a
+/// random token so the client cannot guess or forge one, and an index into the results the server
+/// has buffered for the operation.
+///
+/// On the wire the continuation point is a `ByteString` of the index as 4 little endian bytes
+/// followed by the token. A client must present it back unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContinuationPoint {
+    /// Index into the buffered results
+    index: u32,
+    /// Random token
+    token: Vec<u8>,
+}
+
+impl ContinuationPoint {
+    /// Length in bytes of the random token
+    pub const TOKEN_LENGTH: usize = 16;
+
+    /// Issues a new continuation point with a random token for the index into buffered results
+    pub fn issue(index: u32) -> ContinuationPoint {
+        let token = random::nonce(Self::TOKEN_LENGTH).expect("Random number generator failed");
+        ContinuationPoint { index, token }
+    }
+
+    /// Returns the index into buffered results
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Serializes the continuation point to the `ByteString` that is sent to the client
+    pub fn to_byte_string(&self) -> ByteString {
+        let mut bytes = Vec::with_capacity(4 + self.token.len());
+        bytes.extend_from_slice(&self.index.to_le_bytes());
+        bytes.extend_from_slice(&self.token);
+        ByteString::from(bytes)
+    }
+
+    /// Deserializes a continuation point presented by the client. Fails with
+    /// `BadContinuationPointInvalid` if the bytes are not the length of a continuation point.
+    pub fn from_byte_string(
+        continuation_point: &ByteString,
+    ) -> Result<ContinuationPoint, StatusCode> {
+        match continuation_point.value {
+            Some(ref bytes) if bytes.len() == 4 + Self::TOKEN_LENGTH => {
+                let mut index = [0u8; 4];
+                index.copy_from_slice(&bytes[..4]);
+                Ok(ContinuationPoint {
+                    index: u32::from_le_bytes(index),
+                    token: bytes[4..].to_vec(),
+                })
+            }
+            _ => {
+                error!("Continuation point is null or the wrong length");
+                Err(StatusCode::BadContinuationPointInvalid)
+            }
+        }
+    }
+
+    /// Resolves a continuation point presented by the client against those the server issued,
+    /// returning the index into buffered results. Fails with `BadContinuationPointInvalid` if
+    /// the presented continuation point is malformed or was not issued, e.g. it has been forged or
+    /// its index altered.
+    pub fn resolve(
+        continuation_point: &ByteString,
+        issued: &[ContinuationPoint],
+    ) -> Result<u32, StatusCode> {
+        let presented = Self::from_byte_string(continuation_point)?;
+        if issued.iter().any(|issued| *issued == presented) {
+            Ok(presented.index)
+        } else {
+            error!("Continuation point was not issued by the server");
+            Err(StatusCode::BadContinuationPointInvalid)
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BrowseContinuationPoint {
-    pub id: ByteString,
+    /// The continuation point issued to the client, whose index is where the browse continues
+    /// from in the reference descriptions
+    pub id: ContinuationPoint,
     pub address_space_last_modified: DateTimeUtc,
     pub max_references_per_node: usize,
-    pub starting_index: usize,
     pub reference_descriptions: Arc<Mutex<Vec<ReferenceDescription>>>,
 }
 
 impl BrowseContinuationPoint {
+    /// Returns the index of the first reference description that the browse continues from
+    pub fn starting_index(&self) -> usize {
+        self.id.index() as usize
+    }
+
     /// Test if the continuation point valid which is only true if address space has not been
     /// modified since the point was made.
     pub fn is_valid_browse_continuation_point(&self, address_space: &AddressSpace) -> bool {
         self.address_space_last_modified >= address_space.last_modified()
     }
 }
+
+/// Holds the continuation points of a session. Each continuation point holds the references
+/// that a browse could not return, keyed by the opaque `ContinuationPoint` that the client
+/// supplies to BrowseNext to continue the browse.
+pub struct ContinuationPointManager {
+    /// Maximum number of continuation points, or 0 for no limit
+    max_continuation_points: usize,
+    /// Continuation points (oldest to newest)
+    continuation_points: VecDeque<BrowseContinuationPoint>,
+}
+
+impl ContinuationPointManager {
+    pub fn new(max_continuation_points: usize) -> ContinuationPointManager {
+        ContinuationPointManager {
+            max_continuation_points,
+            continuation_points: VecDeque::with_capacity(max_continuation_points),
+        }
+    }
+
+    /// Returns the number of continuation points being held
+    pub fn len(&self) -> usize {
+        self.continuation_points.len()
+    }
+
+    /// Tests if there are no continuation points being held
+    pub fn is_empty(&self) -> bool {
+        self.continuation_points.is_empty()
+    }
+
+    /// Stores the references from the starting index onwards under a new continuation point, which
+    /// is returned serialized for the client.
+    /// Fails with `BadNoContinuationPoints` if the session already holds the maximum number of
+    /// continuation points.
+    pub fn add(
+        &mut self,
+        address_space: &AddressSpace,
+        max_references_per_node: usize,
+        starting_index: usize,
+        reference_descriptions: Vec<ReferenceDescription>,
+    ) -> Result<ByteString, StatusCode> {
+        if self.max_continuation_points > 0
+            && self.continuation_points.len() >= self.max_continuation_points
+        {
+            error!(
+                "Cannot create a continuation point because the session holds the maximum of {}",
+                self.max_continuation_points
+            );
+            Err(StatusCode::BadNoContinuationPoints)
+        } else {
+            let id = ContinuationPoint::issue(starting_index as u32);
+            let continuation_point = id.to_byte_string();
+            self.continuation_points.push_back(BrowseContinuationPoint {
+                id,
+                address_space_last_modified: address_space.last_modified(),
+                max_references_per_node,
+                reference_descriptions: Arc::new(Mutex::new(reference_descriptions)),
+            });
+            Ok(continuation_point)
+        }
+    }
+
+    /// Finds and REMOVES a continuation point by the id the client presented. A malformed or
+    /// forged id finds nothing.
+    pub fn take(&mut self, id: &ByteString) -> Option<BrowseContinuationPoint> {
+        let id = ContinuationPoint::from_byte_string(id).ok()?;
+        if let Some(idx) = self
+            .continuation_points
+            .iter()
+            .position(|continuation_point| continuation_point.id == id)
+        {
+            self.continuation_points.remove(idx)
+        } else {
+            None
+        }
+    }
+
+    /// Releases all the specified continuation points by id
+    pub fn release(&mut self, ids: &[ByteString]) {
+        let ids: Vec<ContinuationPoint> = ids
+            .iter()
+            .filter_map(|id| ContinuationPoint::from_byte_string(id).ok())
+            .collect();
+        self.continuation_points
+            .retain(|continuation_point| !ids.contains(&continuation_point.id));
+    }
+
+    /// Removes continuation points that were made before the address space was last modified
+    pub fn remove_expired(&mut self, address_space: &AddressSpace) {
+        self.continuation_points.retain(|continuation_point| {
+            let valid = continuation_point.is_valid_browse_continuation_point(address_space);
+            if !valid {
+                debug!("Continuation point {:?} is no longer valid and will be removed, address space last modified = {}", continuation_point, address_space.last_modified());
+            }
+            valid
+        });
+    }
+}
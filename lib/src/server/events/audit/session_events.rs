@@ -3,7 +3,7 @@
 // Copyright (C) 2017-2022 Adam Lock
 
 use crate::crypto::X509;
-use crate::types::*;
+use crate::types::{status_code::StatusCode, *};
 
 use crate::server::{address_space::address_space::AddressSpace, events::event::Event};
 
@@ -236,10 +236,10 @@ impl AuditCreateSessionEventType {
         self
     }
 
-    pub fn client_certificate(mut self, client_certificate: &X509) -> Self {
-        self.client_certificate = client_certificate.as_byte_string();
+    pub fn client_certificate(mut self, client_certificate: &X509) -> Result<Self, StatusCode> {
+        self.client_certificate = client_certificate.as_byte_string()?;
         self.client_certificate_thumbprint = client_certificate.thumbprint().as_hex_string().into();
-        self
+        Ok(self)
     }
 
     pub fn revised_session_timeout(mut self, revised_session_timeout: Duration) -> Self {
@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: MPL-2.0
 // Copyright (C) 2017-2022 Adam Lock
 
-use std::convert::TryFrom;
+use std::{collections::HashSet, convert::TryFrom};
 
 use crate::types::{
     operand::Operand,
     service_types::{
-        ContentFilter, ContentFilterElementResult, ContentFilterResult, EventFieldList,
-        EventFilter, EventFilterResult, FilterOperator, SimpleAttributeOperand,
+        ContentFilter, ContentFilterElement, ContentFilterElementResult, ContentFilterResult,
+        EventFieldList, EventFilter, EventFilterResult, FilterOperator, SimpleAttributeOperand,
     },
     status_code::StatusCode,
     AttributeId, DateTimeUtc, NodeId, Variant,
@@ -39,6 +39,17 @@ pub fn validate(
     })
 }
 
+/// Tests if the where clause of a validated event filter is free of errors. Errors in the select
+/// clauses do not invalidate the filter, the affected fields are just returned as null.
+pub fn is_valid(event_filter_result: &EventFilterResult) -> bool {
+    event_filter_result
+        .where_clause_result
+        .element_results
+        .iter()
+        .flatten()
+        .all(|e| e.status_code.is_good())
+}
+
 /// Evaluate the event filter and see if it triggers.
 pub fn evaluate(
     object_id: &NodeId,
@@ -92,7 +103,6 @@ pub(crate) fn evaluate_where_clause(
     // Clause is meant to have been validated before now so this code is not as stringent and makes some expectations.
     if let Some(ref elements) = where_clause.elements {
         if !elements.is_empty() {
-            use std::collections::HashSet;
             let mut used_elements = HashSet::new();
             used_elements.insert(0);
             let result = operator::evaluate(
@@ -184,7 +194,7 @@ fn validate_where_clause(
     // examples using the ContentFilter structure.
 
     if let Some(ref elements) = where_clause.elements {
-        let element_results = elements.iter().map(|e| {
+        let element_results = elements.iter().enumerate().map(|(i, e)| {
             let (status_code, operand_status_codes) = if e.filter_operands.is_none() {
                 // All operators need at least one operand
                 (StatusCode::BadFilterOperandCountMismatch, None)
@@ -209,9 +219,15 @@ fn validate_where_clause(
                     FilterOperator::Cast => filter_operands.len() < 2,
                     FilterOperator::BitwiseAnd => filter_operands.len() < 2,
                     FilterOperator::BitwiseOr => filter_operands.len() < 2,
-                    _ => true,
+                    FilterOperator::InView | FilterOperator::OfType | FilterOperator::RelatedTo => false,
                 };
 
+                // Operators the server cannot evaluate
+                let operator_unsupported = matches!(
+                    e.filter_operator,
+                    FilterOperator::InView | FilterOperator::OfType | FilterOperator::RelatedTo
+                );
+
                 // Check if the operands look okay
                 let operand_status_codes = filter_operands.iter().map(|e| {
                     // Look to see if any operand cannot be parsed
@@ -228,11 +244,14 @@ fn validate_where_clause(
                                     if o.index as usize >= elements.len() {
                                         error!("Invalid element operand is out of range");
                                         StatusCode::BadFilterOperandInvalid
+                                    } else if leads_to_element(elements, o.index as usize, i) {
+                                        // Operand must not refer to its own element either directly or through
+                                        // circular references
+                                        error!("Invalid element operand refers back to element {}", i);
+                                        StatusCode::BadFilterOperandInvalid
                                     } else {
                                         StatusCode::Good
                                     }
-                                    // TODO operand should not refer to itself either directly or through circular
-                                    //  references
                                 }
                                 Operand::SimpleAttributeOperand(ref o) => {
                                     // The structure requires the node id of an event type supported
@@ -272,7 +291,10 @@ fn validate_where_clause(
                 let operator_invalid = operand_status_codes.iter().any(|e| !e.is_good());
 
                 // Check what error status to return
-                let status_code = if operand_count_mismatch {
+                let status_code = if operator_unsupported {
+                    error!("Where clause has unsupported filter operator {:?}", e.filter_operator);
+                    StatusCode::BadFilterOperatorUnsupported
+                } else if operand_count_mismatch {
                     error!("Where clause has invalid filter operand count");
                     StatusCode::BadFilterOperandCountMismatch
                 } else if operator_invalid {
@@ -303,10 +325,35 @@ fn validate_where_clause(
     }
 }
 
+/// Tests if evaluating the element at `from` would evaluate the element at `to`, by following the
+/// element operands from one element to the next.
+fn leads_to_element(elements: &[ContentFilterElement], from: usize, to: usize) -> bool {
+    let mut visited = HashSet::new();
+    let mut pending = vec![from];
+    while let Some(index) = pending.pop() {
+        if index == to {
+            return true;
+        }
+        if visited.insert(index) {
+            if let Some(filter_operands) =
+                elements.get(index).and_then(|e| e.filter_operands.as_ref())
+            {
+                pending.extend(
+                    filter_operands
+                        .iter()
+                        .filter_map(|o| match Operand::try_from(o) {
+                            Ok(Operand::ElementOperand(o)) => Some(o.index as usize),
+                            _ => None,
+                        }),
+                );
+            }
+        }
+    }
+    false
+}
+
 #[test]
 fn validate_where_clause_test() {
-    use crate::types::service_types::ContentFilterElement;
-
     let address_space = AddressSpace::new();
 
     {
@@ -388,6 +435,70 @@ fn validate_where_clause_test() {
         );
     }
 
+    // check that an element operand referring to a non-existent element is reported against
+    // that element and operand
+    {
+        let where_clause = ContentFilter {
+            elements: Some(vec![
+                ContentFilterElement::from((
+                    FilterOperator::And,
+                    vec![Operand::element(1), Operand::element(5)],
+                )),
+                ContentFilterElement::from((
+                    FilterOperator::Equals,
+                    vec![Operand::literal(10), Operand::literal(10)],
+                )),
+            ]),
+        };
+        let result = validate_where_clause(&where_clause, &address_space).unwrap();
+        let element_results = result.element_results.unwrap();
+        assert_eq!(element_results.len(), 2);
+        assert_eq!(
+            element_results[0].status_code,
+            StatusCode::BadFilterOperatorInvalid
+        );
+        assert_eq!(
+            element_results[0].operand_status_codes,
+            Some(vec![StatusCode::Good, StatusCode::BadFilterOperandInvalid])
+        );
+        assert_eq!(element_results[1].status_code, StatusCode::Good);
+    }
+
+    // check that operators which cannot be evaluated are rejected
+    {
+        let where_clause = ContentFilter {
+            elements: Some(vec![ContentFilterElement::from((
+                FilterOperator::OfType,
+                vec![Operand::literal(10)],
+            ))]),
+        };
+        let result = validate_where_clause(&where_clause, &address_space).unwrap();
+        let element_results = result.element_results.unwrap();
+        assert_eq!(
+            element_results[0].status_code,
+            StatusCode::BadFilterOperatorUnsupported
+        );
+    }
+
+    // check that element operands which lead back to their own element are rejected
+    {
+        let where_clause = ContentFilter {
+            elements: Some(vec![
+                ContentFilterElement::from((FilterOperator::Not, vec![Operand::element(1)])),
+                ContentFilterElement::from((FilterOperator::Not, vec![Operand::element(0)])),
+                ContentFilterElement::from((FilterOperator::Not, vec![Operand::element(2)])),
+            ]),
+        };
+        let result = validate_where_clause(&where_clause, &address_space).unwrap();
+        let element_results = result.element_results.unwrap();
+        element_results.iter().for_each(|e| {
+            assert_eq!(e.status_code, StatusCode::BadFilterOperatorInvalid);
+            assert_eq!(
+                e.operand_status_codes,
+                Some(vec![StatusCode::BadFilterOperandInvalid])
+            );
+        });
+    }
+
     // TODO check operands are compatible with operator
-    // TODO check for ElementOperands which are cyclical or out of range
 }
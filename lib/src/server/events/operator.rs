@@ -16,10 +16,13 @@ use crate::types::{
     VariantTypeId,
 };
 
-use crate::server::address_space::{
-    node::{NodeBase, NodeType},
-    relative_path::find_node_from_browse_path,
-    AddressSpace,
+use crate::server::{
+    address_space::{
+        node::{NodeBase, NodeType},
+        relative_path::find_node_from_browse_path,
+        AddressSpace,
+    },
+    constants,
 };
 
 /// Turns a list of operands inside extension objects to their analogous Operand objects
@@ -263,17 +266,23 @@ pub(crate) fn value_of(
             if used_elements.contains(&o.index) {
                 error!("Operator contains elements that have already been used cyclical and is invalid");
                 Err(StatusCode::BadFilterOperandInvalid)
-            } else {
-                used_elements.insert(o.index);
-                let result = evaluate(
-                    object_id,
-                    &elements[o.index as usize],
-                    used_elements,
-                    elements,
-                    address_space,
+            } else if used_elements.len() >= constants::MAX_CONTENT_FILTER_DEPTH {
+                error!(
+                    "Operator nests elements deeper than the maximum of {} and is invalid",
+                    constants::MAX_CONTENT_FILTER_DEPTH
                 );
+                Err(StatusCode::BadFilterOperandInvalid)
+            } else if let Some(element) = elements.get(o.index as usize) {
+                used_elements.insert(o.index);
+                let result = evaluate(object_id, element, used_elements, elements, address_space);
                 used_elements.remove(&o.index);
                 result
+            } else {
+                error!(
+                    "Operator refers to element {} which does not exist",
+                    o.index
+                );
+                Err(StatusCode::BadFilterOperandInvalid)
             }
         }
         Operand::LiteralOperand(ref o) => Ok(o.value.clone()),
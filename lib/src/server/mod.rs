@@ -130,6 +130,9 @@ pub mod constants {
     pub const MAX_HISTORY_CONTINUATION_POINTS: usize = 10;
     /// Maximum query continuation points
     pub const MAX_QUERY_CONTINUATION_POINTS: usize = 10;
+    /// Maximum depth that ContentFilter elements may nest through element operands before the
+    /// filter is rejected
+    pub const MAX_CONTENT_FILTER_DEPTH: usize = 16;
 
     /// Maximum number of nodes in a TranslateBrowsePathsToNodeIdsRequest
     pub const MAX_NODES_PER_TRANSLATE_BROWSE_PATHS_TO_NODE_IDS: usize = 10;
@@ -19,17 +19,9 @@ use crate::sync::*;
 use crate::types::service_types::ServerState as ServerStateType;
 
 use crate::server::{
-    address_space::types::AddressSpace,
-    comms::tcp_transport::*,
-    comms::transport::Transport,
-    config::ServerConfig,
-    constants,
-    diagnostics::ServerDiagnostics,
-    events::audit::AuditLog,
-    metrics::ServerMetrics,
-    session::SessionManager,
-    state::{OperationalLimits, ServerState},
-    util::PollingAction,
+    address_space::types::AddressSpace, comms::tcp_transport::*, comms::transport::Transport,
+    config::ServerConfig, constants, diagnostics::ServerDiagnostics, events::audit::AuditLog,
+    metrics::ServerMetrics, session::SessionManager, state::ServerState, util::PollingAction,
 };
 
 pub type Connections = Vec<Arc<RwLock<TcpTransport>>>;
@@ -114,6 +106,7 @@ impl Server {
         let min_sampling_interval_ms = config.limits.min_sampling_interval * 1000.0;
         let send_buffer_size = config.limits.send_buffer_size;
         let receive_buffer_size = config.limits.receive_buffer_size;
+        let operational_limits = config.limits.operational_limits.clone();
 
         // Security, pki auto create cert
         let application_description = if config.create_sample_keypair {
@@ -181,7 +174,7 @@ impl Server {
             unregister_nodes_callback: None,
             historical_data_provider: None,
             historical_event_provider: None,
-            operational_limits: OperationalLimits::default(),
+            operational_limits,
             send_buffer_size,
             receive_buffer_size,
         };
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: MPL-2.0
 // Copyright (C) 2017-2022 Adam Lock
 
-use std::{result::Result, sync::Arc};
+use std::{convert::TryFrom, result::Result, sync::Arc};
 
 use crate::{
     core::supported_message::SupportedMessage,
@@ -14,7 +14,7 @@ use crate::server::{
     address_space::{
         node::{HasNodeId, NodeBase, NodeType},
         variable::Variable,
-        AddressSpace, UserAccessLevel,
+        AccessLevel, AddressSpace, UserAccessLevel,
     },
     services::Service,
     session::Session,
@@ -168,10 +168,16 @@ impl AttributeService {
 
             let nodes_to_write = request.nodes_to_write.as_ref().unwrap();
             if nodes_to_write.len() <= server_state.operational_limits.max_nodes_per_write {
+                let decoding_options = server_state.decoding_options();
                 let results = nodes_to_write
                     .iter()
                     .map(|node_to_write| {
-                        Self::write_node_value(&session, &mut address_space, node_to_write)
+                        Self::write_node_value(
+                            &session,
+                            &mut address_space,
+                            node_to_write,
+                            &decoding_options,
+                        )
                     })
                     .collect();
 
@@ -422,8 +428,6 @@ impl AttributeService {
         address_space: Arc<RwLock<AddressSpace>>,
         request: &HistoryReadRequest,
     ) -> Result<Vec<HistoryReadResult>, StatusCode> {
-        // TODO enforce operation limits
-
         // Validate the action being performed
         let nodes_to_read = &request.nodes_to_read.as_ref().unwrap();
         let timestamps_to_return = request.timestamps_to_return;
@@ -432,6 +436,25 @@ impl AttributeService {
             Self::decode_history_read_details(&request.history_read_details, decoding_options)?;
 
         let server_state = trace_read_lock!(server_state);
+        let max_nodes_per_history_read = match read_details {
+            ReadDetails::ReadEventDetails(_) => {
+                server_state
+                    .operational_limits
+                    .max_nodes_per_history_read_events
+            }
+            _ => {
+                server_state
+                    .operational_limits
+                    .max_nodes_per_history_read_data
+            }
+        };
+        if nodes_to_read.len() > max_nodes_per_history_read {
+            warn!(
+                "HistoryReadRequest too many nodes to read {}",
+                nodes_to_read.len()
+            );
+            return Err(StatusCode::BadTooManyOperations);
+        }
         let results = match read_details {
             ReadDetails::ReadEventDetails(details) => {
                 let historical_event_provider = server_state
@@ -546,7 +569,7 @@ impl AttributeService {
                     // Caller must request binary
                     debug!("read_node_value result for read node id {}, attribute {} is invalid data encoding", node_to_read.node_id, node_to_read.attribute_id);
                     result_value.status = Some(StatusCode::BadDataEncodingInvalid);
-                } else if let Some(attribute) = node.as_node().get_attribute_max_age(
+                } else if let Ok(attribute) = node.read_attribute(
                     timestamps_to_return,
                     attribute_id,
                     index_range,
@@ -653,16 +676,41 @@ impl AttributeService {
         Self::user_access_level(session, node, attribute_id).contains(UserAccessLevel::CURRENT_READ)
     }
 
-    fn is_writable(session: &Session, node: &NodeType, attribute_id: AttributeId) -> bool {
-        // TODO session for current user
-        // For a variable, the access level controls access to the variable
-        if let NodeType::Variable(_) = node {
+    /// Checks if the attribute of the node can be written to, returning the status code that the
+    /// write should fail with if it cannot.
+    ///
+    /// For a variable's value, the `AccessLevel` must contain `CURRENT_WRITE` or the result is
+    /// `BadNotWritable`. If the access level permits writing but the `UserAccessLevel` for the
+    /// session does not, the result is `BadUserAccessDenied`. All other attributes are governed by
+    /// the node's write mask.
+    fn check_writable(
+        session: &Session,
+        node: &NodeType,
+        attribute_id: AttributeId,
+    ) -> Result<(), StatusCode> {
+        if let NodeType::Variable(ref variable) = node {
             if attribute_id == AttributeId::Value {
-                return Self::user_access_level(session, node, attribute_id)
-                    .contains(UserAccessLevel::CURRENT_WRITE);
+                return if !variable.access_level().contains(AccessLevel::CURRENT_WRITE) {
+                    Err(StatusCode::BadNotWritable)
+                } else if !Self::user_access_level(session, node, attribute_id)
+                    .contains(UserAccessLevel::CURRENT_WRITE)
+                {
+                    Err(StatusCode::BadUserAccessDenied)
+                } else {
+                    Ok(())
+                };
             }
         }
+        if Self::is_writable(node, attribute_id) {
+            Ok(())
+        } else {
+            Err(StatusCode::BadNotWritable)
+        }
+    }
 
+    /// Tests the write mask of the node to see if the attribute is writable. The value of a
+    /// variable is not controlled by the write mask, see `check_writable`.
+    fn is_writable(node: &NodeType, attribute_id: AttributeId) -> bool {
         if let Some(write_mask) = node.as_node().write_mask() {
             match attribute_id {
                 AttributeId::Value => {
@@ -733,6 +781,15 @@ impl AttributeService {
         let valid = if let Variant::Empty = value {
             // Assigning an empty value is permissible
             true
+        } else if let Variant::ExtensionObject(ref extension_object) = value {
+            // The structure's encoding id must be an encoding of the node's data type or a subtype
+            if let Some(value_data_type) =
+                address_space.data_type_of_encoding(&extension_object.node_id)
+            {
+                address_space.is_subtype(&value_data_type, &node_data_type)
+            } else {
+                false
+            }
         } else if let Some(value_data_type) = value.scalar_data_type() {
             // Value is scalar. Check if the data type matches
             let data_type_matches = address_space.is_subtype(&value_data_type, &node_data_type);
@@ -769,17 +826,109 @@ impl AttributeService {
         valid
     }
 
+    /// Coerces a scalar numeric value to the numeric data type of the variable. The coercion is
+    /// only permitted if it is lossless, i.e. the result has exactly the value that was written.
+    /// Returns `None` if the value cannot be coerced.
+    fn coerce_value_to_write(variable: &Variable, value: &Variant) -> Option<Variant> {
+        let value_type = value.type_id();
+        let target_type = VariantTypeId::try_from(&variable.data_type()).ok()?;
+        if value_type == target_type || !value_type.is_numeric() || !target_type.is_numeric() {
+            None
+        } else {
+            let coerced = value.cast(target_type);
+            if coerced.type_id() == target_type && Self::numeric_values_equal(value, &coerced) {
+                debug!(
+                    "Value {:?} is coerced to {:?} to write to var {}",
+                    value,
+                    target_type,
+                    variable.node_id()
+                );
+                Some(coerced)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Compares two numeric values exactly, whatever their types. Casting a value back to its own
+    /// type is not enough to tell a lossless coercion, because casts saturate and round, e.g.
+    /// `u64::MAX` rounds up to 2^64 as a `Double`, which casts back to `u64::MAX` again.
+    fn numeric_values_equal(a: &Variant, b: &Variant) -> bool {
+        match (Self::integer_value(a), Self::integer_value(b)) {
+            (Some(a), Some(b)) => a == b,
+            (Some(i), None) => Self::float_value(b).map_or(false, |f| Self::float_is(f, i)),
+            (None, Some(i)) => Self::float_value(a).map_or(false, |f| Self::float_is(f, i)),
+            (None, None) => match (Self::float_value(a), Self::float_value(b)) {
+                (Some(a), Some(b)) => a == b || (a.is_nan() && b.is_nan()),
+                _ => false,
+            },
+        }
+    }
+
+    /// Tests if the floating point value is exactly the integer. Every integer variant fits in an
+    /// `i128`, and an integral `f64` smaller than 2^127 converts to one exactly.
+    fn float_is(f: f64, i: i128) -> bool {
+        f.is_finite() && f.fract() == 0.0 && f.abs() < 2f64.powi(127) && f as i128 == i
+    }
+
+    fn integer_value(value: &Variant) -> Option<i128> {
+        match *value {
+            Variant::SByte(v) => Some(v as i128),
+            Variant::Byte(v) => Some(v as i128),
+            Variant::Int16(v) => Some(v as i128),
+            Variant::UInt16(v) => Some(v as i128),
+            Variant::Int32(v) => Some(v as i128),
+            Variant::UInt32(v) => Some(v as i128),
+            Variant::Int64(v) => Some(v as i128),
+            Variant::UInt64(v) => Some(v as i128),
+            _ => None,
+        }
+    }
+
+    fn float_value(value: &Variant) -> Option<f64> {
+        match *value {
+            Variant::Float(v) => Some(v as f64),
+            Variant::Double(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Extension objects of generated structures are decoded and encoded again so the value stored
+    /// in the address space is in canonical form. A body that does not decode as the structure
+    /// its encoding id says it is, is a type mismatch. Unknown structures are stored unchanged.
+    fn canonicalize_value_to_write(
+        value: &Variant,
+        decoding_options: &DecodingOptions,
+    ) -> Result<Variant, StatusCode> {
+        if let Variant::ExtensionObject(ref extension_object) = value {
+            match reencode_extension_object(extension_object, decoding_options) {
+                Some(Ok(extension_object)) => Ok(Variant::from(extension_object)),
+                Some(Err(_)) => {
+                    error!(
+                        "Extension object body does not decode as the structure of its encoding id {}",
+                        extension_object.node_id
+                    );
+                    Err(StatusCode::BadTypeMismatch)
+                }
+                None => Ok(value.clone()),
+            }
+        } else {
+            Ok(value.clone())
+        }
+    }
+
     fn write_node_value(
         session: &Session,
         address_space: &mut AddressSpace,
         node_to_write: &WriteValue,
+        decoding_options: &DecodingOptions,
     ) -> StatusCode {
         if let Some(node) = address_space.find_node(&node_to_write.node_id) {
             if let Ok(attribute_id) = AttributeId::from_u32(node_to_write.attribute_id) {
                 let index_range = node_to_write.index_range.as_ref().parse::<NumericRange>();
 
-                if !Self::is_writable(session, node, attribute_id) {
-                    StatusCode::BadNotWritable
+                if let Err(status_code) = Self::check_writable(session, node, attribute_id) {
+                    status_code
                 } else if attribute_id != AttributeId::Value && !node_to_write.index_range.is_null()
                 {
                     // Index ranges are not supported on anything other than a value attribute
@@ -795,6 +944,22 @@ impl AttributeService {
                 } else if let Some(ref value) = node_to_write.value.value {
                     let index_range = index_range.unwrap();
 
+                    // A value of a different numeric type may be coerced to the variable's type
+                    let coerced_value = match node {
+                        NodeType::Variable(ref variable)
+                            if attribute_id == AttributeId::Value
+                                && !Self::validate_value_to_write(
+                                    address_space,
+                                    variable,
+                                    value,
+                                ) =>
+                        {
+                            Self::coerce_value_to_write(variable, value)
+                        }
+                        _ => None,
+                    };
+                    let value = coerced_value.as_ref().unwrap_or(value);
+
                     // This is a band-aid for Variable::Value which should check if the data type
                     // matches the written value. Note, that ALL attributes should check for subtypes
                     // but they don't. There should be a general purpose fn attribute_type(attribute_id) helper
@@ -816,15 +981,17 @@ impl AttributeService {
                         let node = address_space.find_node_mut(&node_to_write.node_id).unwrap();
                         let result = if attribute_id == AttributeId::Value {
                             match node {
-                                NodeType::Variable(ref mut variable) => variable
-                                    .set_value(index_range, value.clone())
-                                    .map_err(|err| {
-                                        error!(
+                                NodeType::Variable(ref mut variable) => {
+                                    Self::canonicalize_value_to_write(value, decoding_options)
+                                        .and_then(|value| variable.set_value(index_range, value))
+                                        .map_err(|err| {
+                                            error!(
                                             "Value could not be set to node {} Value, error = {}",
                                             node_to_write.node_id, err
                                         );
-                                        err
-                                    }),
+                                            err
+                                        })
+                                }
                                 _ => Err(StatusCode::BadAttributeIdInvalid),
                             }
                         } else {
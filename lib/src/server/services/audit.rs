@@ -51,7 +51,16 @@ pub fn log_create_session(
 
         // Client certificate info
         if let Some(ref client_certificate) = session.client_certificate() {
-            event.client_certificate(client_certificate)
+            match event.client_certificate(client_certificate) {
+                Ok(event) => event,
+                Err(status_code) => {
+                    error!(
+                        "Cannot raise create session audit event, client certificate cannot be encoded, error {}",
+                        status_code
+                    );
+                    return;
+                }
+            }
         } else {
             event
         }
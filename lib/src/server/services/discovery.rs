@@ -38,12 +38,14 @@ impl DiscoveryService {
 
         // TODO audit - generate event for failed service invocation
 
-        let endpoints = server_state.endpoints(&request.endpoint_url, &request.profile_uris);
-        GetEndpointsResponse {
-            response_header: ResponseHeader::new_good(&request.request_header),
-            endpoints,
+        match server_state.endpoints(&request.endpoint_url, &request.profile_uris) {
+            Ok(endpoints) => GetEndpointsResponse {
+                response_header: ResponseHeader::new_good(&request.request_header),
+                endpoints,
+            }
+            .into(),
+            Err(status_code) => self.service_fault(&request.request_header, status_code),
         }
-        .into()
     }
 
     pub fn register_server(
@@ -368,23 +368,28 @@ impl MessageHandler {
                 }),
             SupportedMessage::SetMonitoringModeRequest(request) => {
                 self.validate_service_request(message, SET_MONITORING_MODE_COUNT, |session, _| {
-                    Some(
-                        self.monitored_item_service
-                            .set_monitoring_mode(session, request),
-                    )
+                    Some(self.monitored_item_service.set_monitoring_mode(
+                        server_state,
+                        session,
+                        request,
+                    ))
                 })
             }
             SupportedMessage::SetTriggeringRequest(request) => {
                 self.validate_service_request(message, SET_TRIGGERING_COUNT, |session, _| {
-                    Some(self.monitored_item_service.set_triggering(session, request))
+                    Some(
+                        self.monitored_item_service
+                            .set_triggering(server_state, session, request),
+                    )
                 })
             }
             SupportedMessage::DeleteMonitoredItemsRequest(request) => self
                 .validate_service_request(message, DELETE_MONITORED_ITEMS_COUNT, |session, _| {
-                    Some(
-                        self.monitored_item_service
-                            .delete_monitored_items(session, request),
-                    )
+                    Some(self.monitored_item_service.delete_monitored_items(
+                        server_state,
+                        session,
+                        request,
+                    ))
                 }),
 
             // Subscription Service Set, OPC UA Part 4, Section 5.13
@@ -42,9 +42,17 @@ impl MonitoredItemService {
             let address_space = trace_read_lock!(address_space);
 
             let items_to_create = request.items_to_create.as_ref().unwrap();
-            // Find subscription and add items to it
-            if let Some(subscription) = session.subscriptions_mut().get_mut(request.subscription_id)
+            if items_to_create.len() > server_state.operational_limits.max_monitored_items_per_call
             {
+                warn!(
+                    "CreateMonitoredItemsRequest too many items to create {}",
+                    items_to_create.len()
+                );
+                self.service_fault(&request.request_header, StatusCode::BadTooManyOperations)
+            } else if let Some(subscription) =
+                session.subscriptions_mut().get_mut(request.subscription_id)
+            {
+                // Find subscription and add items to it
                 let now = chrono::Utc::now();
                 let results = Some(subscription.create_monitored_items(
                     &server_state,
@@ -86,7 +94,15 @@ impl MonitoredItemService {
             let items_to_modify = request.items_to_modify.as_ref().unwrap();
             // Find subscription and modify items in it
             let subscription_id = request.subscription_id;
-            if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id) {
+            if items_to_modify.len() > server_state.operational_limits.max_monitored_items_per_call
+            {
+                warn!(
+                    "ModifyMonitoredItemsRequest too many items to modify {}",
+                    items_to_modify.len()
+                );
+                self.service_fault(&request.request_header, StatusCode::BadTooManyOperations)
+            } else if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id)
+            {
                 let results = Some(subscription.modify_monitored_items(
                     &server_state,
                     &address_space,
@@ -112,16 +128,27 @@ impl MonitoredItemService {
     /// Implementation of SetMonitoringMode service. See OPC Unified Architecture, Part 4 5.12.4
     pub fn set_monitoring_mode(
         &self,
+        server_state: Arc<RwLock<ServerState>>,
         session: Arc<RwLock<Session>>,
         request: &SetMonitoringModeRequest,
     ) -> SupportedMessage {
         if is_empty_option_vec!(request.monitored_item_ids) {
             self.service_fault(&request.request_header, StatusCode::BadNothingToDo)
         } else {
+            let server_state = trace_read_lock!(server_state);
             let mut session = trace_write_lock!(session);
             let monitored_item_ids = request.monitored_item_ids.as_ref().unwrap();
             let subscription_id = request.subscription_id;
-            if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id) {
+            if monitored_item_ids.len()
+                > server_state.operational_limits.max_monitored_items_per_call
+            {
+                warn!(
+                    "SetMonitoringModeRequest too many monitored items {}",
+                    monitored_item_ids.len()
+                );
+                self.service_fault(&request.request_header, StatusCode::BadTooManyOperations)
+            } else if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id)
+            {
                 let monitoring_mode = request.monitoring_mode;
                 let results = monitored_item_ids
                     .iter()
@@ -145,6 +172,7 @@ impl MonitoredItemService {
     /// Implementation of SetTriggering service. See OPC Unified Architecture, Part 4 5.12.5
     pub fn set_triggering(
         &self,
+        server_state: Arc<RwLock<ServerState>>,
         session: Arc<RwLock<Session>>,
         request: &SetTriggeringRequest,
     ) -> SupportedMessage {
@@ -153,6 +181,7 @@ impl MonitoredItemService {
         {
             self.service_fault(&request.request_header, StatusCode::BadNothingToDo)
         } else {
+            let server_state = trace_read_lock!(server_state);
             let mut session = trace_write_lock!(session);
             let links_to_add = match request.links_to_add {
                 Some(ref links_to_add) => &links_to_add[..],
@@ -165,7 +194,19 @@ impl MonitoredItemService {
 
             // Set the triggering on the subscription.
             let subscription_id = request.subscription_id;
-            if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id) {
+            let max_monitored_items_per_call =
+                server_state.operational_limits.max_monitored_items_per_call;
+            if links_to_add.len() > max_monitored_items_per_call
+                || links_to_remove.len() > max_monitored_items_per_call
+            {
+                warn!(
+                    "SetTriggeringRequest too many links to add {} or remove {}",
+                    links_to_add.len(),
+                    links_to_remove.len()
+                );
+                self.service_fault(&request.request_header, StatusCode::BadTooManyOperations)
+            } else if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id)
+            {
                 match subscription.set_triggering(
                     request.triggering_item_id,
                     links_to_add,
@@ -203,17 +244,28 @@ impl MonitoredItemService {
     /// Implementation of DeleteMonitoredItems service. See OPC Unified Architecture, Part 4 5.12.6
     pub fn delete_monitored_items(
         &self,
+        server_state: Arc<RwLock<ServerState>>,
         session: Arc<RwLock<Session>>,
         request: &DeleteMonitoredItemsRequest,
     ) -> SupportedMessage {
         if is_empty_option_vec!(request.monitored_item_ids) {
             self.service_fault(&request.request_header, StatusCode::BadNothingToDo)
         } else {
+            let server_state = trace_read_lock!(server_state);
             let mut session = trace_write_lock!(session);
             let monitored_item_ids = request.monitored_item_ids.as_ref().unwrap();
             // Find subscription and delete items from it
             let subscription_id = request.subscription_id;
-            if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id) {
+            if monitored_item_ids.len()
+                > server_state.operational_limits.max_monitored_items_per_call
+            {
+                warn!(
+                    "DeleteMonitoredItemsRequest too many monitored items {}",
+                    monitored_item_ids.len()
+                );
+                self.service_fault(&request.request_header, StatusCode::BadTooManyOperations)
+            } else if let Some(subscription) = session.subscriptions_mut().get_mut(subscription_id)
+            {
                 let results = Some(subscription.delete_monitored_items(monitored_item_ids));
                 let diagnostic_infos = None;
                 let response = DeleteMonitoredItemsResponse {
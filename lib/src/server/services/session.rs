@@ -46,7 +46,23 @@ impl SessionService {
 
         debug!("Create session request {:?}", request);
 
-        let endpoints = server_state.new_endpoint_descriptions(request.endpoint_url.as_ref());
+        let (endpoints, server_certificate) = match server_state
+            .new_endpoint_descriptions(request.endpoint_url.as_ref())
+            .and_then(|endpoints| {
+                Ok((endpoints, server_state.server_certificate_as_byte_string()?))
+            }) {
+            Ok(result) => result,
+            Err(status_code) => {
+                error!(
+                    "Create session cannot describe the server's endpoints, error {}",
+                    status_code
+                );
+                return (
+                    None,
+                    self.service_fault(&request.request_header, status_code),
+                );
+            }
+        };
 
         // Check the args
         let service_result = {
@@ -143,7 +159,7 @@ impl SessionService {
 
                 // Calculate a signature (assuming there is a pkey)
                 let server_signature = if let Some(ref pkey) = server_state.server_pkey {
-                    crypto::create_signature_data(pkey, security_policy, &request.client_certificate, &request.client_nonce)
+                    crypto::create_server_signature(pkey, &request.client_certificate, request.client_nonce.as_ref(), security_policy)
                         .unwrap_or_else(|err| {
                             error!("Cannot create signature data from private key, check log and error {:?}", err);
                             SignatureData::null()
@@ -154,7 +170,6 @@ impl SessionService {
 
                 let authentication_token = NodeId::new(0, random::byte_string(32));
                 let server_nonce = security_policy.random_nonce();
-                let server_certificate = server_state.server_certificate_as_byte_string();
                 let server_endpoints = Some(endpoints);
 
                 session.set_authentication_token(authentication_token.clone());
@@ -415,13 +430,19 @@ impl SessionService {
     ) -> StatusCode {
         if let Some(ref client_certificate) = session.client_certificate() {
             if let Some(ref server_certificate) = server_state.server_certificate {
-                crypto::verify_signature_data(
-                    client_signature,
-                    security_policy,
-                    client_certificate,
-                    server_certificate,
-                    session.session_nonce().as_ref(),
-                )
+                match server_certificate.as_byte_string() {
+                    Ok(server_certificate) => crypto::verify_client_signature(
+                        client_signature,
+                        security_policy,
+                        client_certificate,
+                        &server_certificate,
+                        session.session_nonce().as_ref(),
+                    ),
+                    Err(_) => {
+                        error!("Client signature verification failed, server certificate cannot be encoded");
+                        StatusCode::BadUnexpectedError
+                    }
+                }
             } else {
                 error!("Client signature verification failed, server has no server certificate");
                 StatusCode::BadUnexpectedError
@@ -6,13 +6,11 @@ use std::result::Result;
 use std::sync::Arc;
 
 use crate::core::supported_message::SupportedMessage;
-use crate::crypto::random;
 use crate::sync::*;
 use crate::types::{node_ids::ReferenceTypeId, status_code::StatusCode, *};
 
 use crate::server::{
     address_space::{relative_path, AddressSpace},
-    continuation_point::BrowseContinuationPoint,
     services::Service,
     session::Session,
     state::ServerState,
@@ -478,7 +476,7 @@ impl ViewService {
         {
             debug!(
                 "Browsing from continuation point {}",
-                continuation_point.id.as_base64()
+                continuation_point.id.to_byte_string().as_base64()
             );
             let reference_descriptions = continuation_point.reference_descriptions.lock();
             // Use the existing result. This may result in another continuation point being created
@@ -486,7 +484,7 @@ impl ViewService {
                 session,
                 address_space,
                 &reference_descriptions,
-                continuation_point.starting_index,
+                continuation_point.starting_index(),
                 continuation_point.max_references_per_node,
             )
         } else {
@@ -524,18 +522,24 @@ impl ViewService {
             //  be factored to allow for that
 
             // Create a continuation point for the remainder of the result. The point will hold the entire result
-            let continuation_point = random::byte_string(6);
+            let continuation_point = match session.add_browse_continuation_point(
+                address_space,
+                max_references_per_node,
+                next_starting_index,
+                reference_descriptions.to_vec(),
+            ) {
+                Ok(continuation_point) => continuation_point,
+                Err(status_code) => {
+                    return BrowseResult {
+                        status_code,
+                        continuation_point: ByteString::null(),
+                        references: None,
+                    };
+                }
+            };
 
             debug!("References remaining {} exceeds max references {}, returning range {}..{} and creating new continuation point {}", references_remaining, max_references_per_node, starting_index, next_starting_index, continuation_point.as_base64());
 
-            session.add_browse_continuation_point(BrowseContinuationPoint {
-                id: continuation_point.clone(),
-                address_space_last_modified: address_space.last_modified(),
-                max_references_per_node,
-                starting_index: next_starting_index,
-                reference_descriptions: Arc::new(Mutex::new(reference_descriptions.to_vec())),
-            });
-
             (reference_descriptions_slice, continuation_point)
         } else {
             // Returns the remainder of the results
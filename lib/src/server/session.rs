@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: MPL-2.0
 // Copyright (C) 2017-2022 Adam Lock
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::HashMap,
     sync::{
         atomic::{AtomicI32, Ordering},
         Arc,
@@ -17,7 +17,7 @@ use crate::types::{service_types::PublishRequest, status_code::StatusCode, *};
 
 use crate::server::{
     address_space::{AddressSpace, UserAccessLevel},
-    continuation_point::BrowseContinuationPoint,
+    continuation_point::{BrowseContinuationPoint, ContinuationPointManager},
     diagnostics::ServerDiagnostics,
     identity_token::IdentityToken,
     session_diagnostics::SessionDiagnostics,
@@ -179,10 +179,8 @@ pub struct Session {
     max_response_message_size: u32,
     /// Endpoint url for this session
     endpoint_url: UAString,
-    /// Maximum number of continuation points
-    max_browse_continuation_points: usize,
-    /// Browse continuation points (oldest to newest)
-    browse_continuation_points: VecDeque<BrowseContinuationPoint>,
+    /// Browse continuation points
+    browse_continuation_points: ContinuationPointManager,
     /// Diagnostics associated with the server
     diagnostics: Arc<RwLock<ServerDiagnostics>>,
     /// Diagnostics associated with the session
@@ -235,8 +233,9 @@ impl Session {
             max_request_message_size: 0,
             max_response_message_size: 0,
             endpoint_url: UAString::null(),
-            max_browse_continuation_points,
-            browse_continuation_points: VecDeque::with_capacity(max_browse_continuation_points),
+            browse_continuation_points: ContinuationPointManager::new(
+                max_browse_continuation_points,
+            ),
             can_modify_address_space: true,
             diagnostics: Arc::new(RwLock::new(ServerDiagnostics::default())),
             session_diagnostics: Arc::new(RwLock::new(SessionDiagnostics::default())),
@@ -281,8 +280,9 @@ impl Session {
             max_request_message_size: 0,
             max_response_message_size: 0,
             endpoint_url: UAString::null(),
-            max_browse_continuation_points,
-            browse_continuation_points: VecDeque::with_capacity(max_browse_continuation_points),
+            browse_continuation_points: ContinuationPointManager::new(
+                max_browse_continuation_points,
+            ),
             can_modify_address_space,
             diagnostics,
             session_diagnostics: Arc::new(RwLock::new(SessionDiagnostics::default())),
@@ -458,20 +458,21 @@ impl Session {
         self.subscriptions.expire_stale_publish_requests(now);
     }
 
+    /// Stores the references from the starting index onwards in a new continuation point and
+    /// returns its id. Fails with `BadNoContinuationPoints` if the session holds too many.
     pub(crate) fn add_browse_continuation_point(
         &mut self,
-        continuation_point: BrowseContinuationPoint,
-    ) {
-        // Remove excess browse continuation points
-        while self.browse_continuation_points.len() >= self.max_browse_continuation_points {
-            let continuation_point = self.browse_continuation_points.pop_front();
-            debug!(
-                "Removing old continuation point {} to make way for new one",
-                continuation_point.unwrap().id.as_base64()
-            );
-        }
-        self.browse_continuation_points
-            .push_back(continuation_point);
+        address_space: &AddressSpace,
+        max_references_per_node: usize,
+        starting_index: usize,
+        reference_descriptions: Vec<ReferenceDescription>,
+    ) -> Result<ByteString, StatusCode> {
+        self.browse_continuation_points.add(
+            address_space,
+            max_references_per_node,
+            starting_index,
+            reference_descriptions,
+        )
     }
 
     /// Finds and REMOVES a continuation point by id.
@@ -479,38 +480,20 @@ impl Session {
         &mut self,
         id: &ByteString,
     ) -> Option<BrowseContinuationPoint> {
-        if let Some(idx) = self
-            .browse_continuation_points
-            .iter()
-            .position(|continuation_point| continuation_point.id == *id)
-        {
-            self.browse_continuation_points.remove(idx)
-        } else {
-            None
-        }
+        self.browse_continuation_points.take(id)
     }
 
     pub(crate) fn remove_expired_browse_continuation_points(
         &mut self,
         address_space: &AddressSpace,
     ) {
-        self.browse_continuation_points.retain(|continuation_point| {
-            let valid = continuation_point.is_valid_browse_continuation_point(address_space);
-            if !valid {
-                debug!("Continuation point {:?} is no longer valid and will be removed, address space last modified = {}", continuation_point, address_space.last_modified());
-            }
-            valid
-        });
+        self.browse_continuation_points
+            .remove_expired(address_space);
     }
 
     /// Remove all the specified continuation points by id
     pub(crate) fn remove_browse_continuation_points(&mut self, continuation_points: &[ByteString]) {
-        // Turn the supplied slice into a set
-        let continuation_points_set: HashSet<ByteString> =
-            continuation_points.iter().cloned().collect();
-        // Now remove any continuation points that are part of that set
-        self.browse_continuation_points
-            .retain(|continuation_point| !continuation_points_set.contains(&continuation_point.id));
+        self.browse_continuation_points.release(continuation_points);
     }
 
     pub(crate) fn can_modify_address_space(&self) -> bool {
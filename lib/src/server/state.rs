@@ -21,8 +21,7 @@ use crate::types::{
 
 use crate::server::{
     callbacks::{RegisterNodes, UnregisterNodes},
-    config::{ServerConfig, ServerEndpoint},
-    constants,
+    config::{OperationalLimits, ServerConfig, ServerEndpoint},
     diagnostics::ServerDiagnostics,
     events::{
         audit::{AuditEvent, AuditLog},
@@ -30,46 +29,11 @@ use crate::server::{
     },
     historical::{HistoricalDataProvider, HistoricalEventProvider},
     identity_token::{
-        IdentityToken, POLICY_ID_ANONYMOUS, POLICY_ID_USER_PASS_NONE, POLICY_ID_USER_PASS_RSA_15,
+        IdentityToken, POLICY_ID_USER_PASS_NONE, POLICY_ID_USER_PASS_RSA_15,
         POLICY_ID_USER_PASS_RSA_OAEP, POLICY_ID_X509,
     },
 };
 
-pub(crate) struct OperationalLimits {
-    pub max_nodes_per_translate_browse_paths_to_node_ids: usize,
-    pub max_nodes_per_read: usize,
-    pub max_nodes_per_write: usize,
-    pub max_nodes_per_method_call: usize,
-    pub max_nodes_per_browse: usize,
-    pub max_nodes_per_register_nodes: usize,
-    pub max_nodes_per_node_management: usize,
-    pub max_monitored_items_per_call: usize,
-    pub max_nodes_per_history_read_data: usize,
-    pub max_nodes_per_history_read_events: usize,
-    pub max_nodes_per_history_update_data: usize,
-    pub max_nodes_per_history_update_events: usize,
-}
-
-impl Default for OperationalLimits {
-    fn default() -> Self {
-        Self {
-            max_nodes_per_translate_browse_paths_to_node_ids:
-                constants::MAX_NODES_PER_TRANSLATE_BROWSE_PATHS_TO_NODE_IDS,
-            max_nodes_per_read: constants::MAX_NODES_PER_READ,
-            max_nodes_per_write: constants::MAX_NODES_PER_WRITE,
-            max_nodes_per_method_call: constants::MAX_NODES_PER_METHOD_CALL,
-            max_nodes_per_browse: constants::MAX_NODES_PER_BROWSE,
-            max_nodes_per_register_nodes: constants::MAX_NODES_PER_REGISTER_NODES,
-            max_nodes_per_node_management: constants::MAX_NODES_PER_NODE_MANAGEMENT,
-            max_monitored_items_per_call: constants::MAX_MONITORED_ITEMS_PER_CALL,
-            max_nodes_per_history_read_data: constants::MAX_NODES_PER_HISTORY_READ_DATA,
-            max_nodes_per_history_read_events: constants::MAX_NODES_PER_HISTORY_READ_EVENTS,
-            max_nodes_per_history_update_data: constants::MAX_NODES_PER_HISTORY_UPDATE_DATA,
-            max_nodes_per_history_update_events: constants::MAX_NODES_PER_HISTORY_UPDATE_EVENTS,
-        }
-    }
-}
-
 /// Server state is any state associated with the server as a whole that individual sessions might
 /// be interested in. That includes configuration info etc.
 pub struct ServerState {
@@ -135,11 +99,14 @@ pub struct ServerState {
 }
 
 impl ServerState {
+    /// Returns the endpoints the server offers over the transport profiles the caller asked for,
+    /// or `None` if the server does not support any of them. It is an error if the server
+    /// certificate sent with each endpoint cannot be encoded.
     pub fn endpoints(
         &self,
         endpoint_url: &UAString,
         transport_profile_uris: &Option<Vec<UAString>>,
-    ) -> Option<Vec<EndpointDescription>> {
+    ) -> Result<Option<Vec<EndpointDescription>>, StatusCode> {
         // Filter endpoints based on profile_uris
         debug!(
             "Endpoints requested, transport profile uris {:?}",
@@ -157,7 +124,7 @@ impl ServerState {
                         "Client wants to connect with a non binary transport {:#?}",
                         transport_profile_uris
                     );
-                    return None;
+                    return Ok(None);
                 }
             }
         }
@@ -171,17 +138,17 @@ impl ServerState {
                 .endpoints
                 .iter()
                 .map(|(_, e)| self.new_endpoint_description(&config, e, true))
-                .collect();
-            Some(endpoints)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Some(endpoints))
         } else {
             warn!(
                 "Endpoint url \"{}\" is unrecognized, using default",
                 endpoint_url
             );
             if let Some(e) = config.default_endpoint() {
-                Some(vec![self.new_endpoint_description(&config, e, true)])
+                Ok(Some(vec![self.new_endpoint_description(&config, e, true)?]))
             } else {
-                Some(vec![])
+                Ok(Some(vec![]))
             }
         }
     }
@@ -204,7 +171,7 @@ impl ServerState {
     pub fn new_endpoint_descriptions(
         &self,
         endpoint_url: &str,
-    ) -> Option<Vec<EndpointDescription>> {
+    ) -> Result<Option<Vec<EndpointDescription>>, StatusCode> {
         debug!("find_endpoint, url = {}", endpoint_url);
         let config = trace_read_lock!(self.config);
         let base_endpoint_url = config.base_endpoint_url();
@@ -216,11 +183,11 @@ impl ServerState {
                 url_matches_except_host(&e.endpoint_url(&base_endpoint_url), endpoint_url)
             })
             .map(|(_, e)| self.new_endpoint_description(&config, e, false))
-            .collect();
+            .collect::<Result<Vec<_>, _>>()?;
         if endpoints.is_empty() {
-            None
+            Ok(None)
         } else {
-            Some(endpoints)
+            Ok(Some(endpoints))
         }
     }
 
@@ -258,34 +225,22 @@ impl ServerState {
 
         // Anonymous policy
         if endpoint.supports_anonymous() {
-            user_identity_tokens.push(UserTokenPolicy {
-                policy_id: UAString::from(POLICY_ID_ANONYMOUS),
-                token_type: UserTokenType::Anonymous,
-                issued_token_type: UAString::null(),
-                issuer_endpoint_url: UAString::null(),
-                security_policy_uri: UAString::null(),
-            });
+            user_identity_tokens.push(UserTokenPolicy::anonymous());
         }
         // User pass policy
         if endpoint.supports_user_pass(&config.user_tokens) {
             // The endpoint may set a password security policy
-            user_identity_tokens.push(UserTokenPolicy {
-                policy_id: Self::user_pass_security_policy_id(endpoint),
-                token_type: UserTokenType::UserName,
-                issued_token_type: UAString::null(),
-                issuer_endpoint_url: UAString::null(),
-                security_policy_uri: Self::user_pass_security_policy_uri(endpoint),
-            });
+            user_identity_tokens.push(UserTokenPolicy::user_name(
+                Self::user_pass_security_policy_id(endpoint),
+                Self::user_pass_security_policy_uri(endpoint),
+            ));
         }
         // X509 policy
         if endpoint.supports_x509(&config.user_tokens) {
-            user_identity_tokens.push(UserTokenPolicy {
-                policy_id: UAString::from(POLICY_ID_X509),
-                token_type: UserTokenType::Certificate,
-                issued_token_type: UAString::null(),
-                issuer_endpoint_url: UAString::null(),
-                security_policy_uri: UAString::from(SecurityPolicy::Basic128Rsa15.to_uri()),
-            });
+            user_identity_tokens.push(UserTokenPolicy::x509(
+                POLICY_ID_X509,
+                SecurityPolicy::Basic128Rsa15.to_uri(),
+            ));
         }
 
         if user_identity_tokens.is_empty() {
@@ -304,7 +259,7 @@ impl ServerState {
         config: &ServerConfig,
         endpoint: &ServerEndpoint,
         all_fields: bool,
-    ) -> EndpointDescription {
+    ) -> Result<EndpointDescription, StatusCode> {
         let base_endpoint_url = config.base_endpoint_url();
 
         let user_identity_tokens = self.user_identity_tokens(config, endpoint);
@@ -323,7 +278,7 @@ impl ServerState {
                     discovery_profile_uri: UAString::null(),
                     discovery_urls: self.discovery_urls(),
                 },
-                self.server_certificate_as_byte_string(),
+                self.server_certificate_as_byte_string()?,
             )
         } else {
             (
@@ -340,7 +295,7 @@ impl ServerState {
             )
         };
 
-        EndpointDescription {
+        Ok(EndpointDescription {
             endpoint_url: endpoint.endpoint_url(&base_endpoint_url).into(),
             server,
             server_certificate,
@@ -349,7 +304,7 @@ impl ServerState {
             user_identity_tokens: Some(user_identity_tokens),
             transport_profile_uri: UAString::from(profiles::TRANSPORT_PROFILE_URI_BINARY),
             security_level: endpoint.security_level,
-        }
+        })
     }
 
     pub fn discovery_urls(&self) -> Option<Vec<UAString>> {
@@ -391,11 +346,11 @@ impl ServerState {
         self.state == ServerStateType::Running
     }
 
-    pub fn server_certificate_as_byte_string(&self) -> ByteString {
+    pub fn server_certificate_as_byte_string(&self) -> Result<ByteString, StatusCode> {
         if let Some(ref server_certificate) = self.server_certificate {
-            server_certificate.as_byte_string()
+            Ok(server_certificate.as_byte_string()?)
         } else {
-            ByteString::null()
+            Ok(ByteString::null())
         }
     }
 
@@ -451,7 +406,7 @@ impl ServerState {
                     Err(StatusCode::BadIdentityTokenInvalid)
                 }
                 IdentityToken::AnonymousIdentityToken(token) => {
-                    Self::authenticate_anonymous_token(endpoint, &token)
+                    self.authenticate_anonymous_token(&config, endpoint, &token)
                 }
                 IdentityToken::UserNameIdentityToken(token) => self
                     .authenticate_username_identity_token(
@@ -495,20 +450,35 @@ impl ServerState {
         config.decoding_options()
     }
 
-    /// Authenticates an anonymous token, i.e. does the endpoint support anonymous access or not
+    /// Authenticates an anonymous token, i.e. does the endpoint support anonymous access or not.
+    /// The token's policy id must match an anonymous user token policy that the endpoint
+    /// advertises.
     fn authenticate_anonymous_token(
+        &self,
+        config: &ServerConfig,
         endpoint: &ServerEndpoint,
         token: &AnonymousIdentityToken,
     ) -> Result<String, StatusCode> {
-        if token.policy_id.as_ref() != POLICY_ID_ANONYMOUS {
-            error!("Token doesn't possess the correct policy id");
-            Err(StatusCode::BadIdentityTokenInvalid)
-        } else if !endpoint.supports_anonymous() {
+        let anonymous_policies = self
+            .user_identity_tokens(config, endpoint)
+            .into_iter()
+            .filter(|policy| policy.token_type == UserTokenType::Anonymous)
+            .collect::<Vec<_>>();
+        if anonymous_policies.is_empty() {
             error!(
                 "Endpoint \"{}\" does not support anonymous authentication",
                 endpoint.path
             );
             Err(StatusCode::BadIdentityTokenRejected)
+        } else if !anonymous_policies
+            .iter()
+            .any(|policy| policy.policy_id == token.policy_id)
+        {
+            error!(
+                "Token policy id {} doesn't match an anonymous policy of endpoint \"{}\"",
+                token.policy_id, endpoint.path
+            );
+            Err(StatusCode::BadIdentityTokenRejected)
         } else {
             debug!("Anonymous identity is authenticated");
             Ok(String::from(crate::server::config::ANONYMOUS_USER_TOKEN_ID))
@@ -21,6 +21,14 @@ use crate::server::{
     state::ServerState,
 };
 
+/// Tests if an array value has changed according to the data change filter. The deadband is
+/// applied to each element in turn, so the array has changed if any one element has changed by
+/// more than the deadband, or if the shape of the array is different. A percentage deadband
+/// needs an engineering unit range which isn't known here, so it always counts as a change.
+pub fn array_changed(old: &Variant, new: &Variant, filter: &DataChangeFilter) -> bool {
+    !filter.compare_value(old, new, None).unwrap_or(false)
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Notification {
     MonitoredItemNotification(MonitoredItemNotification),
@@ -194,6 +202,7 @@ impl MonitoredItem {
         }
         // Validate the filter, return that from this function
         self.validate_filter(address_space)
+            .map_err(|(status_code, _)| status_code)
     }
 
     /// Adds or removes other monitored items which will be triggered when this monitored item changes
@@ -209,22 +218,32 @@ impl MonitoredItem {
 
     /// Validates the filter associated with the monitored item and returns the filter result
     /// encoded in an extension object.
+    ///
+    /// If the filter is invalid, the filter result is returned with the error so the client can
+    /// see which element of the filter was rejected.
     pub fn validate_filter(
         &self,
         address_space: &AddressSpace,
-    ) -> Result<ExtensionObject, StatusCode> {
+    ) -> Result<ExtensionObject, (StatusCode, ExtensionObject)> {
         // Event filter must be validated
-        let filter_result = if let FilterType::EventFilter(ref event_filter) = self.filter {
-            let filter_result = event_filter::validate(event_filter, address_space)?;
-            ExtensionObject::from_encodable(
+        if let FilterType::EventFilter(ref event_filter) = self.filter {
+            let filter_result = event_filter::validate(event_filter, address_space)
+                .map_err(|status_code| (status_code, ExtensionObject::null()))?;
+            let valid = event_filter::is_valid(&filter_result);
+            let filter_result = ExtensionObject::from_encodable(
                 ObjectId::EventFilterResult_Encoding_DefaultBinary,
                 &filter_result,
-            )
+            );
+            if valid {
+                Ok(filter_result)
+            } else {
+                error!("Event filter where clause is invalid");
+                Err((StatusCode::BadMonitoredItemFilterInvalid, filter_result))
+            }
         } else {
             // DataChangeFilter has no result
-            ExtensionObject::null()
-        };
-        Ok(filter_result)
+            Ok(ExtensionObject::null())
+        }
     }
 
     /// Called repeatedly on the monitored item.
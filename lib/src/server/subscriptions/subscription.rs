@@ -281,8 +281,11 @@ impl Subscription {
                                             filter_result,
                                         }
                                     }
-                                    Err(status_code) => {
-                                        Self::monitored_item_create_error(status_code)
+                                    Err((status_code, filter_result)) => {
+                                        MonitoredItemCreateResult {
+                                            filter_result,
+                                            ..Self::monitored_item_create_error(status_code)
+                                        }
                                     }
                                 }
                             } else {
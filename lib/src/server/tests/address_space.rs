@@ -656,6 +656,62 @@ fn variable_builder() {
     ));
 }
 
+#[test]
+fn read_attribute() {
+    let mut address_space = AddressSpace::new();
+    let node_id = NodeId::new(1, "Hello");
+    let _v = VariableBuilder::new(&node_id, "BrowseName", "DisplayName")
+        .data_type(DataTypeId::UInt32)
+        .value(Variant::from(999))
+        .organized_by(ObjectId::ObjectsFolder)
+        .insert(&mut address_space);
+
+    let read = |node_id: &NodeId, attribute_id: AttributeId| {
+        address_space.read_attribute(
+            node_id,
+            TimestampsToReturn::Neither,
+            attribute_id,
+            NumericRange::None,
+            &QualifiedName::null(),
+            0.0,
+        )
+    };
+
+    // Value of a variable
+    let value = read(&node_id, AttributeId::Value).unwrap();
+    assert_eq!(value.value.unwrap(), Variant::from(999));
+
+    // BrowseName of an object
+    let objects_folder: NodeId = ObjectId::ObjectsFolder.into();
+    let value = read(&objects_folder, AttributeId::BrowseName).unwrap();
+    assert_eq!(
+        value.value.unwrap(),
+        Variant::from(QualifiedName::new(0, "Objects"))
+    );
+
+    // Value of an object is not a valid combination
+    assert_eq!(
+        read(&objects_folder, AttributeId::Value).unwrap_err(),
+        StatusCode::BadAttributeIdInvalid
+    );
+    assert!(!address_space
+        .find_node(&objects_folder)
+        .unwrap()
+        .supports_attribute(AttributeId::Value));
+
+    // Executable of a variable is not a valid combination either
+    assert_eq!(
+        read(&node_id, AttributeId::Executable).unwrap_err(),
+        StatusCode::BadAttributeIdInvalid
+    );
+
+    // Unknown node
+    assert_eq!(
+        read(&NodeId::new(1, "Nothing"), AttributeId::Value).unwrap_err(),
+        StatusCode::BadNodeIdUnknown
+    );
+}
+
 #[test]
 fn method_builder() {
     let mut address_space = AddressSpace::new();
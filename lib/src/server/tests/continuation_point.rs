@@ -0,0 +1,65 @@
+use crate::server::continuation_point::ContinuationPoint;
+
+use super::*;
+
+#[test]
+fn issue_continuation_point() {
+    let continuation_point = ContinuationPoint::issue(7);
+    assert_eq!(continuation_point.index(), 7);
+
+    let bytes = continuation_point.to_byte_string();
+    assert_eq!(
+        bytes.value.as_ref().unwrap().len(),
+        4 + ContinuationPoint::TOKEN_LENGTH
+    );
+    assert_eq!(
+        ContinuationPoint::from_byte_string(&bytes).unwrap(),
+        continuation_point
+    );
+
+    // Tokens are random so two continuation points for the same index differ
+    assert_ne!(ContinuationPoint::issue(7), continuation_point);
+}
+
+#[test]
+fn resolve_continuation_point() {
+    let issued = vec![ContinuationPoint::issue(0), ContinuationPoint::issue(1)];
+    assert_eq!(
+        ContinuationPoint::resolve(&issued[1].to_byte_string(), &issued).unwrap(),
+        1
+    );
+    assert_eq!(
+        ContinuationPoint::resolve(&issued[0].to_byte_string(), &issued).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn reject_forged_continuation_point() {
+    let issued = vec![ContinuationPoint::issue(0)];
+
+    // A token the server never issued
+    let forged = ContinuationPoint::issue(0).to_byte_string();
+    assert_eq!(
+        ContinuationPoint::resolve(&forged, &issued).unwrap_err(),
+        StatusCode::BadContinuationPointInvalid
+    );
+
+    // An issued token with its index altered
+    let mut bytes = issued[0].to_byte_string().value.unwrap();
+    bytes[0] = 1;
+    assert_eq!(
+        ContinuationPoint::resolve(&ByteString::from(bytes), &issued).unwrap_err(),
+        StatusCode::BadContinuationPointInvalid
+    );
+
+    // Wrong length and null
+    assert_eq!(
+        ContinuationPoint::resolve(&ByteString::from(vec![1u8, 2, 3]), &issued).unwrap_err(),
+        StatusCode::BadContinuationPointInvalid
+    );
+    assert_eq!(
+        ContinuationPoint::resolve(&ByteString::null(), &issued).unwrap_err(),
+        StatusCode::BadContinuationPointInvalid
+    );
+}
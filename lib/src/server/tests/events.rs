@@ -3,13 +3,15 @@ use std::collections::HashSet;
 use crate::types::{
     node_ids::ReferenceTypeId,
     operand::{ContentFilterBuilder, Operand},
-    service_types::ContentFilterElement,
+    service_types::{ContentFilterElement, EventFilter},
+    status_code::StatusCode,
     AttributeId, DataTypeId, LocalizedText, NodeId, ObjectId, ObjectTypeId, QualifiedName,
     UAString, VariableTypeId, Variant,
 };
 
 use crate::server::{
     address_space::{object_type::ObjectTypeBuilder, variable::VariableBuilder, AddressSpace},
+    constants,
     events::event::{BaseEventType, Event},
     events::event_filter,
     events::operator,
@@ -863,3 +865,43 @@ fn test_where_clause() {
             assert_eq!(result.unwrap(), expected.into());
         });
 }
+
+#[test]
+fn test_where_clause_cyclic() {
+    let address_space = address_space();
+    let object_id = NodeId::root_folder_id();
+
+    // Element 0 refers to element 1 which refers back to element 0
+    let f = ContentFilterBuilder::new()
+        .not(Operand::element(1))
+        .not(Operand::element(0))
+        .build();
+    let result = event_filter::evaluate_where_clause(&object_id, &f, &address_space);
+    assert_eq!(result.unwrap_err(), StatusCode::BadFilterOperandInvalid);
+
+    // Validation rejects it too
+    let event_filter = EventFilter {
+        select_clauses: None,
+        where_clause: f,
+    };
+    let result = event_filter::validate(&event_filter, &address_space).unwrap();
+    assert!(!event_filter::is_valid(&result));
+
+    // Elements nested up to the maximum depth are evaluated
+    let nested_filter = |depth: usize| {
+        (1..depth)
+            .fold(ContentFilterBuilder::new(), |f, i| {
+                f.not(Operand::element(i as u32))
+            })
+            .null(Operand::literal(()))
+            .build()
+    };
+    let f = nested_filter(constants::MAX_CONTENT_FILTER_DEPTH);
+    let result = event_filter::evaluate_where_clause(&object_id, &f, &address_space);
+    assert!(result.is_ok());
+
+    // Any deeper is rejected
+    let f = nested_filter(constants::MAX_CONTENT_FILTER_DEPTH + 1);
+    let result = event_filter::evaluate_where_clause(&object_id, &f, &address_space);
+    assert_eq!(result.unwrap_err(), StatusCode::BadFilterOperandInvalid);
+}
@@ -16,7 +16,9 @@ use crate::server::{
 };
 
 mod address_space;
+mod continuation_point;
 mod events;
+mod secure_channel;
 mod services;
 mod subscriptions;
 
@@ -100,6 +102,79 @@ pub fn server_config_invalid() {
     assert_eq!(config.is_valid(), false);
 }
 
+#[test]
+pub fn server_endpoint_security_level() {
+    use crate::crypto::SecurityPolicy;
+    use crate::server::config::ServerEndpoint;
+
+    // From least to most secure
+    let ordered = [
+        (SecurityPolicy::None, MessageSecurityMode::None),
+        (SecurityPolicy::Basic128Rsa15, MessageSecurityMode::Sign),
+        (SecurityPolicy::Basic256, MessageSecurityMode::Sign),
+        (
+            SecurityPolicy::Aes128Sha256RsaOaep,
+            MessageSecurityMode::Sign,
+        ),
+        (SecurityPolicy::Basic256Sha256, MessageSecurityMode::Sign),
+        (
+            SecurityPolicy::Aes256Sha256RsaPss,
+            MessageSecurityMode::Sign,
+        ),
+        (
+            SecurityPolicy::Basic128Rsa15,
+            MessageSecurityMode::SignAndEncrypt,
+        ),
+        (
+            SecurityPolicy::Basic256,
+            MessageSecurityMode::SignAndEncrypt,
+        ),
+        (
+            SecurityPolicy::Aes128Sha256RsaOaep,
+            MessageSecurityMode::SignAndEncrypt,
+        ),
+        (
+            SecurityPolicy::Basic256Sha256,
+            MessageSecurityMode::SignAndEncrypt,
+        ),
+        (
+            SecurityPolicy::Aes256Sha256RsaPss,
+            MessageSecurityMode::SignAndEncrypt,
+        ),
+    ];
+    let levels: Vec<u8> = ordered
+        .iter()
+        .map(|(policy, mode)| ServerEndpoint::security_level(*policy, *mode))
+        .collect();
+    assert_eq!(levels[0], 0);
+    levels
+        .windows(2)
+        .for_each(|w| assert!(w[0] < w[1], "{:?}", levels));
+
+    // Mismatched policy and mode offer no security
+    assert_eq!(
+        ServerEndpoint::security_level(SecurityPolicy::None, MessageSecurityMode::SignAndEncrypt),
+        0
+    );
+    assert_eq!(
+        ServerEndpoint::security_level(
+            SecurityPolicy::Aes256Sha256RsaPss,
+            MessageSecurityMode::None
+        ),
+        0
+    );
+
+    // Endpoints are made with the computed level
+    let endpoint = ServerEndpoint::new_basic256sha256_sign_encrypt("/", &[]);
+    assert_eq!(
+        endpoint.security_level,
+        ServerEndpoint::security_level(
+            SecurityPolicy::Basic256Sha256,
+            MessageSecurityMode::SignAndEncrypt
+        )
+    );
+}
+
 #[test]
 pub fn expired_publish_requests() {
     let now = chrono::Utc::now();
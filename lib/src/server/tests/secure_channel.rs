@@ -0,0 +1,177 @@
+use crate::core::comms::{
+    secure_channel::SecureChannel,
+    security_header::{AsymmetricSecurityHeader, SecurityHeader},
+    tcp_types::PROTOCOL_VERSION,
+};
+use crate::server::comms::secure_channel_service::SecureChannelService;
+
+use super::*;
+
+fn open_secure_channel_request(
+    request_type: SecurityTokenRequestType,
+    security_mode: MessageSecurityMode,
+) -> SupportedMessage {
+    OpenSecureChannelRequest {
+        request_header: RequestHeader::dummy(),
+        client_protocol_version: 0,
+        request_type,
+        security_mode,
+        client_nonce: ByteString::null(),
+        requested_lifetime: 60000,
+    }
+    .into()
+}
+
+fn open_secure_channel(
+    service: &mut SecureChannelService,
+    secure_channel: &mut SecureChannel,
+    request_channel_id: u32,
+    request_type: SecurityTokenRequestType,
+    security_mode: MessageSecurityMode,
+) -> SupportedMessage {
+    let security_header = SecurityHeader::Asymmetric(AsymmetricSecurityHeader::none());
+    let request = open_secure_channel_request(request_type, security_mode);
+    service
+        .open_secure_channel(
+            secure_channel,
+            &security_header,
+            request_channel_id,
+            0,
+            &request,
+        )
+        .unwrap()
+}
+
+/// Issues a secure channel and returns its id
+fn issue_secure_channel(
+    service: &mut SecureChannelService,
+    secure_channel: &mut SecureChannel,
+) -> u32 {
+    let response = open_secure_channel(
+        service,
+        secure_channel,
+        0,
+        SecurityTokenRequestType::Issue,
+        MessageSecurityMode::None,
+    );
+    if let SupportedMessage::OpenSecureChannelResponse(response) = response {
+        assert!(response.response_header.service_result.is_good());
+        response.security_token.channel_id
+    } else {
+        panic!(
+            "Expected an open secure channel response, got {:?}",
+            response
+        );
+    }
+}
+
+fn assert_service_fault(response: SupportedMessage, status_code: StatusCode) {
+    if let SupportedMessage::ServiceFault(fault) = response {
+        assert_eq!(fault.response_header.service_result, status_code);
+    } else {
+        panic!("Expected a service fault, got {:?}", response);
+    }
+}
+
+#[test]
+fn renew_secure_channel() {
+    let mut service = SecureChannelService::new();
+    let mut secure_channel = SecureChannel::new_no_certificate_store();
+    let channel_id = issue_secure_channel(&mut service, &mut secure_channel);
+    let token_id = secure_channel.token_id();
+
+    let response = open_secure_channel(
+        &mut service,
+        &mut secure_channel,
+        channel_id,
+        SecurityTokenRequestType::Renew,
+        MessageSecurityMode::None,
+    );
+    if let SupportedMessage::OpenSecureChannelResponse(response) = response {
+        assert!(response.response_header.service_result.is_good());
+        // Same channel, new token
+        assert_eq!(response.security_token.channel_id, channel_id);
+        assert_ne!(response.security_token.token_id, token_id);
+    } else {
+        panic!(
+            "Expected an open secure channel response, got {:?}",
+            response
+        );
+    }
+}
+
+#[test]
+fn renew_secure_channel_changed_mode() {
+    let mut service = SecureChannelService::new();
+    let mut secure_channel = SecureChannel::new_no_certificate_store();
+    let channel_id = issue_secure_channel(&mut service, &mut secure_channel);
+
+    let response = open_secure_channel(
+        &mut service,
+        &mut secure_channel,
+        channel_id,
+        SecurityTokenRequestType::Renew,
+        MessageSecurityMode::Sign,
+    );
+    assert_service_fault(response, StatusCode::BadSecurityModeRejected);
+    assert_eq!(secure_channel.security_mode(), MessageSecurityMode::None);
+}
+
+#[test]
+fn renew_secure_channel_unknown_channel() {
+    let mut service = SecureChannelService::new();
+    let mut secure_channel = SecureChannel::new_no_certificate_store();
+    let channel_id = issue_secure_channel(&mut service, &mut secure_channel);
+
+    let response = open_secure_channel(
+        &mut service,
+        &mut secure_channel,
+        channel_id + 1,
+        SecurityTokenRequestType::Renew,
+        MessageSecurityMode::None,
+    );
+    assert_service_fault(response, StatusCode::BadSecurityChecksFailed);
+    assert_eq!(secure_channel.secure_channel_id(), channel_id);
+}
+
+#[test]
+fn issue_secure_channel_twice() {
+    let mut service = SecureChannelService::new();
+    let mut secure_channel = SecureChannel::new_no_certificate_store();
+    let channel_id = issue_secure_channel(&mut service, &mut secure_channel);
+
+    let response = open_secure_channel(
+        &mut service,
+        &mut secure_channel,
+        channel_id,
+        SecurityTokenRequestType::Issue,
+        MessageSecurityMode::None,
+    );
+    assert_service_fault(response, StatusCode::BadSecurityChecksFailed);
+    assert_eq!(secure_channel.secure_channel_id(), channel_id);
+}
+
+#[test]
+fn issue_secure_channel_protocol_version_mismatch() {
+    let mut service = SecureChannelService::new();
+    let mut secure_channel = SecureChannel::new_no_certificate_store();
+
+    // The HELLO agreed on one protocol version and the request asks for another
+    let security_header = SecurityHeader::Asymmetric(AsymmetricSecurityHeader::none());
+    let request =
+        open_secure_channel_request(SecurityTokenRequestType::Issue, MessageSecurityMode::None);
+    let response = service
+        .open_secure_channel(
+            &mut secure_channel,
+            &security_header,
+            0,
+            PROTOCOL_VERSION + 1,
+            &request,
+        )
+        .unwrap();
+    assert_service_fault(response, StatusCode::BadProtocolVersionUnsupported);
+
+    // No channel was issued, so a matching request still succeeds
+    let channel_id = issue_secure_channel(&mut service, &mut secure_channel);
+    assert_eq!(secure_channel.secure_channel_id(), channel_id);
+}
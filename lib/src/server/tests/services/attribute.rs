@@ -194,6 +194,150 @@ fn read_invalid_timestamps() {
     });
 }
 
+#[test]
+fn read_too_many_operations() {
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        {
+            let mut server_state = trace_write_lock!(server_state);
+            server_state.operational_limits.max_nodes_per_read = 3;
+        }
+        let read = |count: usize| {
+            let nodes_to_read = (0..count)
+                .map(|i| read_value(&NodeId::new(1, var_name(i)), AttributeId::Value))
+                .collect();
+            let request = ReadRequest {
+                request_header: make_request_header(),
+                max_age: 0f64,
+                timestamps_to_return: TimestampsToReturn::Both,
+                nodes_to_read: Some(nodes_to_read),
+            };
+            ats.read(
+                server_state.clone(),
+                session.clone(),
+                address_space.clone(),
+                &request,
+            )
+        };
+
+        // Up to the limit is fine
+        let response: ReadResponse = supported_message_as!(read(3), ReadResponse);
+        assert_eq!(response.results.unwrap().len(), 3);
+
+        // Exceeding MaxNodesPerRead is rejected
+        let response = supported_message_as!(read(4), ServiceFault);
+        assert_eq!(
+            response.response_header.service_result,
+            StatusCode::BadTooManyOperations
+        );
+    });
+}
+
+#[test]
+fn read_server_status() {
+    // The ServerStatus value is synthesized on each read, so its current time should be now
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        let read_server_status = |server_state: Arc<RwLock<ServerState>>| {
+            let server_status_id: NodeId = VariableId::Server_ServerStatus.into();
+            let state_id: NodeId = VariableId::Server_ServerStatus_State.into();
+            let nodes_to_read = vec![
+                read_value(&server_status_id, AttributeId::Value),
+                read_value(&state_id, AttributeId::Value),
+            ];
+            let request = ReadRequest {
+                request_header: make_request_header(),
+                max_age: 0f64,
+                timestamps_to_return: TimestampsToReturn::Both,
+                nodes_to_read: Some(nodes_to_read),
+            };
+
+            let response = ats.read(
+                server_state,
+                session.clone(),
+                address_space.clone(),
+                &request,
+            );
+            let response: ReadResponse = supported_message_as!(response, ReadResponse);
+            let results = response.results.unwrap();
+            assert_eq!(results[0].status.as_ref().unwrap(), &StatusCode::Good);
+
+            let server_status = if let Some(Variant::ExtensionObject(ref v)) = results[0].value {
+                v.decode_inner::<ServerStatusDataType>(&DecodingOptions::test())
+                    .unwrap()
+            } else {
+                panic!("ServerStatus should be an extension object");
+            };
+            // The state variable agrees with the state in the server status
+            assert_eq!(
+                results[1].value,
+                Some(Variant::Int32(server_status.state as i32))
+            );
+            server_status
+        };
+
+        server_state
+            .write()
+            .set_state(crate::types::ServerState::Running);
+        let server_status = read_server_status(server_state.clone());
+        assert_eq!(server_status.state, crate::types::ServerState::Running);
+        assert!(server_status.start_time <= server_status.current_time);
+
+        let age = DateTime::now().as_chrono() - server_status.current_time.as_chrono();
+        assert!(age >= Duration::zero() && age < Duration::seconds(5));
+
+        // The state follows the server, e.g. when it is suspended or shutting down
+        for state in &[
+            crate::types::ServerState::Suspended,
+            crate::types::ServerState::Shutdown,
+        ] {
+            server_state.write().set_state(*state);
+            assert_eq!(read_server_status(server_state.clone()).state, *state);
+        }
+    });
+}
+
+#[test]
+fn read_service_level() {
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        let read_service_level = |address_space: Arc<RwLock<AddressSpace>>| {
+            let service_level_id: NodeId = VariableId::Server_ServiceLevel.into();
+            let request = ReadRequest {
+                request_header: make_request_header(),
+                max_age: 0f64,
+                timestamps_to_return: TimestampsToReturn::Neither,
+                nodes_to_read: Some(vec![read_value(&service_level_id, AttributeId::Value)]),
+            };
+            let response = ats.read(
+                server_state.clone(),
+                session.clone(),
+                address_space,
+                &request,
+            );
+            let response: ReadResponse = supported_message_as!(response, ReadResponse);
+            response.results.unwrap().remove(0).value.unwrap()
+        };
+
+        // Best quality of service by default
+        assert_eq!(
+            read_service_level(address_space.clone()),
+            Variant::Byte(255)
+        );
+
+        // A provider is called on every read
+        let service_level = Arc::new(Mutex::new(200u8));
+        {
+            let service_level = service_level.clone();
+            let mut address_space = trace_write_lock!(address_space);
+            address_space.set_service_level_provider(move || *service_level.lock());
+        }
+        assert_eq!(
+            read_service_level(address_space.clone()),
+            Variant::Byte(200)
+        );
+        *service_level.lock() = 10;
+        assert_eq!(read_service_level(address_space.clone()), Variant::Byte(10));
+    });
+}
+
 fn write_value(node_id: &NodeId, attribute_id: AttributeId, value: DataValue) -> WriteValue {
     WriteValue {
         node_id: node_id.clone(),
@@ -452,6 +596,339 @@ fn write_bytestring_to_byte_array() {
     });
 }
 
+#[test]
+fn write_extension_object() {
+    // This test checks that structures are validated against the variable's data type
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        let build_info = BuildInfo {
+            product_uri: "urn:product".into(),
+            manufacturer_name: "manufacturer".into(),
+            product_name: "product".into(),
+            software_version: "1.0".into(),
+            build_number: "123".into(),
+            build_date: DateTime::ymd(2021, 1, 1),
+        };
+
+        let node_id = NodeId::next_numeric(2);
+        {
+            let mut address_space = trace_write_lock!(address_space);
+            let _ = VariableBuilder::new(&node_id, var_name(0), "")
+                .data_type(DataTypeId::BuildInfo)
+                .value(ExtensionObject::from_encodable(
+                    ObjectId::BuildInfo_Encoding_DefaultBinary,
+                    &BuildInfo {
+                        software_version: "0.9".into(),
+                        ..build_info.clone()
+                    },
+                ))
+                .organized_by(ObjectId::RootFolder)
+                .writable()
+                .insert(&mut address_space);
+        }
+
+        let expected = ExtensionObject::from_encodable(
+            ObjectId::BuildInfo_Encoding_DefaultBinary,
+            &build_info,
+        );
+
+        // A structure with junk after the encoded body does not decode as the structure
+        let mut body = build_info.encode_to_vec();
+        body.extend_from_slice(&[0xffu8; 4]);
+        let padded = ExtensionObject {
+            node_id: ObjectId::BuildInfo_Encoding_DefaultBinary.into(),
+            body: ExtensionObjectEncoding::ByteString(ByteString::from(body)),
+        };
+
+        let nodes_to_write = vec![
+            // Matching type
+            write_value(
+                &node_id,
+                AttributeId::Value,
+                DataValue::new_now(expected.clone()),
+            ),
+            // Mismatched type
+            write_value(
+                &node_id,
+                AttributeId::Value,
+                DataValue::new_now(ExtensionObject::from_encodable(
+                    ObjectId::Range_Encoding_DefaultBinary,
+                    &Range {
+                        low: 0.0,
+                        high: 1.0,
+                    },
+                )),
+            ),
+            // Body that is not a BuildInfo
+            write_value(
+                &node_id,
+                AttributeId::Value,
+                DataValue::new_now(ExtensionObject {
+                    node_id: ObjectId::BuildInfo_Encoding_DefaultBinary.into(),
+                    body: ExtensionObjectEncoding::ByteString(ByteString::from(vec![0x1u8])),
+                }),
+            ),
+            // Unknown encoding id
+            write_value(
+                &node_id,
+                AttributeId::Value,
+                DataValue::new_now(ExtensionObject::from_encodable(
+                    NodeId::new(2, "NotAnEncoding"),
+                    &build_info,
+                )),
+            ),
+            // Body with junk after it
+            write_value(&node_id, AttributeId::Value, DataValue::new_now(padded)),
+        ];
+
+        let response = write_request(
+            server_state,
+            session,
+            address_space.clone(),
+            ats,
+            nodes_to_write,
+        );
+        let results = response.results.unwrap();
+        assert_eq!(results[0], StatusCode::Good);
+        assert_eq!(results[1], StatusCode::BadTypeMismatch);
+        assert_eq!(results[2], StatusCode::BadTypeMismatch);
+        assert_eq!(results[3], StatusCode::BadTypeMismatch);
+        assert_eq!(results[4], StatusCode::BadTypeMismatch);
+
+        // The stored value is the structure that was written
+        validate_variable_value(address_space, &node_id, |value| {
+            assert_eq!(*value, Variant::from(expected));
+        });
+    });
+}
+
+#[test]
+fn write_coerced_value() {
+    // This test checks that numeric values are coerced to the variable's type only when lossless
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        let double_node_id = NodeId::next_numeric(2);
+        let int32_node_id = NodeId::next_numeric(2);
+        {
+            let mut address_space = trace_write_lock!(address_space);
+            let _ = VariableBuilder::new(&double_node_id, var_name(0), "")
+                .data_type(DataTypeId::Double)
+                .value(0f64)
+                .organized_by(ObjectId::RootFolder)
+                .writable()
+                .insert(&mut address_space);
+            let _ = VariableBuilder::new(&int32_node_id, var_name(1), "")
+                .data_type(DataTypeId::Int32)
+                .value(0i32)
+                .organized_by(ObjectId::RootFolder)
+                .writable()
+                .insert(&mut address_space);
+        }
+
+        let nodes_to_write = vec![
+            // Widening Int32 to Double is allowed
+            write_value(
+                &double_node_id,
+                AttributeId::Value,
+                DataValue::new_now(5i32),
+            ),
+            // Narrowing a fractional Double to Int32 loses the fraction
+            write_value(
+                &int32_node_id,
+                AttributeId::Value,
+                DataValue::new_now(2.5f64),
+            ),
+            // Narrowing an Int64 to Int32 overflows
+            write_value(
+                &int32_node_id,
+                AttributeId::Value,
+                DataValue::new_now(i64::MAX),
+            ),
+            // Strings are not coerced to numbers
+            write_value(
+                &int32_node_id,
+                AttributeId::Value,
+                DataValue::new_now(UAString::from("7")),
+            ),
+        ];
+
+        let response = write_request(
+            server_state,
+            session,
+            address_space.clone(),
+            ats,
+            nodes_to_write,
+        );
+        let results = response.results.unwrap();
+        assert_eq!(results[0], StatusCode::Good);
+        assert_eq!(results[1], StatusCode::BadTypeMismatch);
+        assert_eq!(results[2], StatusCode::BadTypeMismatch);
+        assert_eq!(results[3], StatusCode::BadTypeMismatch);
+
+        // The widened value is stored as the variable's type, the narrowed ones are not stored
+        validate_variable_value(address_space.clone(), &double_node_id, |value| {
+            assert_eq!(*value, Variant::Double(5.0));
+        });
+        validate_variable_value(address_space, &int32_node_id, |value| {
+            assert_eq!(*value, Variant::Int32(0));
+        });
+    });
+}
+
+#[test]
+fn write_coerced_value_boundaries() {
+    // This test checks that integers too big to be represented exactly as a Double are not
+    // coerced, even though the cast saturates back to the original value
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        let double_node_id = NodeId::next_numeric(2);
+        let uint64_node_id = NodeId::next_numeric(2);
+        {
+            let mut address_space = trace_write_lock!(address_space);
+            let _ = VariableBuilder::new(&double_node_id, var_name(0), "")
+                .data_type(DataTypeId::Double)
+                .value(0f64)
+                .organized_by(ObjectId::RootFolder)
+                .writable()
+                .insert(&mut address_space);
+            let _ = VariableBuilder::new(&uint64_node_id, var_name(1), "")
+                .data_type(DataTypeId::UInt64)
+                .value(0u64)
+                .organized_by(ObjectId::RootFolder)
+                .writable()
+                .insert(&mut address_space);
+        }
+
+        let two_pow_53 = 1i64 << 53;
+        let nodes_to_write = vec![
+            // 2^53 is the largest integer a Double holds exactly along with all below it
+            write_value(
+                &double_node_id,
+                AttributeId::Value,
+                DataValue::new_now(two_pow_53),
+            ),
+            // 2^53 + 1 rounds
+            write_value(
+                &double_node_id,
+                AttributeId::Value,
+                DataValue::new_now(two_pow_53 + 1),
+            ),
+            // As do the largest 64-bit integers
+            write_value(
+                &double_node_id,
+                AttributeId::Value,
+                DataValue::new_now(i64::MAX),
+            ),
+            write_value(
+                &double_node_id,
+                AttributeId::Value,
+                DataValue::new_now(u64::MAX),
+            ),
+            // 2^64 saturates to u64::MAX
+            write_value(
+                &uint64_node_id,
+                AttributeId::Value,
+                DataValue::new_now(18446744073709551616f64),
+            ),
+            // 2^63 fits exactly
+            write_value(
+                &uint64_node_id,
+                AttributeId::Value,
+                DataValue::new_now(9223372036854775808f64),
+            ),
+        ];
+
+        let response = write_request(
+            server_state,
+            session,
+            address_space.clone(),
+            ats,
+            nodes_to_write,
+        );
+        let results = response.results.unwrap();
+        assert_eq!(
+            results,
+            vec![
+                StatusCode::Good,
+                StatusCode::BadTypeMismatch,
+                StatusCode::BadTypeMismatch,
+                StatusCode::BadTypeMismatch,
+                StatusCode::BadTypeMismatch,
+                StatusCode::Good,
+            ]
+        );
+
+        validate_variable_value(address_space.clone(), &double_node_id, |value| {
+            assert_eq!(*value, Variant::Double(9007199254740992f64));
+        });
+        validate_variable_value(address_space, &uint64_node_id, |value| {
+            assert_eq!(*value, Variant::UInt64(1 << 63));
+        });
+    });
+}
+
+#[test]
+fn write_access_level() {
+    // This test checks the write is checked against the AccessLevel and UserAccessLevel of the variable
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        let node_ids = {
+            let (_, node_ids) = add_many_vars_to_address_space(address_space.clone(), 3);
+            let mut address_space = trace_write_lock!(address_space);
+            // 0 is read-only, 1 is writable by access level but not by user, 2 is writable
+            for (i, node_id) in node_ids.iter().enumerate() {
+                let (access_level, user_access_level) = match i {
+                    0 => (AccessLevel::CURRENT_READ, UserAccessLevel::CURRENT_READ),
+                    1 => (
+                        AccessLevel::CURRENT_READ | AccessLevel::CURRENT_WRITE,
+                        UserAccessLevel::CURRENT_READ,
+                    ),
+                    _ => (
+                        AccessLevel::CURRENT_READ | AccessLevel::CURRENT_WRITE,
+                        UserAccessLevel::CURRENT_READ | UserAccessLevel::CURRENT_WRITE,
+                    ),
+                };
+                if let NodeType::Variable(v) = address_space.find_node_mut(node_id).unwrap() {
+                    v.set_access_level(access_level);
+                    v.set_user_access_level(user_access_level);
+                } else {
+                    panic!();
+                }
+            }
+            node_ids
+        };
+
+        let nodes_to_write = node_ids
+            .iter()
+            .map(|node_id| write_value(node_id, AttributeId::Value, DataValue::new_now(99i32)))
+            .collect();
+
+        let response = write_request(
+            server_state,
+            session,
+            address_space.clone(),
+            ats,
+            nodes_to_write,
+        );
+        let results = response.results.unwrap();
+        assert_eq!(results.len(), 3);
+
+        // Read-only node is rejected and its value is untouched
+        assert_eq!(results[0], StatusCode::BadNotWritable);
+        validate_variable_value(address_space.clone(), &node_ids[0], |v| {
+            assert_eq!(*v, Variant::Int32(0));
+        });
+
+        // Writable node but not for this user
+        assert_eq!(results[1], StatusCode::BadUserAccessDenied);
+        validate_variable_value(address_space.clone(), &node_ids[1], |v| {
+            assert_eq!(*v, Variant::Int32(1));
+        });
+
+        // Writable node is accepted
+        assert_eq!(results[2], StatusCode::Good);
+        validate_variable_value(address_space.clone(), &node_ids[2], |v| {
+            assert_eq!(*v, Variant::Int32(99));
+        });
+    });
+}
+
 #[test]
 fn write_index_range() {
     // Test that writing to an index in an array works
@@ -553,6 +1030,90 @@ fn write_index_range() {
     });
 }
 
+#[test]
+fn write_index_range_mismatch() {
+    // Test that a partial write is rejected when the written array does not exactly fill the range
+    do_attribute_service_test(|server_state, session, address_space, ats| {
+        let node_id = NodeId::next_numeric(2);
+        {
+            let mut address_space = trace_write_lock!(address_space);
+            let _ = VariableBuilder::new(&node_id, var_name(0), "")
+                .data_type(DataTypeId::Int32)
+                .value_rank(1)
+                .value(vec![0i32; 8])
+                .organized_by(ObjectId::RootFolder)
+                .writable()
+                .insert(&mut address_space);
+        }
+
+        let nodes_to_write = vec![
+            // Written array is shorter than the range span
+            write_value_index_range(
+                &node_id,
+                AttributeId::Value,
+                "2:5",
+                DataValue::new_now(vec![1i32, 2i32]),
+            ),
+            // Written array is longer than the range span
+            write_value_index_range(
+                &node_id,
+                AttributeId::Value,
+                "2:3",
+                DataValue::new_now(vec![1i32, 2i32, 3i32]),
+            ),
+            // Range goes beyond the end of the array
+            write_value_index_range(
+                &node_id,
+                AttributeId::Value,
+                "6:9",
+                DataValue::new_now(vec![1i32, 2i32, 3i32, 4i32]),
+            ),
+            // Index is beyond the end of the array
+            write_value_index_range(
+                &node_id,
+                AttributeId::Value,
+                "8",
+                DataValue::new_now(vec![1i32]),
+            ),
+            // Range that fits exactly
+            write_value_index_range(
+                &node_id,
+                AttributeId::Value,
+                "6:7",
+                DataValue::new_now(vec![6i32, 7i32]),
+            ),
+        ];
+
+        let response = write_request(
+            server_state,
+            session,
+            address_space.clone(),
+            ats,
+            nodes_to_write,
+        );
+        let results = response.results.unwrap();
+        assert_eq!(results[0], StatusCode::BadIndexRangeNoData);
+        assert_eq!(results[1], StatusCode::BadIndexRangeNoData);
+        assert_eq!(results[2], StatusCode::BadIndexRangeNoData);
+        assert_eq!(results[3], StatusCode::BadIndexRangeNoData);
+        assert_eq!(results[4], StatusCode::Good);
+
+        // Only the last write should have modified the array
+        validate_variable_value(address_space, &node_id, |value| match value {
+            Variant::Array(array) => {
+                let expected = [0i32, 0, 0, 0, 0, 0, 6, 7];
+                assert_eq!(array.values.len(), expected.len());
+                array
+                    .values
+                    .iter()
+                    .zip(expected.iter())
+                    .for_each(|(v, e)| assert_eq!(*v, Variant::Int32(*e)));
+            }
+            _ => panic!(),
+        });
+    });
+}
+
 // #[test] fn write_null_value() { /* Write an empty variant to a value and see that it is allowed */}
 
 struct DataProvider;
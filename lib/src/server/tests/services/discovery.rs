@@ -38,6 +38,61 @@ fn get_endpoints() {
     });
 }
 
+#[test]
+fn get_endpoints_security_level() {
+    do_discovery_service_test(|server_state, _session, ds| {
+        // The configured level of an endpoint overrides the computed one
+        {
+            let server_state = trace_read_lock!(server_state);
+            let mut config = trace_write_lock!(server_state.config);
+            config
+                .endpoints
+                .get_mut("basic256sha256_sign_encrypt")
+                .unwrap()
+                .security_level = 200;
+        }
+
+        let request = GetEndpointsRequest {
+            request_header: make_request_header(),
+            endpoint_url: UAString::from("opc.tcp://localhost:4855/"),
+            locale_ids: None,
+            profile_uris: None,
+        };
+        let result = ds.get_endpoints(server_state, &request);
+        let result = supported_message_as!(result, GetEndpointsResponse);
+        let endpoints = result.endpoints.unwrap();
+
+        let security_level = |security_policy: SecurityPolicy, security_mode| {
+            endpoints
+                .iter()
+                .find(|e| {
+                    e.security_policy_uri.as_ref() == security_policy.to_uri()
+                        && e.security_mode == security_mode
+                })
+                .unwrap()
+                .security_level
+        };
+        assert_eq!(
+            security_level(
+                SecurityPolicy::Basic256Sha256,
+                MessageSecurityMode::SignAndEncrypt
+            ),
+            200
+        );
+        assert_eq!(
+            security_level(SecurityPolicy::Basic256Sha256, MessageSecurityMode::Sign),
+            ServerEndpoint::security_level(
+                SecurityPolicy::Basic256Sha256,
+                MessageSecurityMode::Sign
+            )
+        );
+        assert_eq!(
+            security_level(SecurityPolicy::None, MessageSecurityMode::None),
+            0
+        );
+    });
+}
+
 #[test]
 fn find_servers() {
     do_discovery_service_test(|server_state, _session, ds| {
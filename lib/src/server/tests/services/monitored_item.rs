@@ -123,6 +123,7 @@ fn make_create_request_event_filter(
 }
 
 fn set_monitoring_mode(
+    server_state: Arc<RwLock<ServerState>>,
     session: Arc<RwLock<Session>>,
     subscription_id: u32,
     monitored_item_id: u32,
@@ -136,7 +137,7 @@ fn set_monitoring_mode(
         monitored_item_ids: Some(vec![monitored_item_id]),
     };
     let response: SetMonitoringModeResponse = supported_message_as!(
-        mis.set_monitoring_mode(session, &request),
+        mis.set_monitoring_mode(server_state, session, &request),
         SetMonitoringModeResponse
     );
     let results = response.results.unwrap();
@@ -145,6 +146,7 @@ fn set_monitoring_mode(
 }
 
 fn set_triggering(
+    server_state: Arc<RwLock<ServerState>>,
     session: Arc<RwLock<Session>>,
     subscription_id: u32,
     monitored_item_id: u32,
@@ -167,8 +169,10 @@ fn set_triggering(
             Some(links_to_remove.to_vec())
         },
     };
-    let response: SetTriggeringResponse =
-        supported_message_as!(mis.set_triggering(session, &request), SetTriggeringResponse);
+    let response: SetTriggeringResponse = supported_message_as!(
+        mis.set_triggering(server_state, session, &request),
+        SetTriggeringResponse
+    );
     (response.add_results, response.remove_results)
 }
 
@@ -444,6 +448,55 @@ fn deadband_pct() {
     );
 }
 
+#[test]
+fn deadband_array_changed() {
+    let filter = DataChangeFilter {
+        trigger: DataChangeTrigger::StatusValue,
+        deadband_type: DeadbandType::Absolute as u32,
+        deadband_value: 1f64,
+    };
+    let old = Variant::from(vec![10f64, 20f64, 30f64]);
+
+    // No element moves by more than the deadband
+    let new = Variant::from(vec![10.5f64, 19f64, 30.9f64]);
+    assert!(!array_changed(&old, &new, &filter));
+
+    // One element crosses the deadband
+    let new = Variant::from(vec![10.5f64, 21.5f64, 30f64]);
+    assert!(array_changed(&old, &new, &filter));
+
+    // A different number of elements is always a change
+    let new = Variant::from(vec![10f64, 20f64]);
+    assert!(array_changed(&old, &new, &filter));
+
+    // Without a deadband any difference is a change
+    let filter = DataChangeFilter {
+        trigger: DataChangeTrigger::StatusValue,
+        deadband_type: DeadbandType::None as u32,
+        deadband_value: 0f64,
+    };
+    let new = Variant::from(vec![10.5f64, 20f64, 30f64]);
+    assert!(array_changed(&old, &new, &filter));
+    assert!(!array_changed(&old, &old.clone(), &filter));
+
+    // The same array with explicit dimensions is not a change, with or without a deadband
+    let new = Variant::from(
+        Array::new_multi(
+            VariantTypeId::Double,
+            vec![10f64.into(), 20f64.into(), 30f64.into()],
+            vec![3u32],
+        )
+        .unwrap(),
+    );
+    assert!(!array_changed(&old, &new, &filter));
+    let filter = DataChangeFilter {
+        trigger: DataChangeTrigger::StatusValue,
+        deadband_type: DeadbandType::Absolute as u32,
+        deadband_value: 1f64,
+    };
+    assert!(!array_changed(&old, &new, &filter));
+}
+
 #[test]
 fn monitored_item_data_change_filter() {
     // create an address space
@@ -679,6 +732,58 @@ fn unknown_node_id() {
     );
 }
 
+/// Test to ensure create monitored items rejects more items than the per call limit
+#[test]
+fn create_monitored_items_too_many_operations() {
+    do_subscription_service_test(
+        |server_state,
+         session,
+         address_space,
+         ss: SubscriptionService,
+         mis: MonitoredItemService| {
+            {
+                let mut server_state = trace_write_lock!(server_state);
+                server_state.operational_limits.max_monitored_items_per_call = 2;
+            }
+
+            let subscription_id = {
+                let request = create_subscription_request(0, 0);
+                let response: CreateSubscriptionResponse = supported_message_as!(
+                    ss.create_subscription(server_state.clone(), session.clone(), &request),
+                    CreateSubscriptionResponse
+                );
+                response.subscription_id
+            };
+
+            let create_monitored_items = |count: usize| {
+                let node_ids = (1..=count)
+                    .map(|i| NodeId::new(1, var_name(i)))
+                    .collect::<Vec<_>>();
+                let request = create_monitored_items_request(subscription_id, node_ids);
+                mis.create_monitored_items(
+                    server_state.clone(),
+                    session.clone(),
+                    address_space.clone(),
+                    &request,
+                )
+            };
+
+            // Up to the limit is fine
+            let response: CreateMonitoredItemsResponse =
+                supported_message_as!(create_monitored_items(2), CreateMonitoredItemsResponse);
+            assert_eq!(response.results.unwrap().len(), 2);
+
+            // Over the limit is rejected outright
+            let response: ServiceFault =
+                supported_message_as!(create_monitored_items(3), ServiceFault);
+            assert_eq!(
+                response.response_header.service_result,
+                StatusCode::BadTooManyOperations
+            );
+        },
+    );
+}
+
 #[test]
 fn monitored_item_triggers() {
     do_subscription_service_test(
@@ -746,6 +851,7 @@ fn monitored_item_triggers() {
 
             // set 3 monitored items to be reporting, sampling, disabled respectively
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[0],
@@ -753,6 +859,7 @@ fn monitored_item_triggers() {
                 &mis,
             );
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[1],
@@ -760,6 +867,7 @@ fn monitored_item_triggers() {
                 &mis,
             );
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[2],
@@ -769,6 +877,7 @@ fn monitored_item_triggers() {
 
             // set 1 monitored item to trigger other 3 plus itself
             let (add_results, remove_results) = set_triggering(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 monitored_item_ids[0],
@@ -832,6 +941,7 @@ fn monitored_item_triggers() {
 
             // set monitoring mode of all 3 to reporting.
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[0],
@@ -839,6 +949,7 @@ fn monitored_item_triggers() {
                 &mis,
             );
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[1],
@@ -846,6 +957,7 @@ fn monitored_item_triggers() {
                 &mis,
             );
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[2],
@@ -892,6 +1004,7 @@ fn monitored_item_triggers() {
 
             // revert to 3 items to be reporting, sampling, disabled
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[0],
@@ -899,6 +1012,7 @@ fn monitored_item_triggers() {
                 &mis,
             );
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[1],
@@ -906,6 +1020,7 @@ fn monitored_item_triggers() {
                 &mis,
             );
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggered_item_ids[2],
@@ -915,6 +1030,7 @@ fn monitored_item_triggers() {
 
             // change monitoring mode of triggering item to sampling and change value
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggering_item_id,
@@ -958,6 +1074,7 @@ fn monitored_item_triggers() {
 
             // change monitoring mode of triggering item to disable
             set_monitoring_mode(
+                server_state.clone(),
                 session.clone(),
                 subscription_id,
                 triggering_item_id,
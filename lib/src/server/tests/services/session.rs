@@ -96,6 +96,37 @@ fn anonymous_user_token() {
         );
         trace!("result = {:?}", result);
         assert_eq!(result.unwrap_err(), StatusCode::BadIdentityTokenRejected);
+
+        // A null token is treated as anonymous with the advertised policy id
+        let result = server_state.authenticate_endpoint(
+            &request,
+            "opc.tcp://localhost:4855/",
+            SecurityPolicy::None,
+            MessageSecurityMode::None,
+            &ExtensionObject::null(),
+            &server_nonce,
+        );
+        trace!("result = {:?}", result);
+        assert!(result.is_ok());
+
+        // A policy id the endpoint doesn't advertise
+        let token = AnonymousIdentityToken {
+            policy_id: UAString::from("xyz"),
+        };
+        let token = ExtensionObject::from_encodable(
+            ObjectId::AnonymousIdentityToken_Encoding_DefaultBinary,
+            &token,
+        );
+        let result = server_state.authenticate_endpoint(
+            &request,
+            "opc.tcp://localhost:4855/",
+            SecurityPolicy::None,
+            MessageSecurityMode::None,
+            &token,
+            &server_nonce,
+        );
+        trace!("result = {:?}", result);
+        assert_eq!(result.unwrap_err(), StatusCode::BadIdentityTokenRejected);
     });
 }
 
@@ -1,6 +1,6 @@
 use std::sync::Weak;
 
-use crate::server::services::view::ViewService;
+use crate::server::{continuation_point::ContinuationPoint, services::view::ViewService};
 use crate::supported_message_as;
 use crate::sync::*;
 
@@ -684,6 +684,24 @@ fn browse_next_cp() {
         assert!(!r1.continuation_point.is_null());
         verify_references_to_many_vars(references, 99, 0);
 
+        // The continuation point is an opaque ContinuationPoint whose index is where browsing resumes
+        let issued = ContinuationPoint::from_byte_string(&r1.continuation_point).unwrap();
+        assert_eq!(issued.index(), 99);
+
+        // A continuation point with a forged token is rejected and leaves the real one intact
+        let mut forged = r1.continuation_point.value.clone().unwrap();
+        let last = forged.len() - 1;
+        forged[last] ^= 0xff;
+        let response = do_browse_next(
+            &vs,
+            session.clone(),
+            address_space.clone(),
+            &ByteString::from(forged),
+            false,
+        );
+        let r2 = &response.results.unwrap()[0];
+        assert_eq!(r2.status_code, StatusCode::BadContinuationPointInvalid);
+
         // Expect continuation point and browse next to return last var and no more continuation point
         let response = do_browse_next(
             &vs,
@@ -806,6 +824,89 @@ fn browse_next_multiple_cps() {
     });
 }
 
+#[test]
+fn browse_next_no_continuation_points() {
+    // Browse more times than the session has continuation points for, expecting the browse that
+    // exceeds the limit to fail until a continuation point is released
+    do_view_service_test(|server_state, session, address_space, vs| {
+        let parent_node_id = add_many_vars_to_address_space(address_space.clone(), 100).0;
+        let nodes = vec![parent_node_id.clone()];
+
+        let continuation_points = (0..crate::server::constants::MAX_BROWSE_CONTINUATION_POINTS)
+            .map(|_| {
+                let response = do_browse(
+                    &vs,
+                    server_state.clone(),
+                    session.clone(),
+                    address_space.clone(),
+                    &nodes,
+                    10,
+                    BrowseDirection::Forward,
+                );
+                let r1 = &response.results.unwrap()[0];
+                assert_eq!(r1.status_code, StatusCode::Good);
+                assert!(!r1.continuation_point.is_null());
+                r1.continuation_point.clone()
+            })
+            .collect::<Vec<ByteString>>();
+
+        // Continuation points are unique
+        assert!(continuation_points[1..]
+            .iter()
+            .all(|cp| *cp != continuation_points[0]));
+
+        // The session has no continuation points left
+        let response = do_browse(
+            &vs,
+            server_state.clone(),
+            session.clone(),
+            address_space.clone(),
+            &nodes,
+            10,
+            BrowseDirection::Forward,
+        );
+        let r1 = &response.results.unwrap()[0];
+        assert_eq!(r1.status_code, StatusCode::BadNoContinuationPoints);
+        assert!(r1.continuation_point.is_null());
+        assert!(r1.references.is_none());
+
+        // Browse next from a continuation point replaces it with a new one
+        let response = do_browse_next(
+            &vs,
+            session.clone(),
+            address_space.clone(),
+            &continuation_points[0],
+            false,
+        );
+        let r2 = &response.results.unwrap()[0];
+        assert_eq!(r2.status_code, StatusCode::Good);
+        assert!(!r2.continuation_point.is_null());
+        verify_references_to_many_vars(r2.references.as_ref().unwrap(), 10, 10);
+
+        // Releasing a continuation point makes room for a browse
+        let response = do_browse_next(
+            &vs,
+            session.clone(),
+            address_space.clone(),
+            &continuation_points[1],
+            true,
+        );
+        assert!(response.results.is_none());
+        let response = do_browse(
+            &vs,
+            server_state,
+            session.clone(),
+            address_space.clone(),
+            &nodes,
+            10,
+            BrowseDirection::Forward,
+        );
+        let r1 = &response.results.unwrap()[0];
+        assert_eq!(r1.status_code, StatusCode::Good);
+        assert!(!r1.continuation_point.is_null());
+    });
+}
+
 #[test]
 fn browse_next_modify_address_space() {
     // Modify the address space after a browse so continuation point becomes invalid
@@ -17,6 +17,11 @@ pub struct Array {
     /// with dimensions [2,2,2] is written in this order - [0,0,0], [0,0,1], [0,1,0], [0,1,1],
     /// [1,0,0], [1,0,1], [1,1,0], [1,1,1].
     pub dimensions: Vec<u32>,
+
+    /// A null array has no values and is encoded with a length of -1, as distinct from an empty
+    /// array which is encoded with a length of 0.
+    #[serde(default)]
+    pub null: bool,
 }
 
 impl Array {
@@ -30,6 +35,7 @@ impl Array {
                 value_type,
                 values,
                 dimensions: Vec::new(),
+                null: false,
             })
         } else {
             Err(StatusCode::BadDecodingError)
@@ -51,12 +57,20 @@ impl Array {
                 value_type,
                 values,
                 dimensions: dimensions.into(),
+                null: false,
             })
         } else {
             Err(StatusCode::BadDecodingError)
         }
     }
 
+    /// Creates a null array of the specified type, i.e. one that is encoded with a length of -1.
+    pub fn new_null(value_type: VariantTypeId) -> Result<Array, StatusCode> {
+        let mut array = Self::new_single(value_type, Vec::new())?;
+        array.null = true;
+        Ok(array)
+    }
+
     /// This is a runtime check to ensure the type of the array also matches the types of the variants in the array.
     fn validate_array_type_to_values(value_type: VariantTypeId, values: &[Variant]) -> bool {
         match value_type {
@@ -77,7 +91,28 @@ impl Array {
     }
 
     pub fn is_valid(&self) -> bool {
-        self.is_valid_dimensions() && Self::array_is_valid(&self.values)
+        self.is_valid_null() && self.is_valid_dimensions() && Self::array_is_valid(&self.values)
+    }
+
+    /// Tests that a null array has no values, since its length is encoded as -1 and any values
+    /// written after it would corrupt the stream.
+    fn is_valid_null(&self) -> bool {
+        if self.null && !self.values.is_empty() {
+            error!("Null array has {} values", self.values.len());
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Tests if the array is null. A null array is also empty.
+    pub fn is_null(&self) -> bool {
+        self.null
+    }
+
+    /// Tests if the array has no values, which is true for null and empty arrays.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
     }
 
     pub fn has_dimensions(&self) -> bool {
@@ -112,6 +147,40 @@ impl Array {
         }
     }
 
+    /// Validates the dimensions of a fixed size multi dimensional array of `values_len` values.
+    /// There must be at least one dimension, no dimension may be 0, and the product of the
+    /// dimensions must equal the number of values. Fails with `BadDecodingError` otherwise.
+    pub fn validate_dimensions(dimensions: &[u32], values_len: usize) -> Result<(), StatusCode> {
+        if dimensions.is_empty() {
+            error!("Array has no dimensions");
+            return Err(StatusCode::BadDecodingError);
+        }
+        if dimensions.iter().any(|d| *d == 0) {
+            error!("Invalid array dimensions {:?}", dimensions);
+            return Err(StatusCode::BadDecodingError);
+        }
+        // This looks clunky but it's to prevent a panic from malicious data causing an
+        // overflow panic
+        let mut dimensions_length = 1usize;
+        for d in dimensions {
+            if let Some(v) = dimensions_length.checked_mul(*d as usize) {
+                dimensions_length = v;
+            } else {
+                error!("Array dimension overflow!");
+                return Err(StatusCode::BadDecodingError);
+            }
+        }
+        if dimensions_length != values_len {
+            error!(
+                "Array dimensions {:?} do not match array length {}",
+                dimensions, values_len
+            );
+            Err(StatusCode::BadDecodingError)
+        } else {
+            Ok(())
+        }
+    }
+
     fn is_valid_dimensions(&self) -> bool {
         // Check that the array dimensions match the length of the array
         let mut length: usize = 1;
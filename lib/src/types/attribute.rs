@@ -19,7 +19,7 @@ impl fmt::Display for AttributeIdError {
 
 impl Error for AttributeIdError {}
 
-#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum AttributeId {
     NodeId = 1,
     NodeClass = 2,
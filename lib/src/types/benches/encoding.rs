@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate criterion;
+
+use criterion::Criterion;
+
+use opcua::types::{
+    encoding::{encode_to_vec, size_and_encode},
+    *,
+};
+
+/// A read response holding many values, each of which is a nested structure of its own
+fn make_large_read_response() -> ReadResponse {
+    let results = (0..10000)
+        .map(|i| DataValue::new_now(Variant::from(format!("Value {}", i))))
+        .collect();
+    ReadResponse {
+        response_header: ResponseHeader::null(),
+        results: Some(results),
+        diagnostic_infos: None,
+    }
+}
+
+fn encode_to_vec_benchmark(c: &mut Criterion) {
+    // This benchmark sizes the message with byte_len() and then encodes it, walking it twice
+    let response = make_large_read_response();
+    c.bench_function("encode_to_vec", |b| {
+        b.iter(|| encode_to_vec(&response).unwrap())
+    });
+}
+
+fn size_and_encode_benchmark(c: &mut Criterion) {
+    // This benchmark encodes the message into a growing buffer, walking it once
+    let response = make_large_read_response();
+    c.bench_function("size_and_encode", |b| {
+        b.iter(|| size_and_encode(&response).unwrap())
+    });
+}
+
+criterion_group!(benches, encode_to_vec_benchmark, size_and_encode_benchmark);
+criterion_main!(benches);
@@ -51,7 +51,7 @@ impl BinaryEncoder<ByteString> for ByteString {
             let value = self.value.as_ref().unwrap();
             size += write_i32(stream, value.len() as i32)?;
             size += process_encode_io_result(stream.write(value))?;
-            assert_eq!(size, self.byte_len());
+            debug_assert_eq!(size, self.byte_len());
             Ok(size)
         }
     }
@@ -67,7 +67,7 @@ impl BinaryEncoder<ByteString> for ByteString {
         } else if len as usize > decoding_options.max_byte_string_length {
             error!(
                 "ByteString length {} exceeds decoding limit {}",
-                len, decoding_options.max_string_length
+                len, decoding_options.max_byte_string_length
             );
             Err(StatusCode::BadDecodingError)
         } else {
@@ -95,6 +95,13 @@ impl From<Vec<u8>> for ByteString {
     }
 }
 
+/// `None` is the null byte string, i.e. the one encoded with a length of -1.
+impl From<Option<Vec<u8>>> for ByteString {
+    fn from(value: Option<Vec<u8>>) -> Self {
+        ByteString { value }
+    }
+}
+
 impl From<Guid> for ByteString {
     fn from(value: Guid) -> Self {
         ByteString::from(value.as_bytes().to_vec())
@@ -157,22 +164,32 @@ impl ByteString {
         self.is_null() || self.is_empty()
     }
 
-    /// Creates a byte string from a Base64 encoded string
-    pub fn from_base64(data: &str) -> Option<ByteString> {
-        if let Ok(bytes) = base64::decode(data) {
-            Some(Self::from(bytes))
+    /// Creates a byte string from a Base64 encoded string. An empty string decodes to a null byte
+    /// string, the inverse of `as_base64`.
+    ///
+    /// # Errors
+    ///
+    /// `BadDecodingError` if the string is not valid Base64.
+    ///
+    pub fn from_base64(data: &str) -> Result<ByteString, StatusCode> {
+        if data.is_empty() {
+            Ok(ByteString::null())
         } else {
-            None
+            base64::decode(data).map(Self::from).map_err(|err| {
+                error!("Byte string cannot be decoded from Base64, err = {}", err);
+                StatusCode::BadDecodingError
+            })
         }
     }
 
-    /// Encodes the bytestring as a Base64 encoded string
+    /// Encodes the bytestring as a Base64 encoded string. A null or empty byte string encodes
+    /// as an empty string.
     pub fn as_base64(&self) -> String {
         // Base64 encodes the byte string so it can be represented as a string
         if let Some(ref value) = self.value {
             base64::encode(value)
         } else {
-            base64::encode("")
+            String::new()
         }
     }
 
@@ -9,7 +9,7 @@ use std::io::{Read, Write};
 use crate::types::{
     byte_string::ByteString, date_time::*, encoding::*, guid::Guid, localized_text::LocalizedText,
     node_id::NodeId, qualified_name::QualifiedName, service_types::TimestampsToReturn,
-    status_codes::StatusCode, string::UAString, variant::Variant,
+    status_codes::StatusCode, string::UAString, variant::{FromVariant, Variant},
 };
 
 bitflags! {
@@ -356,6 +356,26 @@ impl Default for DataValue {
 }
 
 impl DataValue {
+    /// Returns the value as a scalar of the requested type. If there is no value, the error is
+    /// the data value's status when that is bad, otherwise `BadNoData`. A value of another type
+    /// is a `BadTypeMismatch`.
+    pub fn value_as<T>(&self) -> Result<T, StatusCode>
+    where
+        T: FromVariant,
+    {
+        match self.value {
+            Some(ref value) => T::from_variant(value),
+            None => {
+                let status = self.status();
+                if status.is_bad() {
+                    Err(status)
+                } else {
+                    Err(StatusCode::BadNoData)
+                }
+            }
+        }
+    }
+
     /// Creates a `DataValue` from the supplied value with nothing else.
     pub fn value_only<V>(value: V) -> DataValue
     where
@@ -468,6 +488,22 @@ impl DataValue {
         self.status().status().is_good()
     }
 
+    /// Compares two data values for equality like `==` except that the values are compared
+    /// with `Variant::bitwise_eq`, so a NaN value equals the same NaN value.
+    pub fn bitwise_eq(&self, other: &DataValue) -> bool {
+        let value_eq = match (&self.value, &other.value) {
+            (Some(v1), Some(v2)) => v1.bitwise_eq(v2),
+            (None, None) => true,
+            _ => false,
+        };
+        value_eq
+            && self.status == other.status
+            && self.source_timestamp == other.source_timestamp
+            && self.source_picoseconds == other.source_picoseconds
+            && self.server_timestamp == other.server_timestamp
+            && self.server_picoseconds == other.server_picoseconds
+    }
+
     fn encoding_mask(&self) -> DataValueFlags {
         let mut encoding_mask = DataValueFlags::empty();
         if self.value.is_some() {
@@ -20,6 +20,7 @@ use crate::types::encoding::*;
 const NANOS_PER_SECOND: i64 = 1_000_000_000;
 const NANOS_PER_TICK: i64 = 100;
 const TICKS_PER_SECOND: i64 = NANOS_PER_SECOND / NANOS_PER_TICK;
+const TICKS_PER_MILLISECOND: i64 = TICKS_PER_SECOND / 1000;
 
 const MIN_YEAR: u16 = 1601;
 const MAX_YEAR: u16 = 9999;
@@ -67,9 +68,14 @@ impl BinaryEncoder<DateTime> for DateTime {
     fn decode<S: Read>(stream: &mut S, decoding_options: &DecodingOptions) -> EncodingResult<Self> {
         let ticks = read_i64(stream)?;
         let date_time = DateTime::from(ticks);
-        // Client offset is a value that can be overridden to account for time discrepancies between client & server -
-        // note perhaps it is not a good idea to do it right here but it is the lowest point to intercept DateTime values.
-        Ok(date_time - decoding_options.client_offset)
+        if date_time.is_null() || date_time == DateTime::endtimes() {
+            // The null and endtimes sentinels are not points in time so they are not offset
+            Ok(date_time)
+        } else {
+            // Client offset is a value that can be overridden to account for time discrepancies between client & server -
+            // note perhaps it is not a good idea to do it right here but it is the lowest point to intercept DateTime values.
+            Ok(date_time - decoding_options.client_offset)
+        }
     }
 }
 
@@ -105,7 +111,16 @@ impl Sub<Duration> for DateTime {
 
 impl PartialOrd for DateTime {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.date_time.cmp(&other.date_time))
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for DateTime {}
+
+/// Date times are ordered by their ticks
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.ticks().cmp(&other.ticks())
     }
 }
 
@@ -166,11 +181,14 @@ impl From<DateTimeUtc> for DateTime {
     }
 }
 
+/// Ticks are clamped to the OPC UA range, so 0 or less is the epoch and the endtimes or more,
+/// including the i64::MAX that signifies end times, is the endtimes.
 impl From<i64> for DateTime {
     fn from(value: i64) -> Self {
-        if value == i64::max_value() {
-            // Max signifies end times
+        if value >= Self::endtimes_ticks() {
             Self::endtimes()
+        } else if value <= 0 {
+            Self::epoch()
         } else {
             let secs = value / TICKS_PER_SECOND;
             let nanos = (value - secs * TICKS_PER_SECOND) * NANOS_PER_TICK;
@@ -281,18 +299,33 @@ impl DateTime {
     }
 
     /// To checked ticks. Function returns 0 or MAX_INT64
-    /// if date exceeds valid OPC UA range
+    /// if date exceeds valid OPC UA range, including the endtimes itself
     pub fn checked_ticks(&self) -> i64 {
         let nanos = self.ticks();
         if nanos < 0 {
             return 0;
         }
-        if nanos > Self::endtimes_ticks() {
+        if nanos >= Self::endtimes_ticks() {
             return i64::max_value();
         }
         nanos
     }
 
+    /// Returns a date time that is the supplied number of milliseconds after this one. The
+    /// duration may be negative and is rounded to the nearest tick. The result is clamped to
+    /// the epoch and the endtimes.
+    pub fn add_duration(&self, ms: f64) -> DateTime {
+        // The cast saturates for durations too large for ticks
+        let ticks = (ms * TICKS_PER_MILLISECOND as f64).round() as i64;
+        DateTime::from(self.ticks().saturating_add(ticks))
+    }
+
+    /// Returns the number of milliseconds that have elapsed from the other date time to this one.
+    /// The value is negative if the other date time is later.
+    pub fn duration_since(&self, other: &DateTime) -> f64 {
+        (self.ticks() - other.ticks()) as f64 / TICKS_PER_MILLISECOND as f64
+    }
+
     /// Time as chrono
     pub fn as_chrono(&self) -> DateTimeUtc {
         self.date_time
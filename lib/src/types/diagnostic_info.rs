@@ -128,12 +128,14 @@ impl BinaryEncoder<DiagnosticInfo> for DiagnosticInfo {
         }
         if let Some(ref inner_diagnostic_info) = self.inner_diagnostic_info {
             // Write inner diagnostic info
-            size += inner_diagnostic_info.clone().encode(stream)?;
+            size += inner_diagnostic_info.encode(stream)?;
         }
         Ok(size)
     }
 
     fn decode<S: Read>(stream: &mut S, decoding_options: &DecodingOptions) -> EncodingResult<Self> {
+        // Diagnostic info is depth checked since the inner diagnostic info recurses
+        let _depth_lock = decoding_options.depth_lock()?;
         let encoding_mask =
             DiagnosticInfoMask::from_bits_truncate(u8::decode(stream, decoding_options)?);
         let mut diagnostic_info = DiagnosticInfo::default();
@@ -171,6 +171,50 @@ pub trait BinaryEncoder<T> {
     }
 }
 
+/// Encodes the value into a vec that is presized to its byte length. Unlike
+/// `BinaryEncoder::encode_to_vec()` an encoding error is returned rather than ignored.
+pub fn encode_to_vec<T>(value: &T) -> EncodingResult<Vec<u8>>
+where
+    T: BinaryEncoder<T>,
+{
+    let mut stream = Cursor::new(Vec::with_capacity(value.byte_len()));
+    let _ = value.encode(&mut stream)?;
+    Ok(stream.into_inner())
+}
+
+/// Encodes the value in a single pass into a buffer that grows as it is written. Unlike
+/// `encode_to_vec()` the value is not walked to compute its `byte_len()` first, which for a large
+/// nested message, e.g. a response holding thousands of values, costs as much as encoding it.
+/// `byte_len()` is still the way to go when the size is needed up front, e.g. for a chunk header.
+pub fn size_and_encode<T>(value: &T) -> EncodingResult<Vec<u8>>
+where
+    T: BinaryEncoder<T>,
+{
+    let mut stream = Cursor::new(Vec::new());
+    let size = value.encode(&mut stream)?;
+    let buffer = stream.into_inner();
+    if size != buffer.len() {
+        error!(
+            "Encoder reported {} bytes but wrote {} bytes",
+            size,
+            buffer.len()
+        );
+        Err(StatusCode::BadEncodingError)
+    } else {
+        Ok(buffer)
+    }
+}
+
+/// Decodes a value from the start of the bytes, ignoring any bytes that follow it. Bytes that
+/// end before the value does are a `BadDecodingError`.
+pub fn decode_from_slice<T>(bytes: &[u8], decoding_options: &DecodingOptions) -> EncodingResult<T>
+where
+    T: BinaryEncoder<T>,
+{
+    let mut stream = Cursor::new(bytes);
+    T::decode(&mut stream, decoding_options)
+}
+
 /// Converts an IO encoding error (and logs when in error) into an EncodingResult
 pub fn process_encode_io_result(result: Result<usize>) -> EncodingResult<usize> {
     result.map_err(|err| {
@@ -242,6 +286,107 @@ pub fn read_array<S: Read, T: BinaryEncoder<T>>(
     }
 }
 
+/// Arrays are encoded as an `Int32` length followed by the elements. A `Vec` cannot be null so it
+/// is always written with its length, 0 when it is empty, and a null array decodes to an empty
+/// `Vec`. Use `write_array()` and `read_array()` with an `Option<Vec<T>>` to keep a null array
+/// distinct from an empty one.
+impl<T> BinaryEncoder<Vec<T>> for Vec<T>
+where
+    T: BinaryEncoder<T>,
+{
+    fn byte_len(&self) -> usize {
+        4 + self.iter().map(|v| v.byte_len()).sum::<usize>()
+    }
+
+    fn encode<S: Write>(&self, stream: &mut S) -> EncodingResult<usize> {
+        let mut size = write_i32(stream, self.len() as i32)?;
+        for value in self.iter() {
+            size += value.encode(stream)?;
+        }
+        Ok(size)
+    }
+
+    fn decode<S: Read>(stream: &mut S, decoding_options: &DecodingOptions) -> EncodingResult<Self> {
+        Ok(read_array(stream, decoding_options)?.unwrap_or_default())
+    }
+}
+
+/// Makes the encoding mask that leads a structure with optional fields. Bit `n` of the mask is set
+/// when the `n`th optional field is present, so a structure whose optional fields are all absent
+/// has a mask of 0. The mask has room for 32 optional fields, more than that is an error.
+pub fn optional_fields_mask(present: &[bool]) -> EncodingResult<u32> {
+    if present.len() > 32 {
+        error!(
+            "Structure has {} optional fields which exceeds the 32 that the encoding mask can hold",
+            present.len()
+        );
+        Err(StatusCode::BadEncodingError)
+    } else {
+        Ok(present
+            .iter()
+            .enumerate()
+            .filter(|(_, present)| **present)
+            .fold(0, |mask, (i, _)| mask | (1 << i)))
+    }
+}
+
+/// Reads the encoding mask that leads a structure with the number of optional fields. A mask with
+/// bits set for fields that the structure does not have is invalid.
+pub fn read_optional_fields_mask<S: Read>(
+    stream: &mut S,
+    optional_field_count: usize,
+) -> EncodingResult<u32> {
+    let encoding_mask = read_u32(stream)?;
+    let valid_bits = if optional_field_count >= 32 {
+        u32::MAX
+    } else {
+        (1u32 << optional_field_count) - 1
+    };
+    if encoding_mask & !valid_bits != 0 {
+        error!(
+            "Encoding mask {:#x} has bits set beyond the {} optional fields",
+            encoding_mask, optional_field_count
+        );
+        Err(StatusCode::BadDecodingError)
+    } else {
+        Ok(encoding_mask)
+    }
+}
+
+/// Calculates the length in bytes of an optional field of a structure. An absent field is not
+/// encoded at all.
+pub fn byte_len_optional<T: BinaryEncoder<T>>(value: &Option<T>) -> usize {
+    value.as_ref().map_or(0, |v| v.byte_len())
+}
+
+/// Writes an optional field of a structure to the stream if it is present. An absent field is
+/// skipped entirely, its absence is only recorded in the encoding mask.
+pub fn write_optional<S: Write, T: BinaryEncoder<T>>(
+    stream: &mut S,
+    value: &Option<T>,
+) -> EncodingResult<usize> {
+    if let Some(ref value) = value {
+        value.encode(stream)
+    } else {
+        Ok(0)
+    }
+}
+
+/// Reads an optional field of a structure from the stream if its bit is set in the encoding mask,
+/// otherwise the field is absent and nothing is read.
+pub fn read_optional<S: Read, T: BinaryEncoder<T>>(
+    stream: &mut S,
+    encoding_mask: u32,
+    field_index: usize,
+    decoding_options: &DecodingOptions,
+) -> EncodingResult<Option<T>> {
+    if field_index < 32 && encoding_mask & (1 << field_index) != 0 {
+        Ok(Some(T::decode(stream, decoding_options)?))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Writes a series of identical bytes to the stream
 pub fn write_bytes(stream: &mut dyn Write, value: u8, count: usize) -> EncodingResult<usize> {
     for _ in 0..count {
@@ -79,7 +79,7 @@ impl BinaryEncoder<ExtensionObject> for ExtensionObject {
                 size += value.encode(stream)?;
             }
         }
-        assert_eq!(size, self.byte_len());
+        debug_assert_eq!(size, self.byte_len());
         Ok(size)
     }
 
@@ -133,24 +133,32 @@ impl ExtensionObject {
     }
 
     /// Creates an extension object with the specified node id and the encodable object as its payload.
-    /// The body is set to a byte string containing the encoded struct.
+    /// The body is set to a byte string containing the encoded struct, or no body if the struct
+    /// cannot be encoded.
     pub fn from_encodable<N, T>(node_id: N, encodable: &T) -> ExtensionObject
     where
         N: Into<NodeId>,
         T: BinaryEncoder<T>,
     {
-        // Serialize to extension object
-        let mut stream = Cursor::new(vec![0u8; encodable.byte_len()]);
-        let _ = encodable.encode(&mut stream);
+        // Serialize to extension object. The struct is encoded in a single pass rather than being
+        // sized first, which matters when many extension objects are nested in one message.
+        let body = match size_and_encode(encodable) {
+            Ok(body) => ExtensionObjectEncoding::ByteString(ByteString::from(body)),
+            Err(err) => {
+                error!("Cannot encode body of extension object, err = {}", err);
+                ExtensionObjectEncoding::None
+            }
+        };
         ExtensionObject {
             node_id: node_id.into(),
-            body: ExtensionObjectEncoding::ByteString(ByteString::from(stream.into_inner())),
+            body,
         }
     }
 
     /// Decodes the inner content of the extension object and returns it. The node id is ignored
     /// for decoding. The caller supplies the binary encoder impl that should be used to extract
-    /// the data. Errors result in a decoding error.
+    /// the data. Errors result in a decoding error, as does a body that is longer than the
+    /// encoded struct, since that means the declared body length is wrong.
     pub fn decode_inner<T>(&self, decoding_options: &DecodingOptions) -> EncodingResult<T>
     where
         T: BinaryEncoder<T>,
@@ -158,9 +166,18 @@ impl ExtensionObject {
         match self.body {
             ExtensionObjectEncoding::ByteString(ref byte_string) => {
                 if let Some(ref value) = byte_string.value {
-                    // let value = value.clone();
                     let mut stream = Cursor::new(value);
-                    T::decode(&mut stream, decoding_options)
+                    let result = T::decode(&mut stream, decoding_options)?;
+                    if stream.position() as usize != value.len() {
+                        error!(
+                            "Extension object body is {} bytes but only {} were decoded",
+                            value.len(),
+                            stream.position()
+                        );
+                        Err(StatusCode::BadDecodingError)
+                    } else {
+                        Ok(result)
+                    }
                 } else {
                     Err(StatusCode::BadDecodingError)
                 }
@@ -12,7 +12,7 @@ use std::{
 };
 use uuid::Uuid;
 
-use crate::types::encoding::*;
+use crate::{crypto::random, types::encoding::*};
 
 /// A Guid is a 16 byte Globally Unique Identifier.
 #[derive(Eq, PartialEq, Clone, Hash)]
@@ -58,20 +58,37 @@ impl fmt::Debug for Guid {
     }
 }
 
+/// A Guid is encoded as its Data1 (UInt32), Data2 (UInt16) and Data3 (UInt16) fields in little
+/// endian order, followed by the 8 bytes of Data4 as they are, see OPC UA Part 6 5.1.3. The bytes
+/// held by `Uuid` are in the big endian order of the string form, so each field is converted
+/// explicitly rather than copied as is.
 impl BinaryEncoder<Guid> for Guid {
     fn byte_len(&self) -> usize {
         16
     }
 
     fn encode<S: Write>(&self, stream: &mut S) -> EncodingResult<usize> {
+        let bytes = self.uuid.as_bytes();
+        let data1 = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let data2 = u16::from_be_bytes([bytes[4], bytes[5]]);
+        let data3 = u16::from_be_bytes([bytes[6], bytes[7]]);
         let mut size: usize = 0;
-        size += process_encode_io_result(stream.write(self.uuid.as_bytes()))?;
+        size += write_u32(stream, data1)?;
+        size += write_u16(stream, data2)?;
+        size += write_u16(stream, data3)?;
+        size += process_encode_io_result(stream.write(&bytes[8..]))?;
         Ok(size)
     }
 
     fn decode<S: Read>(stream: &mut S, _: &DecodingOptions) -> EncodingResult<Self> {
+        let data1 = read_u32(stream)?;
+        let data2 = read_u16(stream)?;
+        let data3 = read_u16(stream)?;
         let mut bytes = [0u8; 16];
-        process_decode_io_result(stream.read_exact(&mut bytes))?;
+        bytes[0..4].copy_from_slice(&data1.to_be_bytes());
+        bytes[4..6].copy_from_slice(&data2.to_be_bytes());
+        bytes[6..8].copy_from_slice(&data3.to_be_bytes());
+        read_bytes(stream, &mut bytes[8..])?;
         Ok(Guid {
             uuid: Uuid::from_bytes(bytes),
         })
@@ -107,6 +124,22 @@ impl Guid {
         }
     }
 
+    /// Creates a random (version 4) Guid from the cryptographically strong random number generator
+    /// used for nonces, e.g. for session ids that must not be predictable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the random number generator fails, since a predictable value must never be used.
+    ///
+    pub fn new_random() -> Guid {
+        let mut bytes = [0u8; 16];
+        random::bytes(&mut bytes).expect("Random number generator failed");
+        // Set the version (4) and the RFC 4122 variant bits
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Guid::from_bytes(bytes)
+    }
+
     /// Returns the bytes of the Guid
     pub fn as_bytes(&self) -> &[u8; 16] {
         self.uuid.as_bytes()
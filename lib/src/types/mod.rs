@@ -217,6 +217,8 @@ pub mod service_types;
 pub mod status_code;
 pub mod string;
 pub mod variant;
+#[cfg(feature = "json-value")]
+pub mod variant_json;
 
 pub use crate::types::{
     argument::*, array::*, attribute::*, basic_types::*, byte_string::*, data_types::*,
@@ -56,7 +56,7 @@ impl FromStr for Identifier {
                 "i=" => v.parse::<u32>().map(|v| v.into()).map_err(|_| ()),
                 "s=" => Ok(UAString::from(v).into()),
                 "g=" => Guid::from_str(v).map(|v| v.into()).map_err(|_| ()),
-                "b=" => ByteString::from_base64(v).map(|v| v.into()).ok_or(()),
+                "b=" => ByteString::from_base64(v).map(|v| v.into()).map_err(|_| ()),
                 _ => Err(()),
             }
         }
@@ -198,7 +198,7 @@ impl BinaryEncoder<NodeId> for NodeId {
                 size += value.encode(stream)?;
             }
         }
-        assert_eq!(size, self.byte_len());
+        debug_assert_eq!(size, self.byte_len());
         Ok(size)
     }
 
@@ -348,6 +348,33 @@ impl NodeId {
         }
     }
 
+    /// Constructs a numeric node id. When encoded it uses the two or four byte form if the
+    /// namespace and value are small enough.
+    pub fn numeric(namespace: u16, value: u32) -> NodeId {
+        NodeId::new(namespace, value)
+    }
+
+    /// Constructs a string node id.
+    pub fn string<T>(namespace: u16, value: T) -> NodeId
+    where
+        T: Into<String>,
+    {
+        NodeId {
+            namespace,
+            identifier: Identifier::String(UAString::from(value.into())),
+        }
+    }
+
+    /// Constructs a guid node id.
+    pub fn guid(namespace: u16, value: Guid) -> NodeId {
+        NodeId::new(namespace, value)
+    }
+
+    /// Constructs an opaque node id, i.e. one whose identifier is a byte string.
+    pub fn opaque(namespace: u16, value: ByteString) -> NodeId {
+        NodeId::new(namespace, value)
+    }
+
     /// Returns the node id for the root folder.
     pub fn root_folder_id() -> NodeId {
         ObjectId::RootFolder.into()
@@ -504,7 +531,7 @@ impl BinaryEncoder<ExpandedNodeId> for ExpandedNodeId {
         if self.server_index != 0 {
             size += self.server_index.encode(stream)?;
         }
-        assert_eq!(size, self.byte_len());
+        debug_assert_eq!(size, self.byte_len());
         Ok(size)
     }
 
@@ -44,7 +44,7 @@ impl BinaryEncoder<ResponseHeader> for ResponseHeader {
         size += self.service_diagnostics.encode(stream)?;
         size += write_array(stream, &self.string_table)?;
         size += self.additional_header.encode(stream)?;
-        assert_eq!(size, self.byte_len());
+        debug_assert_eq!(size, self.byte_len());
         Ok(size)
     }
 
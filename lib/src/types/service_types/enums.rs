@@ -45,7 +45,7 @@ impl BinaryEncoder<NodeIdType> for NodeIdType {
             5 => Ok(Self::ByteString),
             v => {
                 error!("Invalid value {} for enum NodeIdType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -75,7 +75,7 @@ impl BinaryEncoder<NamingRuleType> for NamingRuleType {
             3 => Ok(Self::Constraint),
             v => {
                 error!("Invalid value {} for enum NamingRuleType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -107,7 +107,7 @@ impl BinaryEncoder<OpenFileMode> for OpenFileMode {
             8 => Ok(Self::Append),
             v => {
                 error!("Invalid value {} for enum OpenFileMode", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -143,7 +143,7 @@ impl BinaryEncoder<IdentityCriteriaType> for IdentityCriteriaType {
             6 => Ok(Self::AuthenticatedUser),
             v => {
                 error!("Invalid value {} for enum IdentityCriteriaType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -179,7 +179,7 @@ impl BinaryEncoder<TrustListMasks> for TrustListMasks {
             15 => Ok(Self::All),
             v => {
                 error!("Invalid value {} for enum TrustListMasks", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -211,7 +211,7 @@ impl BinaryEncoder<PubSubState> for PubSubState {
             3 => Ok(Self::Error),
             v => {
                 error!("Invalid value {} for enum PubSubState", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -290,7 +290,7 @@ impl BinaryEncoder<OverrideValueHandling> for OverrideValueHandling {
             2 => Ok(Self::OverrideValue),
             v => {
                 error!("Invalid value {} for enum OverrideValueHandling", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -320,7 +320,7 @@ impl BinaryEncoder<DataSetOrderingType> for DataSetOrderingType {
             2 => Ok(Self::AscendingWriterIdSingle),
             v => {
                 error!("Invalid value {} for enum DataSetOrderingType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -466,7 +466,7 @@ impl BinaryEncoder<BrokerTransportQualityOfService> for BrokerTransportQualityOf
             4 => Ok(Self::ExactlyOnce),
             v => {
                 error!("Invalid value {} for enum BrokerTransportQualityOfService", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -500,7 +500,7 @@ impl BinaryEncoder<DiagnosticsLevel> for DiagnosticsLevel {
             4 => Ok(Self::Debug),
             v => {
                 error!("Invalid value {} for enum DiagnosticsLevel", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -528,7 +528,7 @@ impl BinaryEncoder<PubSubDiagnosticsCounterClassification> for PubSubDiagnostics
             1 => Ok(Self::Error),
             v => {
                 error!("Invalid value {} for enum PubSubDiagnosticsCounterClassification", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -560,7 +560,7 @@ impl BinaryEncoder<IdType> for IdType {
             3 => Ok(Self::Opaque),
             v => {
                 error!("Invalid value {} for enum IdType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -602,7 +602,7 @@ impl BinaryEncoder<NodeClass> for NodeClass {
             128 => Ok(Self::View),
             v => {
                 error!("Invalid value {} for enum NodeClass", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -779,7 +779,7 @@ impl BinaryEncoder<StructureType> for StructureType {
             2 => Ok(Self::Union),
             v => {
                 error!("Invalid value {} for enum StructureType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -811,7 +811,7 @@ impl BinaryEncoder<ApplicationType> for ApplicationType {
             3 => Ok(Self::DiscoveryServer),
             v => {
                 error!("Invalid value {} for enum ApplicationType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -843,7 +843,7 @@ impl BinaryEncoder<MessageSecurityMode> for MessageSecurityMode {
             3 => Ok(Self::SignAndEncrypt),
             v => {
                 error!("Invalid value {} for enum MessageSecurityMode", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -875,7 +875,7 @@ impl BinaryEncoder<UserTokenType> for UserTokenType {
             3 => Ok(Self::IssuedToken),
             v => {
                 error!("Invalid value {} for enum UserTokenType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -903,7 +903,7 @@ impl BinaryEncoder<SecurityTokenRequestType> for SecurityTokenRequestType {
             1 => Ok(Self::Renew),
             v => {
                 error!("Invalid value {} for enum SecurityTokenRequestType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -997,7 +997,7 @@ impl BinaryEncoder<NodeAttributesMask> for NodeAttributesMask {
             26501356 => Ok(Self::View),
             v => {
                 error!("Invalid value {} for enum NodeAttributesMask", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1076,7 +1076,7 @@ impl BinaryEncoder<BrowseDirection> for BrowseDirection {
             3 => Ok(Self::Invalid),
             v => {
                 error!("Invalid value {} for enum BrowseDirection", v);
-                Ok(Self::Invalid)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1120,7 +1120,7 @@ impl BinaryEncoder<BrowseResultMask> for BrowseResultMask {
             60 => Ok(Self::TargetInfo),
             v => {
                 error!("Invalid value {} for enum BrowseResultMask", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1180,7 +1180,7 @@ impl BinaryEncoder<FilterOperator> for FilterOperator {
             17 => Ok(Self::BitwiseOr),
             v => {
                 error!("Invalid value {} for enum FilterOperator", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1214,7 +1214,7 @@ impl BinaryEncoder<TimestampsToReturn> for TimestampsToReturn {
             4 => Ok(Self::Invalid),
             v => {
                 error!("Invalid value {} for enum TimestampsToReturn", v);
-                Ok(Self::Invalid)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1246,7 +1246,7 @@ impl BinaryEncoder<HistoryUpdateType> for HistoryUpdateType {
             4 => Ok(Self::Delete),
             v => {
                 error!("Invalid value {} for enum HistoryUpdateType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1278,7 +1278,7 @@ impl BinaryEncoder<PerformUpdateType> for PerformUpdateType {
             4 => Ok(Self::Remove),
             v => {
                 error!("Invalid value {} for enum PerformUpdateType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1308,7 +1308,7 @@ impl BinaryEncoder<MonitoringMode> for MonitoringMode {
             2 => Ok(Self::Reporting),
             v => {
                 error!("Invalid value {} for enum MonitoringMode", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1338,7 +1338,7 @@ impl BinaryEncoder<DataChangeTrigger> for DataChangeTrigger {
             2 => Ok(Self::StatusValueTimestamp),
             v => {
                 error!("Invalid value {} for enum DataChangeTrigger", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1368,7 +1368,7 @@ impl BinaryEncoder<DeadbandType> for DeadbandType {
             2 => Ok(Self::Percent),
             v => {
                 error!("Invalid value {} for enum DeadbandType", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1404,7 +1404,7 @@ impl BinaryEncoder<RedundancySupport> for RedundancySupport {
             5 => Ok(Self::HotAndMirrored),
             v => {
                 error!("Invalid value {} for enum RedundancySupport", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1444,7 +1444,7 @@ impl BinaryEncoder<ServerState> for ServerState {
             7 => Ok(Self::Unknown),
             v => {
                 error!("Invalid value {} for enum ServerState", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1478,7 +1478,7 @@ impl BinaryEncoder<ModelChangeStructureVerbMask> for ModelChangeStructureVerbMas
             16 => Ok(Self::DataTypeChanged),
             v => {
                 error!("Invalid value {} for enum ModelChangeStructureVerbMask", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1508,7 +1508,7 @@ impl BinaryEncoder<AxisScaleEnumeration> for AxisScaleEnumeration {
             2 => Ok(Self::Ln),
             v => {
                 error!("Invalid value {} for enum AxisScaleEnumeration", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
@@ -1542,7 +1542,7 @@ impl BinaryEncoder<ExceptionDeviationFormat> for ExceptionDeviationFormat {
             4 => Ok(Self::Unknown),
             v => {
                 error!("Invalid value {} for enum ExceptionDeviationFormat", v);
-                Err(StatusCode::BadUnexpectedError)
+                Err(StatusCode::BadDecodingError)
             }
         }
     }
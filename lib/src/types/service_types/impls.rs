@@ -1,4 +1,4 @@
-use std::{self, fmt};
+use std::{self, collections::HashMap, fmt};
 
 use crate::types::{
     attribute::AttributeId,
@@ -16,9 +16,10 @@ use crate::types::{
     service_types::{
         enums::DeadbandType, AnonymousIdentityToken, ApplicationDescription, ApplicationType,
         Argument, CallMethodRequest, DataChangeFilter, DataChangeTrigger, EndpointDescription,
-        MessageSecurityMode, MonitoredItemCreateRequest, MonitoringMode, MonitoringParameters,
-        ReadValueId, ServerDiagnosticsSummaryDataType, ServiceCounterDataType, ServiceFault,
-        SignatureData, UserNameIdentityToken, UserTokenPolicy, UserTokenType,
+        EnumValueType, MessageSecurityMode, MonitoredItemCreateRequest, MonitoringMode,
+        MonitoringParameters, ReadValueId, ServerDiagnosticsSummaryDataType,
+        ServiceCounterDataType, ServiceFault, SignatureData, UserNameIdentityToken,
+        UserTokenPolicy, UserTokenType,
     },
     status_codes::StatusCode,
     string::UAString,
@@ -49,6 +50,58 @@ impl UserTokenPolicy {
             security_policy_uri: UAString::null(),
         }
     }
+
+    /// Creates a user name policy. The security policy uri is the policy used to encrypt the
+    /// password, or null to use the security policy of the endpoint.
+    pub fn user_name<T, S>(policy_id: T, security_policy_uri: S) -> UserTokenPolicy
+    where
+        T: Into<UAString>,
+        S: Into<UAString>,
+    {
+        UserTokenPolicy {
+            policy_id: policy_id.into(),
+            token_type: UserTokenType::UserName,
+            issued_token_type: UAString::null(),
+            issuer_endpoint_url: UAString::null(),
+            security_policy_uri: security_policy_uri.into(),
+        }
+    }
+
+    /// Creates an X509 certificate policy. The security policy uri is the policy used to sign
+    /// the token, or null to use the security policy of the endpoint.
+    pub fn x509<T, S>(policy_id: T, security_policy_uri: S) -> UserTokenPolicy
+    where
+        T: Into<UAString>,
+        S: Into<UAString>,
+    {
+        UserTokenPolicy {
+            policy_id: policy_id.into(),
+            token_type: UserTokenType::Certificate,
+            issued_token_type: UAString::null(),
+            issuer_endpoint_url: UAString::null(),
+            security_policy_uri: security_policy_uri.into(),
+        }
+    }
+
+    /// Tests if the policy is usable on an endpoint with the supplied security mode. A user name
+    /// or certificate token on an endpoint without security must specify a security policy of its
+    /// own, otherwise the password would be sent in the clear, or the token could not be signed.
+    pub fn is_valid_for_endpoint(&self, security_mode: MessageSecurityMode) -> bool {
+        if self.policy_id.is_empty() {
+            false
+        } else {
+            match self.token_type {
+                UserTokenType::Anonymous => true,
+                UserTokenType::UserName | UserTokenType::Certificate => {
+                    security_mode != MessageSecurityMode::None
+                        || (!self.security_policy_uri.is_empty()
+                            && self.security_policy_uri.as_ref()
+                                != constants::SECURITY_POLICY_NONE_URI)
+                }
+                UserTokenType::IssuedToken => !self.issued_token_type.is_empty(),
+            }
+        }
+    }
 }
 
 impl DataChangeFilter {
@@ -96,6 +149,11 @@ impl DataChangeFilter {
     /// The eu_range is the engineering unit range and represents the range that the value should
     /// typically operate between. It's used for percentage change operations and ignored otherwise.
     ///
+    /// Values are normalized with `Variant::normalize` before they are compared, so a representation
+    /// that differs but means the same is not a change. Arrays are compared element by element
+    /// against the deadband. They are the same if they have the same dimensions and every element
+    /// is the same within the limits.
+    ///
     /// # Errors
     ///
     /// BadDeadbandFilterInvalid indicates the deadband settings were invalid, e.g. an invalid
@@ -106,10 +164,25 @@ impl DataChangeFilter {
         v2: &Variant,
         eu_range: Option<(f64, f64)>,
     ) -> std::result::Result<bool, StatusCode> {
-        // TODO be able to compare arrays of numbers
         if self.deadband_type == DeadbandType::None as u32 {
             // Straight comparison of values
-            Ok(v1 == v2)
+            Ok(v1 == v2 || v1.normalize() == v2.normalize())
+        } else if let (Variant::Array(_), Variant::Array(_)) = (v1, v2) {
+            let (v1, v2) = (v1.normalize(), v2.normalize());
+            let (a1, a2) = match (&v1, &v2) {
+                (Variant::Array(a1), Variant::Array(a2)) => (a1, a2),
+                _ => unreachable!(),
+            };
+            if a1.values.len() != a2.values.len() || a1.dimensions != a2.dimensions {
+                Ok(false)
+            } else {
+                for (e1, e2) in a1.values.iter().zip(a2.values.iter()) {
+                    if !self.compare_value(e1, e2, eu_range)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
         } else {
             // Absolute
             match (v1.as_f64(), v2.as_f64()) {
@@ -493,3 +566,16 @@ impl ServiceCounterDataType {
         self.error_count += 1;
     }
 }
+
+impl EnumValueType {
+    /// Builds a lookup of enumeration value to display name from the supplied enum values, e.g.
+    /// as read from the `EnumValues` property of an enumeration data type. This allows a client
+    /// to render the value of an enum-typed variable by its name. If a value appears more than
+    /// once, the last entry wins.
+    pub fn value_name_map(enum_values: &[EnumValueType]) -> HashMap<i64, String> {
+        enum_values
+            .iter()
+            .map(|v| (v.value, v.display_name.text.as_ref().to_string()))
+            .collect()
+    }
+}
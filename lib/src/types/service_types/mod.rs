@@ -575,3 +575,304 @@ pub use self::xv_type::*;
 pub use self::program_diagnostic_data_type::*;
 pub use self::program_diagnostic_2_data_type::*;
 pub use self::annotation::*;
+
+use crate::types::{
+    encoding::{BinaryEncoder, DecodingOptions, EncodingResult},
+    extension_object::ExtensionObject,
+    node_ids::ObjectId,
+};
+
+/// Decodes the body of an extension object as the structure that its encoding id identifies and
+/// encodes it again. Returns `None` if the encoding id is not for one of the structured types.
+pub fn reencode_extension_object(extension_object: &ExtensionObject, decoding_options: &DecodingOptions) -> Option<EncodingResult<ExtensionObject>> {
+    fn reencode<T: BinaryEncoder<T>>(object_id: ObjectId, extension_object: &ExtensionObject, decoding_options: &DecodingOptions) -> EncodingResult<ExtensionObject> {
+        let inner = extension_object.decode_inner::<T>(decoding_options)?;
+        Ok(ExtensionObject::from_encodable(object_id, &inner))
+    }
+    let object_id = extension_object.object_id().ok()?;
+    let result = match object_id {
+        ObjectId::KeyValuePair_Encoding_DefaultBinary => reencode::<KeyValuePair>(object_id, extension_object, decoding_options),
+        ObjectId::AdditionalParametersType_Encoding_DefaultBinary => reencode::<AdditionalParametersType>(object_id, extension_object, decoding_options),
+        ObjectId::EphemeralKeyType_Encoding_DefaultBinary => reencode::<EphemeralKeyType>(object_id, extension_object, decoding_options),
+        ObjectId::EndpointType_Encoding_DefaultBinary => reencode::<EndpointType>(object_id, extension_object, decoding_options),
+        ObjectId::RationalNumber_Encoding_DefaultBinary => reencode::<RationalNumber>(object_id, extension_object, decoding_options),
+        ObjectId::Vector_Encoding_DefaultBinary => reencode::<Vector>(object_id, extension_object, decoding_options),
+        ObjectId::ThreeDVector_Encoding_DefaultBinary => reencode::<ThreeDVector>(object_id, extension_object, decoding_options),
+        ObjectId::CartesianCoordinates_Encoding_DefaultBinary => reencode::<CartesianCoordinates>(object_id, extension_object, decoding_options),
+        ObjectId::ThreeDCartesianCoordinates_Encoding_DefaultBinary => reencode::<ThreeDCartesianCoordinates>(object_id, extension_object, decoding_options),
+        ObjectId::Orientation_Encoding_DefaultBinary => reencode::<Orientation>(object_id, extension_object, decoding_options),
+        ObjectId::ThreeDOrientation_Encoding_DefaultBinary => reencode::<ThreeDOrientation>(object_id, extension_object, decoding_options),
+        ObjectId::Frame_Encoding_DefaultBinary => reencode::<Frame>(object_id, extension_object, decoding_options),
+        ObjectId::ThreeDFrame_Encoding_DefaultBinary => reencode::<ThreeDFrame>(object_id, extension_object, decoding_options),
+        ObjectId::IdentityMappingRuleType_Encoding_DefaultBinary => reencode::<IdentityMappingRuleType>(object_id, extension_object, decoding_options),
+        ObjectId::CurrencyUnitType_Encoding_DefaultBinary => reencode::<CurrencyUnitType>(object_id, extension_object, decoding_options),
+        ObjectId::TrustListDataType_Encoding_DefaultBinary => reencode::<TrustListDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DecimalDataType_Encoding_DefaultBinary => reencode::<DecimalDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataTypeSchemaHeader_Encoding_DefaultBinary => reencode::<DataTypeSchemaHeader>(object_id, extension_object, decoding_options),
+        ObjectId::DataTypeDescription_Encoding_DefaultBinary => reencode::<DataTypeDescription>(object_id, extension_object, decoding_options),
+        ObjectId::StructureDescription_Encoding_DefaultBinary => reencode::<StructureDescription>(object_id, extension_object, decoding_options),
+        ObjectId::EnumDescription_Encoding_DefaultBinary => reencode::<EnumDescription>(object_id, extension_object, decoding_options),
+        ObjectId::SimpleTypeDescription_Encoding_DefaultBinary => reencode::<SimpleTypeDescription>(object_id, extension_object, decoding_options),
+        ObjectId::UABinaryFileDataType_Encoding_DefaultBinary => reencode::<UABinaryFileDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataSetMetaDataType_Encoding_DefaultBinary => reencode::<DataSetMetaDataType>(object_id, extension_object, decoding_options),
+        ObjectId::FieldMetaData_Encoding_DefaultBinary => reencode::<FieldMetaData>(object_id, extension_object, decoding_options),
+        ObjectId::ConfigurationVersionDataType_Encoding_DefaultBinary => reencode::<ConfigurationVersionDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PublishedDataSetDataType_Encoding_DefaultBinary => reencode::<PublishedDataSetDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PublishedDataSetSourceDataType_Encoding_DefaultBinary => reencode::<PublishedDataSetSourceDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PublishedVariableDataType_Encoding_DefaultBinary => reencode::<PublishedVariableDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PublishedDataItemsDataType_Encoding_DefaultBinary => reencode::<PublishedDataItemsDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PublishedEventsDataType_Encoding_DefaultBinary => reencode::<PublishedEventsDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataSetWriterDataType_Encoding_DefaultBinary => reencode::<DataSetWriterDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataSetWriterTransportDataType_Encoding_DefaultBinary => reencode::<DataSetWriterTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataSetWriterMessageDataType_Encoding_DefaultBinary => reencode::<DataSetWriterMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PubSubGroupDataType_Encoding_DefaultBinary => reencode::<PubSubGroupDataType>(object_id, extension_object, decoding_options),
+        ObjectId::WriterGroupDataType_Encoding_DefaultBinary => reencode::<WriterGroupDataType>(object_id, extension_object, decoding_options),
+        ObjectId::WriterGroupTransportDataType_Encoding_DefaultBinary => reencode::<WriterGroupTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::WriterGroupMessageDataType_Encoding_DefaultBinary => reencode::<WriterGroupMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PubSubConnectionDataType_Encoding_DefaultBinary => reencode::<PubSubConnectionDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ConnectionTransportDataType_Encoding_DefaultBinary => reencode::<ConnectionTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::NetworkAddressDataType_Encoding_DefaultBinary => reencode::<NetworkAddressDataType>(object_id, extension_object, decoding_options),
+        ObjectId::NetworkAddressUrlDataType_Encoding_DefaultBinary => reencode::<NetworkAddressUrlDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ReaderGroupDataType_Encoding_DefaultBinary => reencode::<ReaderGroupDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ReaderGroupTransportDataType_Encoding_DefaultBinary => reencode::<ReaderGroupTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ReaderGroupMessageDataType_Encoding_DefaultBinary => reencode::<ReaderGroupMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataSetReaderDataType_Encoding_DefaultBinary => reencode::<DataSetReaderDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataSetReaderTransportDataType_Encoding_DefaultBinary => reencode::<DataSetReaderTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DataSetReaderMessageDataType_Encoding_DefaultBinary => reencode::<DataSetReaderMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::SubscribedDataSetDataType_Encoding_DefaultBinary => reencode::<SubscribedDataSetDataType>(object_id, extension_object, decoding_options),
+        ObjectId::TargetVariablesDataType_Encoding_DefaultBinary => reencode::<TargetVariablesDataType>(object_id, extension_object, decoding_options),
+        ObjectId::FieldTargetDataType_Encoding_DefaultBinary => reencode::<FieldTargetDataType>(object_id, extension_object, decoding_options),
+        ObjectId::SubscribedDataSetMirrorDataType_Encoding_DefaultBinary => reencode::<SubscribedDataSetMirrorDataType>(object_id, extension_object, decoding_options),
+        ObjectId::PubSubConfigurationDataType_Encoding_DefaultBinary => reencode::<PubSubConfigurationDataType>(object_id, extension_object, decoding_options),
+        ObjectId::UadpWriterGroupMessageDataType_Encoding_DefaultBinary => reencode::<UadpWriterGroupMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::UadpDataSetWriterMessageDataType_Encoding_DefaultBinary => reencode::<UadpDataSetWriterMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::UadpDataSetReaderMessageDataType_Encoding_DefaultBinary => reencode::<UadpDataSetReaderMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::JsonWriterGroupMessageDataType_Encoding_DefaultBinary => reencode::<JsonWriterGroupMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::JsonDataSetWriterMessageDataType_Encoding_DefaultBinary => reencode::<JsonDataSetWriterMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::JsonDataSetReaderMessageDataType_Encoding_DefaultBinary => reencode::<JsonDataSetReaderMessageDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DatagramConnectionTransportDataType_Encoding_DefaultBinary => reencode::<DatagramConnectionTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::DatagramWriterGroupTransportDataType_Encoding_DefaultBinary => reencode::<DatagramWriterGroupTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::BrokerConnectionTransportDataType_Encoding_DefaultBinary => reencode::<BrokerConnectionTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::BrokerWriterGroupTransportDataType_Encoding_DefaultBinary => reencode::<BrokerWriterGroupTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::BrokerDataSetWriterTransportDataType_Encoding_DefaultBinary => reencode::<BrokerDataSetWriterTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::BrokerDataSetReaderTransportDataType_Encoding_DefaultBinary => reencode::<BrokerDataSetReaderTransportDataType>(object_id, extension_object, decoding_options),
+        ObjectId::AliasNameDataType_Encoding_DefaultBinary => reencode::<AliasNameDataType>(object_id, extension_object, decoding_options),
+        ObjectId::RolePermissionType_Encoding_DefaultBinary => reencode::<RolePermissionType>(object_id, extension_object, decoding_options),
+        ObjectId::StructureField_Encoding_DefaultBinary => reencode::<StructureField>(object_id, extension_object, decoding_options),
+        ObjectId::StructureDefinition_Encoding_DefaultBinary => reencode::<StructureDefinition>(object_id, extension_object, decoding_options),
+        ObjectId::EnumDefinition_Encoding_DefaultBinary => reencode::<EnumDefinition>(object_id, extension_object, decoding_options),
+        ObjectId::Argument_Encoding_DefaultBinary => reencode::<Argument>(object_id, extension_object, decoding_options),
+        ObjectId::EnumValueType_Encoding_DefaultBinary => reencode::<EnumValueType>(object_id, extension_object, decoding_options),
+        ObjectId::EnumField_Encoding_DefaultBinary => reencode::<EnumField>(object_id, extension_object, decoding_options),
+        ObjectId::OptionSet_Encoding_DefaultBinary => reencode::<OptionSet>(object_id, extension_object, decoding_options),
+        ObjectId::TimeZoneDataType_Encoding_DefaultBinary => reencode::<TimeZoneDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ApplicationDescription_Encoding_DefaultBinary => reencode::<ApplicationDescription>(object_id, extension_object, decoding_options),
+        ObjectId::ServiceFault_Encoding_DefaultBinary => reencode::<ServiceFault>(object_id, extension_object, decoding_options),
+        ObjectId::SessionlessInvokeRequestType_Encoding_DefaultBinary => reencode::<SessionlessInvokeRequestType>(object_id, extension_object, decoding_options),
+        ObjectId::SessionlessInvokeResponseType_Encoding_DefaultBinary => reencode::<SessionlessInvokeResponseType>(object_id, extension_object, decoding_options),
+        ObjectId::FindServersRequest_Encoding_DefaultBinary => reencode::<FindServersRequest>(object_id, extension_object, decoding_options),
+        ObjectId::FindServersResponse_Encoding_DefaultBinary => reencode::<FindServersResponse>(object_id, extension_object, decoding_options),
+        ObjectId::ServerOnNetwork_Encoding_DefaultBinary => reencode::<ServerOnNetwork>(object_id, extension_object, decoding_options),
+        ObjectId::FindServersOnNetworkRequest_Encoding_DefaultBinary => reencode::<FindServersOnNetworkRequest>(object_id, extension_object, decoding_options),
+        ObjectId::FindServersOnNetworkResponse_Encoding_DefaultBinary => reencode::<FindServersOnNetworkResponse>(object_id, extension_object, decoding_options),
+        ObjectId::UserTokenPolicy_Encoding_DefaultBinary => reencode::<UserTokenPolicy>(object_id, extension_object, decoding_options),
+        ObjectId::EndpointDescription_Encoding_DefaultBinary => reencode::<EndpointDescription>(object_id, extension_object, decoding_options),
+        ObjectId::GetEndpointsRequest_Encoding_DefaultBinary => reencode::<GetEndpointsRequest>(object_id, extension_object, decoding_options),
+        ObjectId::GetEndpointsResponse_Encoding_DefaultBinary => reencode::<GetEndpointsResponse>(object_id, extension_object, decoding_options),
+        ObjectId::RegisteredServer_Encoding_DefaultBinary => reencode::<RegisteredServer>(object_id, extension_object, decoding_options),
+        ObjectId::RegisterServerRequest_Encoding_DefaultBinary => reencode::<RegisterServerRequest>(object_id, extension_object, decoding_options),
+        ObjectId::RegisterServerResponse_Encoding_DefaultBinary => reencode::<RegisterServerResponse>(object_id, extension_object, decoding_options),
+        ObjectId::DiscoveryConfiguration_Encoding_DefaultBinary => reencode::<DiscoveryConfiguration>(object_id, extension_object, decoding_options),
+        ObjectId::MdnsDiscoveryConfiguration_Encoding_DefaultBinary => reencode::<MdnsDiscoveryConfiguration>(object_id, extension_object, decoding_options),
+        ObjectId::RegisterServer2Request_Encoding_DefaultBinary => reencode::<RegisterServer2Request>(object_id, extension_object, decoding_options),
+        ObjectId::RegisterServer2Response_Encoding_DefaultBinary => reencode::<RegisterServer2Response>(object_id, extension_object, decoding_options),
+        ObjectId::ChannelSecurityToken_Encoding_DefaultBinary => reencode::<ChannelSecurityToken>(object_id, extension_object, decoding_options),
+        ObjectId::OpenSecureChannelRequest_Encoding_DefaultBinary => reencode::<OpenSecureChannelRequest>(object_id, extension_object, decoding_options),
+        ObjectId::OpenSecureChannelResponse_Encoding_DefaultBinary => reencode::<OpenSecureChannelResponse>(object_id, extension_object, decoding_options),
+        ObjectId::CloseSecureChannelRequest_Encoding_DefaultBinary => reencode::<CloseSecureChannelRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CloseSecureChannelResponse_Encoding_DefaultBinary => reencode::<CloseSecureChannelResponse>(object_id, extension_object, decoding_options),
+        ObjectId::SignedSoftwareCertificate_Encoding_DefaultBinary => reencode::<SignedSoftwareCertificate>(object_id, extension_object, decoding_options),
+        ObjectId::SignatureData_Encoding_DefaultBinary => reencode::<SignatureData>(object_id, extension_object, decoding_options),
+        ObjectId::CreateSessionRequest_Encoding_DefaultBinary => reencode::<CreateSessionRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CreateSessionResponse_Encoding_DefaultBinary => reencode::<CreateSessionResponse>(object_id, extension_object, decoding_options),
+        ObjectId::UserIdentityToken_Encoding_DefaultBinary => reencode::<UserIdentityToken>(object_id, extension_object, decoding_options),
+        ObjectId::AnonymousIdentityToken_Encoding_DefaultBinary => reencode::<AnonymousIdentityToken>(object_id, extension_object, decoding_options),
+        ObjectId::UserNameIdentityToken_Encoding_DefaultBinary => reencode::<UserNameIdentityToken>(object_id, extension_object, decoding_options),
+        ObjectId::X509IdentityToken_Encoding_DefaultBinary => reencode::<X509IdentityToken>(object_id, extension_object, decoding_options),
+        ObjectId::IssuedIdentityToken_Encoding_DefaultBinary => reencode::<IssuedIdentityToken>(object_id, extension_object, decoding_options),
+        ObjectId::ActivateSessionRequest_Encoding_DefaultBinary => reencode::<ActivateSessionRequest>(object_id, extension_object, decoding_options),
+        ObjectId::ActivateSessionResponse_Encoding_DefaultBinary => reencode::<ActivateSessionResponse>(object_id, extension_object, decoding_options),
+        ObjectId::CloseSessionRequest_Encoding_DefaultBinary => reencode::<CloseSessionRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CloseSessionResponse_Encoding_DefaultBinary => reencode::<CloseSessionResponse>(object_id, extension_object, decoding_options),
+        ObjectId::CancelRequest_Encoding_DefaultBinary => reencode::<CancelRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CancelResponse_Encoding_DefaultBinary => reencode::<CancelResponse>(object_id, extension_object, decoding_options),
+        ObjectId::NodeAttributes_Encoding_DefaultBinary => reencode::<NodeAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::ObjectAttributes_Encoding_DefaultBinary => reencode::<ObjectAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::VariableAttributes_Encoding_DefaultBinary => reencode::<VariableAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::MethodAttributes_Encoding_DefaultBinary => reencode::<MethodAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::ObjectTypeAttributes_Encoding_DefaultBinary => reencode::<ObjectTypeAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::VariableTypeAttributes_Encoding_DefaultBinary => reencode::<VariableTypeAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::ReferenceTypeAttributes_Encoding_DefaultBinary => reencode::<ReferenceTypeAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::DataTypeAttributes_Encoding_DefaultBinary => reencode::<DataTypeAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::ViewAttributes_Encoding_DefaultBinary => reencode::<ViewAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::GenericAttributeValue_Encoding_DefaultBinary => reencode::<GenericAttributeValue>(object_id, extension_object, decoding_options),
+        ObjectId::GenericAttributes_Encoding_DefaultBinary => reencode::<GenericAttributes>(object_id, extension_object, decoding_options),
+        ObjectId::AddNodesItem_Encoding_DefaultBinary => reencode::<AddNodesItem>(object_id, extension_object, decoding_options),
+        ObjectId::AddNodesResult_Encoding_DefaultBinary => reencode::<AddNodesResult>(object_id, extension_object, decoding_options),
+        ObjectId::AddNodesRequest_Encoding_DefaultBinary => reencode::<AddNodesRequest>(object_id, extension_object, decoding_options),
+        ObjectId::AddNodesResponse_Encoding_DefaultBinary => reencode::<AddNodesResponse>(object_id, extension_object, decoding_options),
+        ObjectId::AddReferencesItem_Encoding_DefaultBinary => reencode::<AddReferencesItem>(object_id, extension_object, decoding_options),
+        ObjectId::AddReferencesRequest_Encoding_DefaultBinary => reencode::<AddReferencesRequest>(object_id, extension_object, decoding_options),
+        ObjectId::AddReferencesResponse_Encoding_DefaultBinary => reencode::<AddReferencesResponse>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteNodesItem_Encoding_DefaultBinary => reencode::<DeleteNodesItem>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteNodesRequest_Encoding_DefaultBinary => reencode::<DeleteNodesRequest>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteNodesResponse_Encoding_DefaultBinary => reencode::<DeleteNodesResponse>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteReferencesItem_Encoding_DefaultBinary => reencode::<DeleteReferencesItem>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteReferencesRequest_Encoding_DefaultBinary => reencode::<DeleteReferencesRequest>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteReferencesResponse_Encoding_DefaultBinary => reencode::<DeleteReferencesResponse>(object_id, extension_object, decoding_options),
+        ObjectId::ViewDescription_Encoding_DefaultBinary => reencode::<ViewDescription>(object_id, extension_object, decoding_options),
+        ObjectId::BrowseDescription_Encoding_DefaultBinary => reencode::<BrowseDescription>(object_id, extension_object, decoding_options),
+        ObjectId::ReferenceDescription_Encoding_DefaultBinary => reencode::<ReferenceDescription>(object_id, extension_object, decoding_options),
+        ObjectId::BrowseResult_Encoding_DefaultBinary => reencode::<BrowseResult>(object_id, extension_object, decoding_options),
+        ObjectId::BrowseRequest_Encoding_DefaultBinary => reencode::<BrowseRequest>(object_id, extension_object, decoding_options),
+        ObjectId::BrowseResponse_Encoding_DefaultBinary => reencode::<BrowseResponse>(object_id, extension_object, decoding_options),
+        ObjectId::BrowseNextRequest_Encoding_DefaultBinary => reencode::<BrowseNextRequest>(object_id, extension_object, decoding_options),
+        ObjectId::BrowseNextResponse_Encoding_DefaultBinary => reencode::<BrowseNextResponse>(object_id, extension_object, decoding_options),
+        ObjectId::RelativePathElement_Encoding_DefaultBinary => reencode::<RelativePathElement>(object_id, extension_object, decoding_options),
+        ObjectId::RelativePath_Encoding_DefaultBinary => reencode::<RelativePath>(object_id, extension_object, decoding_options),
+        ObjectId::BrowsePath_Encoding_DefaultBinary => reencode::<BrowsePath>(object_id, extension_object, decoding_options),
+        ObjectId::BrowsePathTarget_Encoding_DefaultBinary => reencode::<BrowsePathTarget>(object_id, extension_object, decoding_options),
+        ObjectId::BrowsePathResult_Encoding_DefaultBinary => reencode::<BrowsePathResult>(object_id, extension_object, decoding_options),
+        ObjectId::TranslateBrowsePathsToNodeIdsRequest_Encoding_DefaultBinary => reencode::<TranslateBrowsePathsToNodeIdsRequest>(object_id, extension_object, decoding_options),
+        ObjectId::TranslateBrowsePathsToNodeIdsResponse_Encoding_DefaultBinary => reencode::<TranslateBrowsePathsToNodeIdsResponse>(object_id, extension_object, decoding_options),
+        ObjectId::RegisterNodesRequest_Encoding_DefaultBinary => reencode::<RegisterNodesRequest>(object_id, extension_object, decoding_options),
+        ObjectId::RegisterNodesResponse_Encoding_DefaultBinary => reencode::<RegisterNodesResponse>(object_id, extension_object, decoding_options),
+        ObjectId::UnregisterNodesRequest_Encoding_DefaultBinary => reencode::<UnregisterNodesRequest>(object_id, extension_object, decoding_options),
+        ObjectId::UnregisterNodesResponse_Encoding_DefaultBinary => reencode::<UnregisterNodesResponse>(object_id, extension_object, decoding_options),
+        ObjectId::EndpointConfiguration_Encoding_DefaultBinary => reencode::<EndpointConfiguration>(object_id, extension_object, decoding_options),
+        ObjectId::QueryDataDescription_Encoding_DefaultBinary => reencode::<QueryDataDescription>(object_id, extension_object, decoding_options),
+        ObjectId::NodeTypeDescription_Encoding_DefaultBinary => reencode::<NodeTypeDescription>(object_id, extension_object, decoding_options),
+        ObjectId::QueryDataSet_Encoding_DefaultBinary => reencode::<QueryDataSet>(object_id, extension_object, decoding_options),
+        ObjectId::NodeReference_Encoding_DefaultBinary => reencode::<NodeReference>(object_id, extension_object, decoding_options),
+        ObjectId::ContentFilterElement_Encoding_DefaultBinary => reencode::<ContentFilterElement>(object_id, extension_object, decoding_options),
+        ObjectId::ContentFilter_Encoding_DefaultBinary => reencode::<ContentFilter>(object_id, extension_object, decoding_options),
+        ObjectId::FilterOperand_Encoding_DefaultBinary => reencode::<FilterOperand>(object_id, extension_object, decoding_options),
+        ObjectId::ElementOperand_Encoding_DefaultBinary => reencode::<ElementOperand>(object_id, extension_object, decoding_options),
+        ObjectId::LiteralOperand_Encoding_DefaultBinary => reencode::<LiteralOperand>(object_id, extension_object, decoding_options),
+        ObjectId::AttributeOperand_Encoding_DefaultBinary => reencode::<AttributeOperand>(object_id, extension_object, decoding_options),
+        ObjectId::SimpleAttributeOperand_Encoding_DefaultBinary => reencode::<SimpleAttributeOperand>(object_id, extension_object, decoding_options),
+        ObjectId::ContentFilterElementResult_Encoding_DefaultBinary => reencode::<ContentFilterElementResult>(object_id, extension_object, decoding_options),
+        ObjectId::ContentFilterResult_Encoding_DefaultBinary => reencode::<ContentFilterResult>(object_id, extension_object, decoding_options),
+        ObjectId::ParsingResult_Encoding_DefaultBinary => reencode::<ParsingResult>(object_id, extension_object, decoding_options),
+        ObjectId::QueryFirstRequest_Encoding_DefaultBinary => reencode::<QueryFirstRequest>(object_id, extension_object, decoding_options),
+        ObjectId::QueryFirstResponse_Encoding_DefaultBinary => reencode::<QueryFirstResponse>(object_id, extension_object, decoding_options),
+        ObjectId::QueryNextRequest_Encoding_DefaultBinary => reencode::<QueryNextRequest>(object_id, extension_object, decoding_options),
+        ObjectId::QueryNextResponse_Encoding_DefaultBinary => reencode::<QueryNextResponse>(object_id, extension_object, decoding_options),
+        ObjectId::ReadValueId_Encoding_DefaultBinary => reencode::<ReadValueId>(object_id, extension_object, decoding_options),
+        ObjectId::ReadRequest_Encoding_DefaultBinary => reencode::<ReadRequest>(object_id, extension_object, decoding_options),
+        ObjectId::ReadResponse_Encoding_DefaultBinary => reencode::<ReadResponse>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryReadValueId_Encoding_DefaultBinary => reencode::<HistoryReadValueId>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryReadResult_Encoding_DefaultBinary => reencode::<HistoryReadResult>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryReadDetails_Encoding_DefaultBinary => reencode::<HistoryReadDetails>(object_id, extension_object, decoding_options),
+        ObjectId::ReadEventDetails_Encoding_DefaultBinary => reencode::<ReadEventDetails>(object_id, extension_object, decoding_options),
+        ObjectId::ReadRawModifiedDetails_Encoding_DefaultBinary => reencode::<ReadRawModifiedDetails>(object_id, extension_object, decoding_options),
+        ObjectId::ReadProcessedDetails_Encoding_DefaultBinary => reencode::<ReadProcessedDetails>(object_id, extension_object, decoding_options),
+        ObjectId::ReadAtTimeDetails_Encoding_DefaultBinary => reencode::<ReadAtTimeDetails>(object_id, extension_object, decoding_options),
+        ObjectId::ReadAnnotationDataDetails_Encoding_DefaultBinary => reencode::<ReadAnnotationDataDetails>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryData_Encoding_DefaultBinary => reencode::<HistoryData>(object_id, extension_object, decoding_options),
+        ObjectId::ModificationInfo_Encoding_DefaultBinary => reencode::<ModificationInfo>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryModifiedData_Encoding_DefaultBinary => reencode::<HistoryModifiedData>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryEvent_Encoding_DefaultBinary => reencode::<HistoryEvent>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryReadRequest_Encoding_DefaultBinary => reencode::<HistoryReadRequest>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryReadResponse_Encoding_DefaultBinary => reencode::<HistoryReadResponse>(object_id, extension_object, decoding_options),
+        ObjectId::WriteValue_Encoding_DefaultBinary => reencode::<WriteValue>(object_id, extension_object, decoding_options),
+        ObjectId::WriteRequest_Encoding_DefaultBinary => reencode::<WriteRequest>(object_id, extension_object, decoding_options),
+        ObjectId::WriteResponse_Encoding_DefaultBinary => reencode::<WriteResponse>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryUpdateDetails_Encoding_DefaultBinary => reencode::<HistoryUpdateDetails>(object_id, extension_object, decoding_options),
+        ObjectId::UpdateDataDetails_Encoding_DefaultBinary => reencode::<UpdateDataDetails>(object_id, extension_object, decoding_options),
+        ObjectId::UpdateStructureDataDetails_Encoding_DefaultBinary => reencode::<UpdateStructureDataDetails>(object_id, extension_object, decoding_options),
+        ObjectId::UpdateEventDetails_Encoding_DefaultBinary => reencode::<UpdateEventDetails>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteRawModifiedDetails_Encoding_DefaultBinary => reencode::<DeleteRawModifiedDetails>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteAtTimeDetails_Encoding_DefaultBinary => reencode::<DeleteAtTimeDetails>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteEventDetails_Encoding_DefaultBinary => reencode::<DeleteEventDetails>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryUpdateResult_Encoding_DefaultBinary => reencode::<HistoryUpdateResult>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryUpdateRequest_Encoding_DefaultBinary => reencode::<HistoryUpdateRequest>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryUpdateResponse_Encoding_DefaultBinary => reencode::<HistoryUpdateResponse>(object_id, extension_object, decoding_options),
+        ObjectId::CallMethodRequest_Encoding_DefaultBinary => reencode::<CallMethodRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CallMethodResult_Encoding_DefaultBinary => reencode::<CallMethodResult>(object_id, extension_object, decoding_options),
+        ObjectId::CallRequest_Encoding_DefaultBinary => reencode::<CallRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CallResponse_Encoding_DefaultBinary => reencode::<CallResponse>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoringFilter_Encoding_DefaultBinary => reencode::<MonitoringFilter>(object_id, extension_object, decoding_options),
+        ObjectId::DataChangeFilter_Encoding_DefaultBinary => reencode::<DataChangeFilter>(object_id, extension_object, decoding_options),
+        ObjectId::EventFilter_Encoding_DefaultBinary => reencode::<EventFilter>(object_id, extension_object, decoding_options),
+        ObjectId::AggregateConfiguration_Encoding_DefaultBinary => reencode::<AggregateConfiguration>(object_id, extension_object, decoding_options),
+        ObjectId::AggregateFilter_Encoding_DefaultBinary => reencode::<AggregateFilter>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoringFilterResult_Encoding_DefaultBinary => reencode::<MonitoringFilterResult>(object_id, extension_object, decoding_options),
+        ObjectId::EventFilterResult_Encoding_DefaultBinary => reencode::<EventFilterResult>(object_id, extension_object, decoding_options),
+        ObjectId::AggregateFilterResult_Encoding_DefaultBinary => reencode::<AggregateFilterResult>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoringParameters_Encoding_DefaultBinary => reencode::<MonitoringParameters>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoredItemCreateRequest_Encoding_DefaultBinary => reencode::<MonitoredItemCreateRequest>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoredItemCreateResult_Encoding_DefaultBinary => reencode::<MonitoredItemCreateResult>(object_id, extension_object, decoding_options),
+        ObjectId::CreateMonitoredItemsRequest_Encoding_DefaultBinary => reencode::<CreateMonitoredItemsRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CreateMonitoredItemsResponse_Encoding_DefaultBinary => reencode::<CreateMonitoredItemsResponse>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoredItemModifyRequest_Encoding_DefaultBinary => reencode::<MonitoredItemModifyRequest>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoredItemModifyResult_Encoding_DefaultBinary => reencode::<MonitoredItemModifyResult>(object_id, extension_object, decoding_options),
+        ObjectId::ModifyMonitoredItemsRequest_Encoding_DefaultBinary => reencode::<ModifyMonitoredItemsRequest>(object_id, extension_object, decoding_options),
+        ObjectId::ModifyMonitoredItemsResponse_Encoding_DefaultBinary => reencode::<ModifyMonitoredItemsResponse>(object_id, extension_object, decoding_options),
+        ObjectId::SetMonitoringModeRequest_Encoding_DefaultBinary => reencode::<SetMonitoringModeRequest>(object_id, extension_object, decoding_options),
+        ObjectId::SetMonitoringModeResponse_Encoding_DefaultBinary => reencode::<SetMonitoringModeResponse>(object_id, extension_object, decoding_options),
+        ObjectId::SetTriggeringRequest_Encoding_DefaultBinary => reencode::<SetTriggeringRequest>(object_id, extension_object, decoding_options),
+        ObjectId::SetTriggeringResponse_Encoding_DefaultBinary => reencode::<SetTriggeringResponse>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteMonitoredItemsRequest_Encoding_DefaultBinary => reencode::<DeleteMonitoredItemsRequest>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteMonitoredItemsResponse_Encoding_DefaultBinary => reencode::<DeleteMonitoredItemsResponse>(object_id, extension_object, decoding_options),
+        ObjectId::CreateSubscriptionRequest_Encoding_DefaultBinary => reencode::<CreateSubscriptionRequest>(object_id, extension_object, decoding_options),
+        ObjectId::CreateSubscriptionResponse_Encoding_DefaultBinary => reencode::<CreateSubscriptionResponse>(object_id, extension_object, decoding_options),
+        ObjectId::ModifySubscriptionRequest_Encoding_DefaultBinary => reencode::<ModifySubscriptionRequest>(object_id, extension_object, decoding_options),
+        ObjectId::ModifySubscriptionResponse_Encoding_DefaultBinary => reencode::<ModifySubscriptionResponse>(object_id, extension_object, decoding_options),
+        ObjectId::SetPublishingModeRequest_Encoding_DefaultBinary => reencode::<SetPublishingModeRequest>(object_id, extension_object, decoding_options),
+        ObjectId::SetPublishingModeResponse_Encoding_DefaultBinary => reencode::<SetPublishingModeResponse>(object_id, extension_object, decoding_options),
+        ObjectId::NotificationMessage_Encoding_DefaultBinary => reencode::<NotificationMessage>(object_id, extension_object, decoding_options),
+        ObjectId::NotificationData_Encoding_DefaultBinary => reencode::<NotificationData>(object_id, extension_object, decoding_options),
+        ObjectId::DataChangeNotification_Encoding_DefaultBinary => reencode::<DataChangeNotification>(object_id, extension_object, decoding_options),
+        ObjectId::MonitoredItemNotification_Encoding_DefaultBinary => reencode::<MonitoredItemNotification>(object_id, extension_object, decoding_options),
+        ObjectId::EventNotificationList_Encoding_DefaultBinary => reencode::<EventNotificationList>(object_id, extension_object, decoding_options),
+        ObjectId::EventFieldList_Encoding_DefaultBinary => reencode::<EventFieldList>(object_id, extension_object, decoding_options),
+        ObjectId::HistoryEventFieldList_Encoding_DefaultBinary => reencode::<HistoryEventFieldList>(object_id, extension_object, decoding_options),
+        ObjectId::StatusChangeNotification_Encoding_DefaultBinary => reencode::<StatusChangeNotification>(object_id, extension_object, decoding_options),
+        ObjectId::SubscriptionAcknowledgement_Encoding_DefaultBinary => reencode::<SubscriptionAcknowledgement>(object_id, extension_object, decoding_options),
+        ObjectId::PublishRequest_Encoding_DefaultBinary => reencode::<PublishRequest>(object_id, extension_object, decoding_options),
+        ObjectId::PublishResponse_Encoding_DefaultBinary => reencode::<PublishResponse>(object_id, extension_object, decoding_options),
+        ObjectId::RepublishRequest_Encoding_DefaultBinary => reencode::<RepublishRequest>(object_id, extension_object, decoding_options),
+        ObjectId::RepublishResponse_Encoding_DefaultBinary => reencode::<RepublishResponse>(object_id, extension_object, decoding_options),
+        ObjectId::TransferResult_Encoding_DefaultBinary => reencode::<TransferResult>(object_id, extension_object, decoding_options),
+        ObjectId::TransferSubscriptionsRequest_Encoding_DefaultBinary => reencode::<TransferSubscriptionsRequest>(object_id, extension_object, decoding_options),
+        ObjectId::TransferSubscriptionsResponse_Encoding_DefaultBinary => reencode::<TransferSubscriptionsResponse>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteSubscriptionsRequest_Encoding_DefaultBinary => reencode::<DeleteSubscriptionsRequest>(object_id, extension_object, decoding_options),
+        ObjectId::DeleteSubscriptionsResponse_Encoding_DefaultBinary => reencode::<DeleteSubscriptionsResponse>(object_id, extension_object, decoding_options),
+        ObjectId::BuildInfo_Encoding_DefaultBinary => reencode::<BuildInfo>(object_id, extension_object, decoding_options),
+        ObjectId::RedundantServerDataType_Encoding_DefaultBinary => reencode::<RedundantServerDataType>(object_id, extension_object, decoding_options),
+        ObjectId::EndpointUrlListDataType_Encoding_DefaultBinary => reencode::<EndpointUrlListDataType>(object_id, extension_object, decoding_options),
+        ObjectId::NetworkGroupDataType_Encoding_DefaultBinary => reencode::<NetworkGroupDataType>(object_id, extension_object, decoding_options),
+        ObjectId::SamplingIntervalDiagnosticsDataType_Encoding_DefaultBinary => reencode::<SamplingIntervalDiagnosticsDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ServerDiagnosticsSummaryDataType_Encoding_DefaultBinary => reencode::<ServerDiagnosticsSummaryDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ServerStatusDataType_Encoding_DefaultBinary => reencode::<ServerStatusDataType>(object_id, extension_object, decoding_options),
+        ObjectId::SessionDiagnosticsDataType_Encoding_DefaultBinary => reencode::<SessionDiagnosticsDataType>(object_id, extension_object, decoding_options),
+        ObjectId::SessionSecurityDiagnosticsDataType_Encoding_DefaultBinary => reencode::<SessionSecurityDiagnosticsDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ServiceCounterDataType_Encoding_DefaultBinary => reencode::<ServiceCounterDataType>(object_id, extension_object, decoding_options),
+        ObjectId::StatusResult_Encoding_DefaultBinary => reencode::<StatusResult>(object_id, extension_object, decoding_options),
+        ObjectId::SubscriptionDiagnosticsDataType_Encoding_DefaultBinary => reencode::<SubscriptionDiagnosticsDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ModelChangeStructureDataType_Encoding_DefaultBinary => reencode::<ModelChangeStructureDataType>(object_id, extension_object, decoding_options),
+        ObjectId::SemanticChangeStructureDataType_Encoding_DefaultBinary => reencode::<SemanticChangeStructureDataType>(object_id, extension_object, decoding_options),
+        ObjectId::Range_Encoding_DefaultBinary => reencode::<Range>(object_id, extension_object, decoding_options),
+        ObjectId::EUInformation_Encoding_DefaultBinary => reencode::<EUInformation>(object_id, extension_object, decoding_options),
+        ObjectId::ComplexNumberType_Encoding_DefaultBinary => reencode::<ComplexNumberType>(object_id, extension_object, decoding_options),
+        ObjectId::DoubleComplexNumberType_Encoding_DefaultBinary => reencode::<DoubleComplexNumberType>(object_id, extension_object, decoding_options),
+        ObjectId::AxisInformation_Encoding_DefaultBinary => reencode::<AxisInformation>(object_id, extension_object, decoding_options),
+        ObjectId::XVType_Encoding_DefaultBinary => reencode::<XVType>(object_id, extension_object, decoding_options),
+        ObjectId::ProgramDiagnosticDataType_Encoding_DefaultBinary => reencode::<ProgramDiagnosticDataType>(object_id, extension_object, decoding_options),
+        ObjectId::ProgramDiagnostic2DataType_Encoding_DefaultBinary => reencode::<ProgramDiagnostic2DataType>(object_id, extension_object, decoding_options),
+        ObjectId::Annotation_Encoding_DefaultBinary => reencode::<Annotation>(object_id, extension_object, decoding_options),
+        _ => return None,
+    };
+    Some(result)
+}
@@ -58,7 +58,7 @@ impl BinaryEncoder<UAString> for UAString {
             size += write_i32(stream, value.len() as i32)?;
             let buf = value.as_bytes();
             size += process_encode_io_result(stream.write(buf))?;
-            assert_eq!(size, self.byte_len());
+            debug_assert_eq!(size, self.byte_len());
             Ok(size)
         }
     }
@@ -126,6 +126,13 @@ impl From<String> for UAString {
     }
 }
 
+/// `None` is the null string, i.e. the one encoded with a length of -1.
+impl From<Option<String>> for UAString {
+    fn from(value: Option<String>) -> Self {
+        UAString { value }
+    }
+}
+
 impl Default for UAString {
     fn default() -> Self {
         UAString::null()
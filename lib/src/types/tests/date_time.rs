@@ -1,6 +1,21 @@
-use std::str::FromStr;
+use std::{io::Cursor, str::FromStr};
 
-use crate::types::*;
+use chrono::Duration;
+
+use crate::types::{
+    tests::{serialize_and_compare, serialize_test_and_return},
+    *,
+};
+
+/// Decodes a date time from its ticks with the supplied client offset
+fn decode_ticks(ticks: i64, client_offset: Duration) -> DateTime {
+    let decoding_options = DecodingOptions {
+        client_offset,
+        ..Default::default()
+    };
+    let mut stream = Cursor::new(ticks.to_le_bytes().to_vec());
+    DateTime::decode(&mut stream, &decoding_options).unwrap()
+}
 
 #[test]
 fn null() {
@@ -57,3 +72,124 @@ fn string() {
     // so this code may have to change to compare an interval delta
     assert_eq!(now, now2);
 }
+
+#[test]
+fn add_duration() {
+    // Adding crosses the second boundary
+    let dt = DateTime::ymd_hms_nano(2020, 12, 31, 23, 59, 59, 900_000_000);
+    let later = dt.add_duration(250.0);
+    assert_eq!(
+        later,
+        DateTime::ymd_hms_nano(2021, 1, 1, 0, 0, 0, 150_000_000)
+    );
+    assert_eq!(later.ticks() - dt.ticks(), 2_500_000);
+    assert!(later > dt);
+
+    // Fractions of a millisecond are kept to the nearest tick
+    let later = dt.add_duration(0.0001);
+    assert_eq!(later.ticks() - dt.ticks(), 1);
+
+    // Negative durations go back in time
+    let earlier = later.add_duration(-0.0001);
+    assert_eq!(earlier, dt);
+    assert!(earlier < later);
+    assert!(earlier <= dt && earlier >= dt);
+
+    // Durations past either end of the range are clamped
+    assert_eq!(dt.add_duration(f64::MAX), DateTime::endtimes());
+    assert_eq!(dt.add_duration(1e15), DateTime::endtimes());
+    assert_eq!(dt.add_duration(f64::MIN), DateTime::epoch());
+    assert_eq!(dt.add_duration(-1e15), DateTime::epoch());
+    assert_eq!(DateTime::endtimes().add_duration(1.0), DateTime::endtimes());
+}
+
+#[test]
+fn duration_since() {
+    let start = DateTime::ymd_hms_nano(2020, 6, 1, 12, 0, 59, 999_000_000);
+    let end = DateTime::ymd_hms_nano(2020, 6, 1, 12, 1, 1, 500_000_000);
+    assert_eq!(end.duration_since(&start), 1501.0);
+    assert_eq!(start.duration_since(&end), -1501.0);
+    assert_eq!(start.duration_since(&start), 0.0);
+
+    // Sub tick differences are in fractions of a millisecond
+    let end = start.add_duration(0.5);
+    assert_eq!(end.duration_since(&start), 0.5);
+
+    assert_eq!(end.cmp(&start), std::cmp::Ordering::Greater);
+    assert_eq!(start.max(end), end);
+}
+
+#[test]
+fn encode_epoch_boundary() {
+    // The epoch and anything before it encode as 0
+    serialize_and_compare(DateTime::epoch(), &0i64.to_le_bytes());
+    serialize_and_compare(DateTime::ymd(1600, 12, 31), &0i64.to_le_bytes());
+    serialize_and_compare(DateTime::ymd(1066, 10, 14), &0i64.to_le_bytes());
+    serialize_and_compare(
+        DateTime::ymd_hms_nano(1601, 1, 1, 0, 0, 0, 100),
+        &1i64.to_le_bytes(),
+    );
+
+    // And 0 or less decodes as the epoch, which is null
+    [0, -1, i64::min_value()].iter().for_each(|ticks| {
+        let date_time = decode_ticks(*ticks, Duration::zero());
+        assert_eq!(date_time, DateTime::epoch());
+        assert!(date_time.is_null());
+    });
+    assert_eq!(decode_ticks(1, Duration::zero()).ticks(), 1);
+}
+
+#[test]
+fn encode_ticks_resolution() {
+    // Ticks are 100ns so a date time round trips at that resolution
+    let date_time = DateTime::ymd_hms_nano(2022, 3, 1, 12, 30, 15, 123_456_700);
+    let date_time = serialize_test_and_return(date_time);
+    assert_eq!(date_time.as_chrono().timestamp_subsec_nanos(), 123_456_700);
+    assert_eq!(
+        decode_ticks(date_time.ticks() + 1, Duration::zero()).ticks() - date_time.ticks(),
+        1
+    );
+
+    // Anything finer is truncated to the tick
+    let truncated = DateTime::ymd_hms_nano(2022, 3, 1, 12, 30, 15, 123_456_789);
+    assert_eq!(truncated, date_time);
+}
+
+#[test]
+fn encode_endtimes() {
+    // The endtimes and anything after encode as the infinity sentinel
+    serialize_and_compare(DateTime::endtimes(), &i64::max_value().to_le_bytes());
+    serialize_and_compare(DateTime::ymd(10000, 1, 1), &i64::max_value().to_le_bytes());
+
+    // Which decodes as the endtimes, as does anything past it
+    [
+        i64::max_value(),
+        i64::max_value() - 1,
+        DateTime::endtimes_ticks(),
+        DateTime::endtimes_ticks() + 1,
+    ]
+    .iter()
+    .for_each(|ticks| {
+        let date_time = decode_ticks(*ticks, Duration::zero());
+        assert_eq!(date_time, DateTime::endtimes());
+        assert_eq!(date_time.checked_ticks(), i64::max_value());
+    });
+    let date_time = decode_ticks(DateTime::endtimes_ticks() - 1, Duration::zero());
+    assert_eq!(date_time.ticks(), DateTime::endtimes_ticks() - 1);
+}
+
+#[test]
+fn decode_client_offset() {
+    // The client offset moves decoded times, but not the sentinels
+    let offset = Duration::seconds(10);
+    let date_time = DateTime::ymd_hms(2022, 3, 1, 12, 30, 15);
+    assert_eq!(
+        decode_ticks(date_time.ticks(), offset),
+        DateTime::ymd_hms(2022, 3, 1, 12, 30, 5)
+    );
+    assert!(decode_ticks(0, -offset).is_null());
+    assert_eq!(
+        decode_ticks(i64::max_value(), -offset),
+        DateTime::endtimes()
+    );
+}
@@ -2,7 +2,11 @@ use parking_lot::Mutex;
 use std::sync::Arc;
 use std::{io::Cursor, str::FromStr};
 
-use crate::types::{encoding::DecodingOptions, string::UAString, tests::*};
+use crate::types::{
+    encoding::{decode_from_slice, encode_to_vec, size_and_encode, DecodingOptions},
+    string::UAString,
+    tests::*,
+};
 
 #[test]
 fn encoding_bool() {
@@ -90,6 +94,74 @@ fn encoding_string() {
     serialize_test(UAString::from("This is a test"));
 }
 
+#[test]
+fn encoding_string_null_vs_empty() {
+    // Null and empty strings are different values and must keep their own length prefix on the
+    // wire, -1 for null and 0 for empty
+    serialize_and_compare(UAString::null(), &[0xff, 0xff, 0xff, 0xff]);
+    serialize_and_compare(UAString::from(""), &[0x00, 0x00, 0x00, 0x00]);
+    serialize_and_compare(
+        UAString::from("abc"),
+        &[0x03, 0x00, 0x00, 0x00, 0x61, 0x62, 0x63],
+    );
+
+    let null = serialize_test_and_return(UAString::null());
+    assert!(null.is_null());
+    assert_eq!(null.len(), -1);
+
+    let empty = serialize_test_and_return(UAString::from(""));
+    assert!(!empty.is_null());
+    assert_eq!(empty.len(), 0);
+    assert_ne!(null, empty);
+
+    let value = serialize_test_and_return(UAString::from("abc"));
+    assert_eq!(value.len(), 3);
+    assert_eq!(value.as_ref(), "abc");
+}
+
+#[test]
+fn byte_string_base64() {
+    // Sample bytes from the OPC UA spec
+    let b = ByteString::from_base64("M/RbKBsRVkePCePcx24oRA==").unwrap();
+    assert_eq!(
+        b.as_ref(),
+        &[
+            0x33, 0xf4, 0x5b, 0x28, 0x1b, 0x11, 0x56, 0x47, 0x8f, 0x09, 0xe3, 0xdc, 0xc7, 0x6e,
+            0x28, 0x44
+        ]
+    );
+    assert_eq!(b.as_base64(), "M/RbKBsRVkePCePcx24oRA==");
+
+    let b = ByteString::from(b"Everything or nothing");
+    assert_eq!(ByteString::from_base64(&b.as_base64()).unwrap(), b);
+
+    // Null is preserved
+    let b = ByteString::null();
+    assert_eq!(b.as_base64(), "");
+    assert!(ByteString::from_base64(&b.as_base64()).unwrap().is_null());
+
+    assert_eq!(
+        ByteString::from_base64("not base64!").unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
+
+#[test]
+fn encoding_variant_64bit() {
+    // 64-bit integers are 8 bytes little endian after the encoding mask
+    serialize_and_compare(
+        Variant::UInt64(u64::MAX),
+        &[0x09, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+    );
+    serialize_and_compare(
+        Variant::Int64(0x0102030405060708),
+        &[0x08, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01],
+    );
+    serialize_test(Variant::UInt64(u64::MAX));
+    serialize_test(Variant::Int64(i64::MIN));
+    serialize_test(Variant::UInt64((1u64 << 53) + 1));
+}
+
 #[test]
 fn encode_string_part_6_5224() {
     // Sample from OPCUA Part 6 - 5.2.2.4
@@ -111,6 +183,132 @@ fn decode_string_malformed_utf8() {
     );
 }
 
+#[test]
+fn encode_to_vec_decode_from_slice() {
+    let decoding_options = DecodingOptions::test();
+    let read_value_id = ReadValueId {
+        node_id: NodeId::new(2, "Temperature"),
+        attribute_id: AttributeId::Value as u32,
+        index_range: UAString::from("1:2"),
+        data_encoding: QualifiedName::null(),
+    };
+    let bytes = encode_to_vec(&read_value_id).unwrap();
+    assert_eq!(bytes.len(), read_value_id.byte_len());
+    assert_eq!(bytes, read_value_id.encode_to_vec());
+    assert_eq!(
+        decode_from_slice::<ReadValueId>(&bytes, &decoding_options).unwrap(),
+        read_value_id
+    );
+
+    // Truncated input at every length is an error, not a panic
+    (0..bytes.len()).for_each(|len| {
+        assert_eq!(
+            decode_from_slice::<ReadValueId>(&bytes[..len], &decoding_options).unwrap_err(),
+            StatusCode::BadDecodingError,
+            "Truncated to {} bytes",
+            len
+        );
+    });
+
+    // Decoding limits still apply
+    let bytes = encode_to_vec(&UAString::from("Hello world")).unwrap();
+    let decoding_options = DecodingOptions {
+        max_string_length: 5,
+        ..Default::default()
+    };
+    assert_eq!(
+        decode_from_slice::<UAString>(&bytes, &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
+
+#[test]
+fn size_and_encode_single_pass() {
+    let v = Variant::from((
+        VariantTypeId::LocalizedText,
+        vec![
+            Variant::from(LocalizedText::new("en", "Hello")),
+            Variant::from(LocalizedText::null()),
+        ],
+    ));
+    let bytes = size_and_encode(&v).unwrap();
+    assert_eq!(bytes.len(), v.byte_len());
+    assert_eq!(bytes, encode_to_vec(&v).unwrap());
+    assert_eq!(
+        decode_from_slice::<Variant>(&bytes, &DecodingOptions::test()).unwrap(),
+        v
+    );
+}
+
+#[test]
+fn decode_length_limits() {
+    let decoding_options = DecodingOptions {
+        max_string_length: 100,
+        max_byte_string_length: 100,
+        max_array_length: 100,
+        ..DecodingOptions::test()
+    };
+
+    // A length prefix that claims to be enormous is rejected before anything is allocated
+    let absurd = [0xff, 0xff, 0xff, 0x7f];
+    assert_eq!(
+        ByteString::decode(&mut Cursor::new(absurd), &DecodingOptions::test()).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        UAString::decode(&mut Cursor::new(absurd), &DecodingOptions::test()).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        read_array::<_, u32>(&mut Cursor::new(absurd), &DecodingOptions::test()).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // A length one over the configured limit is rejected, the limit itself is not
+    let mut bytes = vec![101, 0, 0, 0];
+    bytes.extend(vec![0u8; 101]);
+    assert_eq!(
+        ByteString::decode(&mut Cursor::new(&bytes), &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        UAString::decode(&mut Cursor::new(&bytes), &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    let mut bytes = vec![100, 0, 0, 0];
+    bytes.extend(vec![0u8; 100]);
+    assert_eq!(
+        ByteString::decode(&mut Cursor::new(&bytes), &decoding_options)
+            .unwrap()
+            .as_ref()
+            .len(),
+        100
+    );
+
+    // Within the limit but longer than the data that follows
+    let bytes = [50, 0, 0, 0, 1, 2, 3];
+    assert_eq!(
+        ByteString::decode(&mut Cursor::new(bytes), &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // Arrays of values are limited by element count
+    let mut stream = Cursor::new(Vec::new());
+    write_array(&mut stream, &Some(vec![0u32; 101])).unwrap();
+    let bytes = stream.into_inner();
+    assert_eq!(
+        read_array::<_, u32>(&mut Cursor::new(&bytes), &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        read_array::<_, u32>(&mut Cursor::new(&bytes), &DecodingOptions::test())
+            .unwrap()
+            .unwrap()
+            .len(),
+        101
+    );
+}
+
 #[test]
 fn encoding_datetime() {
     let now = DateTime::now();
@@ -153,10 +351,49 @@ fn encode_guid_5226() {
         0x91, 0x2B, 0x96, 0x72, 0x75, 0xFA, 0xE6, 0x4A, 0x8D, 0x28, 0xB4, 0x04, 0xDC, 0x7D, 0xAF,
         0x63,
     ];
-    let guid = Guid::from_str("912b9672-75fa-e64a-8D28-B404DC7DAF63").unwrap();
+    let guid = Guid::from_str("72962B91-FA75-4AE6-8D28-B404DC7DAF63").unwrap();
     serialize_and_compare(guid, &expected_bytes);
 }
 
+#[test]
+fn guid_new_random() {
+    let g1 = Guid::new_random();
+    let g2 = Guid::new_random();
+    assert_ne!(g1, g2);
+    assert_ne!(g1, Guid::null());
+    // Version 4 and the RFC 4122 variant
+    assert_eq!(g1.as_bytes()[6] >> 4, 4);
+    assert_eq!(g1.as_bytes()[8] >> 6, 0b10);
+    // Text form round trips and the binary form follows the Data1/2/3 little endian layout
+    let text = g1.to_string();
+    assert_eq!(text.len(), 36);
+    assert_eq!(Guid::from_str(&text).unwrap(), g1);
+    let b = g1.as_bytes();
+    let expected_bytes = [
+        b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6], b[8], b[9], b[10], b[11], b[12], b[13],
+        b[14], b[15],
+    ];
+    serialize_and_compare(g1.clone(), &expected_bytes);
+    serialize_test(g1);
+}
+
+#[test]
+fn decode_guid_5226() {
+    // The wire form from OPCUA Part 6 - 5.2.2.6. The first three fields are little endian, the
+    // rest are bytes in order, regardless of the endianness of the host.
+    let wire_bytes = vec![
+        0x91, 0x2B, 0x96, 0x72, 0x75, 0xFA, 0xE6, 0x4A, 0x8D, 0x28, 0xB4, 0x04, 0xDC, 0x7D, 0xAF,
+        0x63,
+    ];
+    let mut stream = Cursor::new(wire_bytes);
+    let guid = Guid::decode(&mut stream, &DecodingOptions::test()).unwrap();
+    assert_eq!(guid.to_string(), "72962b91-fa75-4ae6-8d28-b404dc7daf63");
+    assert_eq!(
+        guid,
+        Guid::from_str("72962B91-FA75-4AE6-8D28-B404DC7DAF63").unwrap()
+    );
+}
+
 #[test]
 fn node_id_2byte_numeric() {
     // Sample from OPCUA Part 6 - 5.2.2.9
@@ -251,6 +488,127 @@ fn extension_object() {
     serialize_test(eo);
 }
 
+#[test]
+fn extension_object_from_encodable() {
+    let decoding_options = DecodingOptions::test();
+    // The body is a plain u32 to keep the expected bytes short, the type id is only a label
+    let eo = ExtensionObject::from_encodable(
+        ObjectId::ReadValueId_Encoding_DefaultBinary,
+        &0x01020304u32,
+    );
+
+    // Numeric node id, binary body encoding mask, body length, body
+    let expected_bytes = [
+        0x01, 0x00, 0x74, 0x02, 0x01, 0x04, 0x00, 0x00, 0x00, 0x04, 0x03, 0x02, 0x01,
+    ];
+    serialize_and_compare(eo.clone(), &expected_bytes);
+    let eo = serialize_test_and_return(eo);
+    assert_eq!(
+        eo.object_id().unwrap(),
+        ObjectId::ReadValueId_Encoding_DefaultBinary
+    );
+    assert_eq!(
+        eo.decode_inner::<u32>(&decoding_options).unwrap(),
+        0x01020304
+    );
+
+    // A null body has just the encoding mask
+    let eo = ExtensionObject {
+        node_id: ObjectId::ReadValueId_Encoding_DefaultBinary.into(),
+        body: ExtensionObjectEncoding::None,
+    };
+    serialize_and_compare(eo.clone(), &[0x01, 0x00, 0x74, 0x02, 0x00]);
+    assert_eq!(
+        eo.decode_inner::<u32>(&decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // An xml body cannot be decoded as binary
+    let eo = ExtensionObject {
+        node_id: ObjectId::ReadValueId_Encoding_DefaultBinary.into(),
+        body: ExtensionObjectEncoding::XmlElement(XmlElement::from("<Duration>1</Duration>")),
+    };
+    let eo = serialize_test_and_return(eo);
+    assert_eq!(
+        eo.decode_inner::<u32>(&decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // An unknown encoding mask is rejected
+    let mut stream = Cursor::new(vec![0x01, 0x00, 0x74, 0x02, 0x03]);
+    assert_eq!(
+        ExtensionObject::decode(&mut stream, &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
+
+#[test]
+fn extension_object_wrong_body_length() {
+    use std::io::Write;
+
+    let decoding_options = DecodingOptions::test();
+    let node_id: NodeId = ObjectId::ReadValueId_Encoding_DefaultBinary.into();
+    let read_value_id = ReadValueId {
+        node_id: NodeId::new(1, "Hello"),
+        attribute_id: AttributeId::Value as u32,
+        index_range: UAString::null(),
+        data_encoding: QualifiedName::null(),
+    };
+    let body = {
+        let mut stream = Cursor::new(Vec::new());
+        read_value_id.encode(&mut stream).unwrap();
+        stream.into_inner()
+    };
+
+    // Encodes the extension object with the declared length, followed by a trailing u32 field
+    let encode = |declared_len: i32, body: &[u8]| {
+        let mut stream = Cursor::new(Vec::new());
+        node_id.encode(&mut stream).unwrap();
+        1u8.encode(&mut stream).unwrap();
+        declared_len.encode(&mut stream).unwrap();
+        stream.write_all(body).unwrap();
+        0xdeadbeefu32.encode(&mut stream).unwrap();
+        stream.into_inner()
+    };
+
+    // Correct length decodes the body and leaves the following field intact
+    let mut stream = Cursor::new(encode(body.len() as i32, &body));
+    let eo = ExtensionObject::decode(&mut stream, &decoding_options).unwrap();
+    assert_eq!(
+        eo.decode_inner::<ReadValueId>(&decoding_options).unwrap(),
+        read_value_id
+    );
+    assert_eq!(
+        u32::decode(&mut stream, &decoding_options).unwrap(),
+        0xdeadbeef
+    );
+
+    // Declared length too short truncates the body, which then fails to decode
+    let mut stream = Cursor::new(encode(body.len() as i32 - 2, &body));
+    let eo = ExtensionObject::decode(&mut stream, &decoding_options).unwrap();
+    assert_eq!(
+        eo.decode_inner::<ReadValueId>(&decoding_options)
+            .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // Declared length too long swallows the following field into the body, which is rejected
+    let mut stream = Cursor::new(encode(body.len() as i32 + 4, &body));
+    let eo = ExtensionObject::decode(&mut stream, &decoding_options).unwrap();
+    assert_eq!(
+        eo.decode_inner::<ReadValueId>(&decoding_options)
+            .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // Declared length beyond the end of the stream
+    let mut stream = Cursor::new(encode(body.len() as i32 + 100, &body));
+    assert_eq!(
+        ExtensionObject::decode(&mut stream, &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
+
 #[test]
 fn localized_text() {
     let t = LocalizedText {
@@ -278,6 +636,41 @@ fn localized_text() {
     serialize_test(t);
 }
 
+#[test]
+fn localized_text_encoding_mask() {
+    // Only the fields that are present are encoded, as flagged by the mask
+    serialize_and_compare(LocalizedText::null(), &[0x00]);
+    serialize_and_compare(
+        LocalizedText {
+            locale: UAString::null(),
+            text: UAString::from("Hi"),
+        },
+        &[0x02, 0x02, 0x00, 0x00, 0x00, b'H', b'i'],
+    );
+    serialize_and_compare(
+        LocalizedText::new("en", "Hi"),
+        &[
+            0x03, 0x02, 0x00, 0x00, 0x00, b'e', b'n', 0x02, 0x00, 0x00, 0x00, b'H', b'i',
+        ],
+    );
+
+    // An empty field is omitted the same as a null field, so it decodes as null
+    let t = LocalizedText::from("Hi");
+    serialize_and_compare(t.clone(), &[0x02, 0x02, 0x00, 0x00, 0x00, b'H', b'i']);
+    let expected = LocalizedText {
+        locale: UAString::null(),
+        text: UAString::from("Hi"),
+    };
+    let t = serialize_test_and_return_expected(t, expected);
+    assert!(t.locale.is_null());
+
+    // A locale on its own
+    let mut stream = Cursor::new(vec![0x01, 0x02, 0x00, 0x00, 0x00, b'e', b'n']);
+    let t = LocalizedText::decode(&mut stream, &DecodingOptions::test()).unwrap();
+    assert_eq!(t.locale.as_ref(), "en");
+    assert!(t.text.is_null());
+}
+
 #[test]
 fn expanded_node_id() {
     let node_id = ExpandedNodeId::new(NodeId::new(200, 2000));
@@ -305,6 +698,13 @@ fn qualified_name() {
         name: UAString::from("this is a qualified name"),
     };
     serialize_test(qname);
+
+    // The namespace index is a UInt16 ahead of the name
+    serialize_and_compare(
+        QualifiedName::new(0x0102, "Hi"),
+        &[0x02, 0x01, 0x02, 0x00, 0x00, 0x00, b'H', b'i'],
+    );
+    serialize_and_compare(QualifiedName::null(), &[0x00, 0x00, 0xff, 0xff, 0xff, 0xff]);
 }
 
 #[test]
@@ -435,6 +835,36 @@ fn variant_single_dimension_array() {
     serialize_test(v);
 }
 
+#[test]
+fn variant_encoded_layout() {
+    // An empty variant is just a zero encoding mask
+    serialize_and_compare(Variant::Empty, &[0x00]);
+
+    // A scalar is the type id followed by the value
+    serialize_and_compare(Variant::Int32(-2), &[0x06, 0xfe, 0xff, 0xff, 0xff]);
+
+    // An array sets the array values bit and prefixes the values with their count
+    let v = Variant::from((
+        VariantTypeId::String,
+        vec![Variant::from("ab"), Variant::from(UAString::null())],
+    ));
+    serialize_and_compare(
+        v.clone(),
+        &[
+            0x8c, 0x02, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, b'a', b'b', 0xff, 0xff, 0xff,
+            0xff,
+        ],
+    );
+    serialize_test(v);
+
+    // A negative length other than -1 is invalid
+    let mut stream = Cursor::new(vec![0x86, 0xfe, 0xff, 0xff, 0xff]);
+    assert_eq!(
+        Variant::decode(&mut stream, &DecodingOptions::test()).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
+
 #[test]
 fn variant_multi_dimension_array() {
     let values = vec![
@@ -450,6 +880,131 @@ fn variant_multi_dimension_array() {
     serialize_test(v);
 }
 
+/// Encodes an Int32 variant array with the dimensions bit set, the values and the dimensions
+fn encode_multi_dimension_array(values: &[i32], dimensions: &[u32]) -> Vec<u8> {
+    // Int32 type id with the array values and array dimensions bits
+    let mut bytes = vec![0x06 | 0x80 | 0x40];
+    bytes.extend_from_slice(&(values.len() as i32).to_le_bytes());
+    values
+        .iter()
+        .for_each(|v| bytes.extend_from_slice(&v.to_le_bytes()));
+    bytes.extend_from_slice(&(dimensions.len() as i32).to_le_bytes());
+    dimensions
+        .iter()
+        .for_each(|d| bytes.extend_from_slice(&d.to_le_bytes()));
+    bytes
+}
+
+fn decode_multi_dimension_array(bytes: Vec<u8>) -> Result<Variant, StatusCode> {
+    let mut stream = Cursor::new(bytes);
+    Variant::decode(&mut stream, &DecodingOptions::test())
+}
+
+#[test]
+fn variant_multi_dimension_array_dimensions() {
+    // Dimensions are a length prefixed UInt32 array after the values
+    let v = Variant::from((
+        VariantTypeId::Int32,
+        vec![Variant::Int32(1), Variant::Int32(2)],
+        vec![1u32, 2u32],
+    ));
+    let bytes = v.encode_to_vec();
+    assert_eq!(bytes, encode_multi_dimension_array(&[1, 2], &[1, 2]));
+
+    // Product of dimensions matches the number of values
+    let v =
+        decode_multi_dimension_array(encode_multi_dimension_array(&[1, 2, 3, 4, 5, 6], &[3, 2]))
+            .unwrap();
+    if let Variant::Array(array) = v {
+        assert_eq!(array.dimensions, vec![3u32, 2u32]);
+        assert_eq!(array.values.len(), 6);
+    } else {
+        panic!("Expected an array, got {:?}", v);
+    }
+}
+
+#[test]
+fn variant_multi_dimension_array_mismatch() {
+    // Too few and too many values for the dimensions
+    assert_eq!(
+        decode_multi_dimension_array(encode_multi_dimension_array(&[1, 2, 3, 4, 5], &[3, 2]))
+            .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        decode_multi_dimension_array(encode_multi_dimension_array(&[1, 2, 3], &[1, 2]))
+            .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    // A dimension of 0 is invalid
+    assert_eq!(
+        decode_multi_dimension_array(encode_multi_dimension_array(&[1, 2], &[2, 0])).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    // The dimensions bit is set but there are no dimensions
+    assert_eq!(
+        decode_multi_dimension_array(encode_multi_dimension_array(&[1, 2], &[])).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    // Dimensions that overflow
+    assert_eq!(
+        decode_multi_dimension_array(encode_multi_dimension_array(
+            &[1, 2],
+            &[u32::MAX, u32::MAX, u32::MAX]
+        ))
+        .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
+
+#[test]
+fn status_code_name_and_severity() {
+    let status_code = StatusCode::BadCertificateTimeInvalid;
+    assert_eq!(status_code.name(), "BadCertificateTimeInvalid");
+    assert_eq!(
+        status_code.description(),
+        "The certificate has expired or is not yet valid."
+    );
+    assert_eq!(
+        StatusCode::from_str("BadCertificateTimeInvalid").unwrap(),
+        status_code
+    );
+    assert_eq!(StatusCode::Good.name(), "Good");
+    assert_eq!(
+        StatusCode::UncertainInitialValue.name(),
+        "UncertainInitialValue"
+    );
+
+    // Bit flags do not change the name but are shown by Display
+    let with_flags = StatusCode::GoodClamped | StatusCode::HISTORICAL_CALCULATED;
+    assert_eq!(with_flags.name(), "GoodClamped");
+    assert_ne!(with_flags.to_string(), "GoodClamped");
+    assert!(with_flags.to_string().starts_with("GoodClamped+"));
+
+    // Severity comes from the top two bits
+    [
+        StatusCode::Good,
+        StatusCode::GoodClamped,
+        StatusCode::GoodClamped | StatusCode::HISTORICAL_CALCULATED,
+        StatusCode::UncertainInitialValue,
+        StatusCode::UncertainNoCommunicationLastUsableValue,
+        StatusCode::BadCertificateTimeInvalid,
+        StatusCode::BadNumericOverflow,
+    ]
+    .iter()
+    .for_each(|status_code| {
+        let severity = status_code.bits() >> 30;
+        assert_eq!(status_code.is_good(), severity == 0b00, "{}", status_code);
+        assert_eq!(
+            status_code.is_uncertain(),
+            severity == 0b01,
+            "{}",
+            status_code
+        );
+        assert_eq!(status_code.is_bad(), severity == 0b10, "{}", status_code);
+    });
+}
+
 #[test]
 fn diagnostic_info() {
     let mut d = DiagnosticInfo {
@@ -497,6 +1052,69 @@ fn diagnostic_info() {
     serialize_test(d.clone());
 }
 
+#[test]
+fn diagnostic_info_encoding_mask() {
+    // An empty diagnostic info is just the mask
+    serialize_and_compare(DiagnosticInfo::null(), &[0x00]);
+
+    let d = DiagnosticInfo {
+        symbolic_id: Some(1),
+        additional_info: Some(UAString::from("Hi")),
+        ..DiagnosticInfo::null()
+    };
+    serialize_and_compare(
+        d.clone(),
+        &[
+            0x11, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, b'H', b'i',
+        ],
+    );
+    serialize_test(d);
+}
+
+#[test]
+fn diagnostic_info_depth() {
+    let decoding_options = DecodingOptions {
+        decoding_depth_gauge: Arc::new(Mutex::new(DepthGauge {
+            max_depth: 3,
+            current_depth: 0,
+        })),
+        ..Default::default()
+    };
+
+    let nest = |d: DiagnosticInfo| DiagnosticInfo {
+        inner_status_code: Some(StatusCode::BadUnexpectedError),
+        inner_diagnostic_info: Some(Box::new(d)),
+        ..DiagnosticInfo::null()
+    };
+    let innermost = DiagnosticInfo {
+        symbolic_id: Some(10),
+        ..DiagnosticInfo::null()
+    };
+
+    // Nested two levels deep decodes within the limit
+    let d = nest(nest(innermost));
+    let mut stream = serialize_as_stream(d.clone());
+    assert_eq!(
+        DiagnosticInfo::decode(&mut stream, &decoding_options).unwrap(),
+        d
+    );
+    serialize_test(d.clone());
+
+    // One more level is too deep
+    let d = nest(d);
+    let mut stream = serialize_as_stream(d);
+    assert_eq!(
+        DiagnosticInfo::decode(&mut stream, &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // The gauge is released after decoding, successful or not
+    assert_eq!(
+        decoding_options.decoding_depth_gauge.lock().current_depth(),
+        0
+    );
+}
+
 #[test]
 fn argument() {
     serialize_test(Argument {
@@ -511,7 +1129,7 @@ fn argument() {
 // test decoding of an null array  null != empty!
 #[test]
 fn null_array() -> EncodingResult<()> {
-    // @FIXME currently creating an null array via Array or Variant is not possible so do it by hand
+    // Encode the null array by hand to test the decoder in isolation
     let vec = Vec::new();
     let mut stream = Cursor::new(vec);
     let mask = EncodingMask::BOOLEAN | EncodingMask::ARRAY_MASK;
@@ -526,12 +1144,116 @@ fn null_array() -> EncodingResult<()> {
         Variant::Array(Box::new(Array {
             value_type: VariantTypeId::Boolean,
             values: Vec::new(),
-            dimensions: Vec::new()
+            dimensions: Vec::new(),
+            null: true,
         }))
     );
+    assert_eq!(arr, Variant::null_array(VariantTypeId::Boolean)?);
+    Ok(())
+}
+
+#[test]
+fn null_and_empty_array() -> EncodingResult<()> {
+    let null_array = Variant::null_array(VariantTypeId::Int32)?;
+    let empty_array = Variant::empty_array(VariantTypeId::Int32)?;
+    assert_ne!(null_array, empty_array);
+    assert!(null_array.is_null_array() && null_array.is_empty_array());
+    assert!(!empty_array.is_null_array() && empty_array.is_empty_array());
+
+    // Array length is -1 for null, 0 for empty
+    let mask = EncodingMask::INT32 | EncodingMask::ARRAY_VALUES_BIT;
+    serialize_and_compare(null_array.clone(), &[mask, 0xff, 0xff, 0xff, 0xff]);
+    serialize_and_compare(empty_array.clone(), &[mask, 0, 0, 0, 0]);
+
+    // Round trip preserves the distinction
+    serialize_test(null_array);
+    serialize_test(empty_array);
+
+    // A scalar type is required
+    assert!(Variant::null_array(VariantTypeId::Array).is_err());
+    assert!(Variant::empty_array(VariantTypeId::Empty).is_err());
+
+    // A null array with values is invalid and cannot be encoded
+    let array = Array {
+        value_type: VariantTypeId::Int32,
+        values: vec![Variant::Int32(1)],
+        dimensions: Vec::new(),
+        null: true,
+    };
+    assert!(!array.is_valid());
+    assert_eq!(
+        encode_to_vec(&Variant::Array(Box::new(array))).unwrap_err(),
+        StatusCode::BadEncodingError
+    );
     Ok(())
 }
 
+#[test]
+fn vec_encoding() {
+    use crate::types::encoding::{read_array, write_array};
+
+    // Empty array has a length of 0
+    serialize_and_compare(Vec::<u16>::new(), &[0, 0, 0, 0]);
+
+    // Populated arrays round trip
+    serialize_and_compare(vec![1u16, 0x0203], &[2, 0, 0, 0, 1, 0, 3, 2]);
+    serialize_test(vec![
+        UAString::from("a"),
+        UAString::null(),
+        UAString::from(""),
+    ]);
+    serialize_test(vec![vec![1i32, 2], vec![], vec![3]]);
+
+    // A null array decodes to an empty vec, but stays null through read_array
+    let null_bytes = [0xff, 0xff, 0xff, 0xff];
+    let decoding_options = DecodingOptions::test();
+    assert!(
+        decode_from_slice::<Vec<u16>>(&null_bytes, &decoding_options)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        read_array::<_, u16>(&mut Cursor::new(&null_bytes[..]), &decoding_options).unwrap(),
+        None
+    );
+    let mut stream = Cursor::new(Vec::new());
+    assert_eq!(write_array::<_, u16>(&mut stream, &None).unwrap(), 4);
+    assert_eq!(stream.into_inner(), null_bytes);
+
+    // Other negative lengths are invalid
+    assert_eq!(
+        decode_from_slice::<Vec<u16>>(&[0xfe, 0xff, 0xff, 0xff], &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // The array length limit applies
+    let decoding_options = DecodingOptions {
+        max_array_length: 2,
+        ..DecodingOptions::test()
+    };
+    let bytes = encode_to_vec(&vec![1u16, 2, 3]).unwrap();
+    assert_eq!(
+        decode_from_slice::<Vec<u16>>(&bytes, &decoding_options).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    let bytes = encode_to_vec(&vec![1u16, 2]).unwrap();
+    assert_eq!(
+        decode_from_slice::<Vec<u16>>(&bytes, &decoding_options).unwrap(),
+        vec![1u16, 2]
+    );
+
+    // Optional strings map None to the null string and byte string
+    assert!(UAString::from(None).is_null());
+    assert_eq!(UAString::from(Some("a".to_string())), UAString::from("a"));
+    assert!(ByteString::from(None).is_null());
+    assert_eq!(
+        ByteString::from(Some(vec![1u8])),
+        ByteString::from(vec![1u8])
+    );
+    serialize_and_compare(UAString::from(None), &null_bytes);
+    serialize_and_compare(ByteString::from(None), &null_bytes);
+}
+
 #[test]
 fn deep_encoding() {
     let decoding_options = DecodingOptions {
@@ -597,3 +1319,456 @@ fn depth_gauge() {
         assert_eq!(dg.current_depth(), 0);
     }
 }
+
+#[test]
+fn enum_value_type() {
+    serialize_test(EnumValueType {
+        value: -5,
+        display_name: LocalizedText::new("en", "Minus five"),
+        description: LocalizedText::new("en", "A negative enum value"),
+    });
+}
+
+#[test]
+fn enum_value_type_name_map() {
+    let enum_values = vec![
+        EnumValueType {
+            value: 0,
+            display_name: LocalizedText::new("en", "Off"),
+            description: LocalizedText::null(),
+        },
+        EnumValueType {
+            value: 1,
+            display_name: LocalizedText::new("en", "On"),
+            description: LocalizedText::null(),
+        },
+        EnumValueType {
+            value: 100,
+            display_name: LocalizedText::new("en", "Fault"),
+            description: LocalizedText::new("en", "The device is faulty"),
+        },
+    ];
+    let map = EnumValueType::value_name_map(&enum_values);
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get(&0).unwrap(), "Off");
+    assert_eq!(map.get(&1).unwrap(), "On");
+    assert_eq!(map.get(&100).unwrap(), "Fault");
+    assert!(map.get(&2).is_none());
+}
+
+fn published_field_meta_data(
+    name: &str,
+    built_in_type: u8,
+    data_type: DataTypeId,
+) -> FieldMetaData {
+    FieldMetaData {
+        name: UAString::from(name),
+        description: LocalizedText::new("en", name),
+        field_flags: DataSetFieldFlags::None,
+        built_in_type,
+        data_type: data_type.into(),
+        value_rank: -1,
+        array_dimensions: None,
+        max_string_length: 0,
+        data_set_field_id: Guid::new(),
+        properties: None,
+    }
+}
+
+#[test]
+fn data_set_meta_data_type() {
+    // Metadata describing two published fields, an Int32 (built-in type 6) and a Double (built-in type 11)
+    let meta_data = DataSetMetaDataType {
+        namespaces: Some(vec![UAString::from("urn:test")]),
+        structure_data_types: None,
+        enum_data_types: None,
+        simple_data_types: None,
+        name: UAString::from("DataSet1"),
+        description: LocalizedText::new("en", "A published data set"),
+        fields: Some(vec![
+            published_field_meta_data("Counter", 6, DataTypeId::Int32),
+            published_field_meta_data("Temperature", 11, DataTypeId::Double),
+        ]),
+        data_set_class_id: Guid::new(),
+        configuration_version: ConfigurationVersionDataType {
+            major_version: 1,
+            minor_version: 2,
+        },
+    };
+    let meta_data = serialize_test_and_return(meta_data);
+    let fields = meta_data.fields.unwrap();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0].built_in_type, 6);
+    assert_eq!(fields[1].data_type, DataTypeId::Double.into());
+}
+
+#[test]
+fn published_data_items_data_type() {
+    let published_variable = |node_id: NodeId| PublishedVariableDataType {
+        published_variable: node_id,
+        attribute_id: AttributeId::Value as u32,
+        sampling_interval_hint: 100f64,
+        deadband_type: 0,
+        deadband_value: 0f64,
+        index_range: UAString::null(),
+        substitute_value: Variant::Empty,
+        meta_data_properties: None,
+    };
+    serialize_test(PublishedDataItemsDataType {
+        published_data: Some(vec![
+            published_variable(NodeId::new(2, "Counter")),
+            published_variable(NodeId::new(2, "Temperature")),
+        ]),
+    });
+    serialize_test(PublishedDataItemsDataType {
+        published_data: None,
+    });
+}
+
+#[test]
+fn user_token_policy() {
+    serialize_test(UserTokenPolicy::anonymous());
+    serialize_test(UserTokenPolicy::user_name(
+        "userpass_rsa_oaep",
+        "http://opcfoundation.org/UA/SecurityPolicy#Basic256Sha256",
+    ));
+    serialize_test(UserTokenPolicy::x509("x509", UAString::null()));
+    serialize_test(UserTokenPolicy {
+        policy_id: UAString::from("issued"),
+        token_type: UserTokenType::IssuedToken,
+        issued_token_type: UAString::from("http://opcfoundation.org/UA/UserToken#JWT"),
+        issuer_endpoint_url: UAString::from("https://issuer"),
+        security_policy_uri: UAString::null(),
+    });
+}
+
+#[test]
+fn user_token_policy_valid_for_endpoint() {
+    let anonymous = UserTokenPolicy::anonymous();
+    assert!(anonymous.is_valid_for_endpoint(MessageSecurityMode::None));
+    assert!(anonymous.is_valid_for_endpoint(MessageSecurityMode::SignAndEncrypt));
+
+    // A user name without its own security policy relies on the endpoint's
+    let user_name = UserTokenPolicy::user_name("userpass", UAString::null());
+    assert!(!user_name.is_valid_for_endpoint(MessageSecurityMode::None));
+    assert!(user_name.is_valid_for_endpoint(MessageSecurityMode::Sign));
+    let user_name = UserTokenPolicy::user_name("userpass", constants::SECURITY_POLICY_NONE_URI);
+    assert!(!user_name.is_valid_for_endpoint(MessageSecurityMode::None));
+
+    // A user name with a security policy is good for any endpoint
+    let user_name = UserTokenPolicy::user_name(
+        "userpass",
+        "http://opcfoundation.org/UA/SecurityPolicy#Basic256Sha256",
+    );
+    assert!(user_name.is_valid_for_endpoint(MessageSecurityMode::None));
+    assert!(user_name.is_valid_for_endpoint(MessageSecurityMode::SignAndEncrypt));
+
+    let x509 = UserTokenPolicy::x509("x509", UAString::null());
+    assert!(!x509.is_valid_for_endpoint(MessageSecurityMode::None));
+    assert!(x509.is_valid_for_endpoint(MessageSecurityMode::SignAndEncrypt));
+
+    // Policy id is mandatory
+    let user_name = UserTokenPolicy::user_name(
+        UAString::null(),
+        "http://opcfoundation.org/UA/SecurityPolicy#Basic256Sha256",
+    );
+    assert!(!user_name.is_valid_for_endpoint(MessageSecurityMode::SignAndEncrypt));
+}
+
+/// Decodes the integer as the enum, expecting it to be rejected as out of range
+fn decode_invalid_enum<T>(value: i32)
+where
+    T: BinaryEncoder<T> + std::fmt::Debug,
+{
+    let mut stream = Cursor::new(value.to_le_bytes().to_vec());
+    let result = T::decode(&mut stream, &DecodingOptions::test());
+    assert_eq!(result.unwrap_err(), StatusCode::BadDecodingError);
+}
+
+#[test]
+fn decode_node_class_out_of_range() {
+    decode_invalid_enum::<NodeClass>(99);
+    // Node classes are a mask, so a value between the valid ones is invalid too
+    decode_invalid_enum::<NodeClass>(3);
+}
+
+#[test]
+fn decode_application_type_out_of_range() {
+    decode_invalid_enum::<ApplicationType>(99);
+    decode_invalid_enum::<ApplicationType>(-1);
+}
+
+#[test]
+fn decode_monitoring_mode_out_of_range() {
+    decode_invalid_enum::<MonitoringMode>(99);
+}
+
+#[test]
+fn decode_timestamps_to_return_out_of_range() {
+    decode_invalid_enum::<TimestampsToReturn>(99);
+    // The highest enumerated value is still accepted
+    let mut stream = Cursor::new(4i32.to_le_bytes().to_vec());
+    let value = TimestampsToReturn::decode(&mut stream, &DecodingOptions::test()).unwrap();
+    assert_eq!(value, TimestampsToReturn::Invalid);
+}
+
+#[test]
+fn decode_browse_direction_out_of_range() {
+    decode_invalid_enum::<BrowseDirection>(99);
+}
+
+#[test]
+fn encoding_content_filter_result() {
+    serialize_test(ContentFilterResult {
+        element_results: None,
+        element_diagnostic_infos: None,
+    });
+
+    let where_clause_result = ContentFilterResult {
+        element_results: Some(vec![
+            ContentFilterElementResult {
+                status_code: StatusCode::BadFilterOperatorInvalid,
+                operand_status_codes: Some(vec![
+                    StatusCode::Good,
+                    StatusCode::BadFilterOperandInvalid,
+                ]),
+                operand_diagnostic_infos: None,
+            },
+            ContentFilterElementResult {
+                status_code: StatusCode::Good,
+                operand_status_codes: Some(vec![StatusCode::Good]),
+                operand_diagnostic_infos: Some(vec![DiagnosticInfo::null()]),
+            },
+        ]),
+        element_diagnostic_infos: Some(vec![DiagnosticInfo::null(), DiagnosticInfo::null()]),
+    };
+    serialize_test(where_clause_result.clone());
+
+    serialize_test(EventFilterResult {
+        select_clause_results: Some(vec![StatusCode::Good, StatusCode::BadNodeIdUnknown]),
+        select_clause_diagnostic_infos: None,
+        where_clause_result,
+    });
+}
+
+#[test]
+fn delete_at_time_details() {
+    let details = DeleteAtTimeDetails {
+        node_id: NodeId::new(2, "Temperature"),
+        req_times: Some(vec![
+            DateTime::ymd_hms(2022, 3, 1, 12, 0, 0),
+            DateTime::ymd_hms(2022, 3, 1, 12, 0, 30),
+        ]),
+    };
+    serialize_test(details.clone());
+
+    // As sent in a HistoryUpdateRequest
+    let decoding_options = DecodingOptions::test();
+    let extension_object = ExtensionObject::from_encodable(
+        ObjectId::DeleteAtTimeDetails_Encoding_DefaultBinary,
+        &details,
+    );
+    let extension_object = serialize_test_and_return(extension_object);
+    assert_eq!(
+        extension_object.object_id().unwrap(),
+        ObjectId::DeleteAtTimeDetails_Encoding_DefaultBinary
+    );
+    assert_eq!(
+        extension_object
+            .decode_inner::<DeleteAtTimeDetails>(&decoding_options)
+            .unwrap(),
+        details
+    );
+}
+
+#[test]
+fn update_structure_data_details() {
+    let annotation = ExtensionObject {
+        node_id: ObjectId::Annotation_Encoding_DefaultBinary.into(),
+        body: ExtensionObjectEncoding::ByteString(ByteString::from(b"annotation")),
+    };
+    let details = UpdateStructureDataDetails {
+        node_id: NodeId::new(2, "Temperature"),
+        perform_insert_replace: PerformUpdateType::Replace,
+        update_values: Some(vec![DataValue {
+            value: Some(Variant::from(annotation)),
+            status: Some(StatusCode::Good),
+            source_timestamp: Some(DateTime::ymd_hms(2022, 3, 1, 12, 0, 0)),
+            source_picoseconds: None,
+            server_timestamp: None,
+            server_picoseconds: None,
+        }]),
+    };
+    serialize_test(details.clone());
+
+    // As sent in a HistoryUpdateRequest
+    let decoding_options = DecodingOptions::test();
+    let extension_object = ExtensionObject::from_encodable(
+        ObjectId::UpdateStructureDataDetails_Encoding_DefaultBinary,
+        &details,
+    );
+    let extension_object = serialize_test_and_return(extension_object);
+    assert_eq!(
+        extension_object.object_id().unwrap(),
+        ObjectId::UpdateStructureDataDetails_Encoding_DefaultBinary
+    );
+    assert_eq!(
+        extension_object
+            .decode_inner::<UpdateStructureDataDetails>(&decoding_options)
+            .unwrap(),
+        details
+    );
+}
+
+#[test]
+fn signature_data_null() {
+    // A null signature, as sent under no security, has both fields encoded as null (-1 length)
+    let signature = SignatureData::null();
+    serialize_and_compare(signature.clone(), &[0xff; 8]);
+    let signature = serialize_test_and_return(signature);
+    assert!(signature.algorithm.is_null());
+    assert!(signature.signature.is_null());
+
+    // Which is distinct from an empty signature
+    let signature = SignatureData {
+        algorithm: UAString::from(""),
+        signature: ByteString::from(b""),
+    };
+    serialize_and_compare(signature.clone(), &[0u8; 8]);
+    let signature = serialize_test_and_return(signature);
+    assert!(!signature.algorithm.is_null());
+    assert!(!signature.signature.is_null());
+}
+
+/// A structure with a mandatory field and two optional fields, encoded as Part 6 5.2.7 describes
+/// for structures with optional fields.
+#[derive(Debug, Clone, PartialEq)]
+struct OptionalFieldsStructure {
+    id: u32,
+    name: Option<UAString>,
+    value: Option<f64>,
+}
+
+impl BinaryEncoder<OptionalFieldsStructure> for OptionalFieldsStructure {
+    fn byte_len(&self) -> usize {
+        4 + self.id.byte_len() + byte_len_optional(&self.name) + byte_len_optional(&self.value)
+    }
+
+    fn encode<S: std::io::Write>(&self, stream: &mut S) -> EncodingResult<usize> {
+        let encoding_mask = optional_fields_mask(&[self.name.is_some(), self.value.is_some()])?;
+        let mut size = write_u32(stream, encoding_mask)?;
+        size += self.id.encode(stream)?;
+        size += write_optional(stream, &self.name)?;
+        size += write_optional(stream, &self.value)?;
+        Ok(size)
+    }
+
+    fn decode<S: std::io::Read>(
+        stream: &mut S,
+        decoding_options: &DecodingOptions,
+    ) -> EncodingResult<Self> {
+        let encoding_mask = read_optional_fields_mask(stream, 2)?;
+        let id = u32::decode(stream, decoding_options)?;
+        let name = read_optional(stream, encoding_mask, 0, decoding_options)?;
+        let value = read_optional(stream, encoding_mask, 1, decoding_options)?;
+        Ok(OptionalFieldsStructure { id, name, value })
+    }
+}
+
+#[test]
+fn structure_with_optional_fields() {
+    // All present
+    let v = OptionalFieldsStructure {
+        id: 1,
+        name: Some(UAString::from("x")),
+        value: Some(1.5),
+    };
+    assert_eq!(v.byte_len(), 4 + 4 + 5 + 8);
+    serialize_test(v);
+
+    // All absent, the mask is 0 and nothing follows the mandatory field
+    let v = OptionalFieldsStructure {
+        id: 1,
+        name: None,
+        value: None,
+    };
+    serialize_and_compare(v.clone(), &[0, 0, 0, 0, 1, 0, 0, 0]);
+    serialize_test(v);
+
+    // Mixed, only the second optional field is present
+    let v = OptionalFieldsStructure {
+        id: 1,
+        name: None,
+        value: Some(1.5),
+    };
+    let stream = serialize_as_stream(v.clone());
+    assert_eq!(&stream.get_ref()[0..4], &[2, 0, 0, 0]);
+    assert_eq!(stream.get_ref().len(), 4 + 4 + 8);
+    serialize_test(v);
+
+    // A present but null string is distinct from an absent one
+    serialize_test(OptionalFieldsStructure {
+        id: 1,
+        name: Some(UAString::null()),
+        value: None,
+    });
+
+    // A mask with a bit set for a field the structure doesn't have is invalid
+    let mut stream = Cursor::new(vec![4u8, 0, 0, 0, 1, 0, 0, 0]);
+    assert_eq!(
+        OptionalFieldsStructure::decode(&mut stream, &DecodingOptions::test()).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // The mask holds at most 32 optional fields
+    assert_eq!(optional_fields_mask(&[true; 32]).unwrap(), u32::MAX);
+    assert_eq!(
+        optional_fields_mask(&[true; 33]).unwrap_err(),
+        StatusCode::BadEncodingError
+    );
+}
+
+#[test]
+fn extension_object_reencode() {
+    let decoding_options = DecodingOptions::test();
+    let vector = ThreeDVector {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    let expected =
+        ExtensionObject::from_encodable(ObjectId::ThreeDVector_Encoding_DefaultBinary, &vector);
+
+    // Any generated structure is decoded and encoded again without the junk after its body
+    let mut body = vector.encode_to_vec();
+    body.extend_from_slice(&[0xffu8; 4]);
+    let padded = ExtensionObject {
+        node_id: ObjectId::ThreeDVector_Encoding_DefaultBinary.into(),
+        body: ExtensionObjectEncoding::ByteString(ByteString::from(body)),
+    };
+    assert_eq!(
+        reencode_extension_object(&expected, &decoding_options)
+            .unwrap()
+            .unwrap(),
+        expected
+    );
+    assert_eq!(
+        reencode_extension_object(&padded, &decoding_options)
+            .unwrap()
+            .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // A body that is too short for the structure fails
+    let short = ExtensionObject {
+        node_id: ObjectId::ThreeDVector_Encoding_DefaultBinary.into(),
+        body: ExtensionObjectEncoding::ByteString(ByteString::from(vec![0x1u8])),
+    };
+    assert!(reencode_extension_object(&short, &decoding_options)
+        .unwrap()
+        .is_err());
+
+    // An encoding id that is not a structure's is not decoded
+    let unknown = ExtensionObject::from_encodable(NodeId::new(2, "NotAnEncoding"), &vector);
+    assert!(reencode_extension_object(&unknown, &decoding_options).is_none());
+}
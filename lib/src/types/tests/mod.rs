@@ -3,6 +3,8 @@ mod encoding;
 mod node_id;
 mod serde;
 mod variant;
+#[cfg(feature = "json-value")]
+mod variant_json;
 
 use std::cmp::PartialEq;
 use std::fmt::Debug;
@@ -40,6 +42,10 @@ where
     let actual = stream.into_inner();
     println!("value = {:?}", value);
     println!("encoded bytes = {:?}", actual);
+
+    // The single pass encoding without the byte length must produce the same bytes
+    assert_eq!(size_and_encode(&value).unwrap(), actual);
+
     Cursor::new(actual)
 }
 
@@ -17,6 +17,13 @@ fn parse_invalid_node_id() {
         "ns=;s=valid str",
         "ns=;g=efa38e40-f232-497a-a534-f205e800d73", // Missing char
         "ns=65537;s=valid str",
+        "2;s=missing ns",
+        "ns2;i=1",
+        "ns=a;i=1",
+        "ns=-1;i=1",
+        "ns=1;x=1",
+        "ns=1;i=4294967296",
+        "ns=1;b=not base64!",
     ]
     .iter()
     .for_each(|s| {
@@ -87,6 +94,31 @@ fn parse_node_id_byte_string() {
     assert_eq!(format!("{}", node_id), "ns=1;b=M/RbKBsRVkePCePcx24oRA==");
 }
 
+#[test]
+fn node_id_display_roundtrip() {
+    // Every kind of identifier parses back from its display form
+    [
+        NodeId::new(0, 2258),
+        NodeId::new(2, 2258),
+        NodeId::new(2, "MyVariable"),
+        NodeId::new(
+            3,
+            Guid::from_str("72962b91-fa75-4ae6-8d28-b404dc7daf63").unwrap(),
+        ),
+        NodeId::new(4, ByteString::from(vec![0u8, 1, 2, 3, 255])),
+    ]
+    .iter()
+    .for_each(|node_id| {
+        let s = node_id.to_string();
+        assert_eq!(NodeId::from_str(&s).unwrap(), *node_id);
+    });
+
+    // An explicit namespace 0 is accepted but not written out
+    let node_id = NodeId::from_str("ns=0;i=2258").unwrap();
+    assert_eq!(node_id, NodeId::new(0, 2258));
+    assert_eq!(node_id.to_string(), "i=2258");
+}
+
 #[test]
 fn expanded_node_id() {
     // Parse invalid expanded node ids
@@ -145,3 +177,97 @@ fn expanded_node_id() {
         node_id
     );
 }
+
+#[test]
+fn node_id_constructors() {
+    // Numeric ids pick the smallest encoding that fits
+    let node_id = NodeId::numeric(0, 255);
+    assert_eq!(node_id.identifier, Identifier::Numeric(255));
+    assert_eq!(node_id.encode_to_vec(), vec![0x0, 0xff]);
+
+    let node_id = NodeId::numeric(5, 1025);
+    assert_eq!(node_id.namespace, 5);
+    assert_eq!(node_id.identifier, Identifier::Numeric(1025));
+    assert_eq!(node_id.encode_to_vec(), vec![0x1, 0x5, 0x01, 0x04]);
+
+    let node_id = NodeId::numeric(256, 70000);
+    assert_eq!(node_id.identifier, Identifier::Numeric(70000));
+    assert_eq!(
+        node_id.encode_to_vec(),
+        vec![0x2, 0x00, 0x01, 0x70, 0x11, 0x01, 0x00]
+    );
+
+    // String
+    let node_id = NodeId::string(2, "Hello");
+    assert!(node_id.is_string());
+    assert_eq!(
+        node_id.identifier,
+        Identifier::String(UAString::from("Hello"))
+    );
+    assert_eq!(node_id, NodeId::string(2, String::from("Hello")));
+    let bytes = node_id.encode_to_vec();
+    assert_eq!(bytes[..3], [0x3, 0x02, 0x00]);
+    assert_eq!(bytes.len(), 3 + 4 + 5);
+
+    // Guid
+    let guid = Guid::new();
+    let node_id = NodeId::guid(3, guid.clone());
+    assert!(node_id.is_guid());
+    assert_eq!(node_id.identifier, Identifier::Guid(guid));
+    let bytes = node_id.encode_to_vec();
+    assert_eq!(bytes[..3], [0x4, 0x03, 0x00]);
+    assert_eq!(bytes.len(), 3 + 16);
+
+    // Opaque
+    let node_id = NodeId::opaque(4, ByteString::from(vec![1u8, 2, 3]));
+    assert!(node_id.is_byte_string());
+    assert_eq!(
+        node_id.identifier,
+        Identifier::ByteString(ByteString::from(vec![1u8, 2, 3]))
+    );
+    assert_eq!(
+        node_id.encode_to_vec(),
+        vec![0x5, 0x04, 0x00, 0x03, 0x00, 0x00, 0x00, 0x1, 0x2, 0x3]
+    );
+
+    // Each constructor round trips through the binary encoding
+    [
+        NodeId::numeric(0, 10),
+        NodeId::numeric(1, 60000),
+        NodeId::numeric(1000, 1),
+        NodeId::string(1, "x"),
+        NodeId::guid(1, Guid::new()),
+        NodeId::opaque(1, ByteString::from(vec![0u8; 4])),
+    ]
+    .iter()
+    .for_each(|node_id| {
+        let decoded = NodeId::decode(
+            &mut std::io::Cursor::new(node_id.encode_to_vec()),
+            &DecodingOptions::test(),
+        )
+        .unwrap();
+        assert_eq!(*node_id, decoded);
+    });
+}
+
+#[test]
+fn server_variable_ids() {
+    // The standard variables of the Server object map to their documented node ids in namespace 0
+    [
+        (VariableId::Server_ServerArray, 2254),
+        (VariableId::Server_NamespaceArray, 2255),
+        (VariableId::Server_ServerStatus, 2256),
+        (VariableId::Server_ServerStatus_CurrentTime, 2258),
+        (VariableId::Server_ServerStatus_State, 2259),
+        (VariableId::Server_ServiceLevel, 2267),
+    ]
+    .iter()
+    .for_each(|(variable_id, id)| {
+        let node_id: NodeId = (*variable_id).into();
+        assert_eq!(node_id, NodeId::new(0, *id as u32));
+        assert_eq!(NodeId::from(variable_id), node_id);
+    });
+
+    let node_id: NodeId = VariableId::Server_ServerStatus_CurrentTime.into();
+    assert_eq!(node_id.to_string(), "i=2258");
+}
@@ -25,6 +25,38 @@ fn serialize_variant() {
     assert_eq!(vs, format!("{{\"DateTime\":{}}}", ticks));
 }
 
+#[test]
+fn serialize_variant_large_integers() {
+    // 64-bit integers are strings so values beyond 2^53 survive a JSON parser
+    [
+        (
+            Variant::from(u64::MAX),
+            r#"{"UInt64":"18446744073709551615"}"#,
+        ),
+        (
+            Variant::from(i64::MIN),
+            r#"{"Int64":"-9223372036854775808"}"#,
+        ),
+        (
+            Variant::from(1u64 << 53 | 1),
+            r#"{"UInt64":"9007199254740993"}"#,
+        ),
+    ]
+    .iter()
+    .for_each(|(v, expected)| {
+        let vs = serde_json::to_string(v).unwrap();
+        assert_eq!(vs, *expected);
+        assert_eq!(serde_json::from_str::<Variant>(&vs).unwrap(), *v);
+    });
+
+    // A plain number is still accepted
+    assert_eq!(
+        serde_json::from_str::<Variant>(r#"{"Int64":-5}"#).unwrap(),
+        Variant::Int64(-5)
+    );
+    assert!(serde_json::from_str::<Variant>(r#"{"UInt64":"-1"}"#).is_err());
+}
+
 #[test]
 fn serialize_deserialize_date_time() {
     let dt1 = DateTime::now();
@@ -5,7 +5,7 @@ use crate::types::{
     numeric_range::NumericRange,
     status_code::StatusCode,
     variant::{Variant, VariantTypeId},
-    ByteString, DataTypeId, DataValue, DateTime, DiagnosticInfo, ExpandedNodeId, Guid,
+    Array, ByteString, DataTypeId, DataValue, DateTime, DiagnosticInfo, ExpandedNodeId, Guid,
     LocalizedText, NodeId, QualifiedName, UAString,
 };
 
@@ -1465,4 +1465,127 @@ fn variant_bytestring_to_bytearray() {
     assert_eq!(v[3], Variant::Byte(0x4));
 }
 
+#[test]
+fn variant_bitwise_eq() {
+    let v1 = Variant::from(vec![1.0f64, f64::NAN, 3.0]);
+    let v2 = Variant::from(vec![1.0f64, f64::NAN, 3.0]);
+    assert!(v1 != v2);
+    assert!(v1.bitwise_eq(&v2));
+
+    let v4 = Variant::from(f32::NAN);
+    assert!(v4 != v4.clone());
+    assert!(v4.bitwise_eq(&v4.clone()));
+    assert!(!v4.bitwise_eq(&Variant::from(1.0f32)));
+
+    // Different values are still different
+    let v3 = Variant::from(vec![1.0f64, f64::NAN, 4.0]);
+    assert!(!v1.bitwise_eq(&v3));
+    assert!(!Variant::from(vec![1.0f64, f64::NAN]).bitwise_eq(&v3));
+
+    // Non floats are compared as normal
+    assert!(Variant::from(1i32).bitwise_eq(&Variant::from(1i32)));
+    assert!(!Variant::from(1i32).bitwise_eq(&Variant::from(1.0f64)));
+
+    let dv1 = DataValue::new_now(f64::NAN);
+    let mut dv2 = dv1.clone();
+    assert!(dv1 != dv2);
+    assert!(dv1.bitwise_eq(&dv2));
+    dv2.status = Some(StatusCode::BadUnexpectedError);
+    assert!(!dv1.bitwise_eq(&dv2));
+}
+
 // TODO arrays
+
+#[test]
+fn variant_normalize() {
+    // An array with explicit dimensions that are just its length
+    let v1 = Variant::from(vec![1i32, 2, 3]);
+    let v2 = Variant::from(
+        Array::new_multi(
+            VariantTypeId::Int32,
+            vec![1i32.into(), 2i32.into(), 3i32.into()],
+            vec![3u32],
+        )
+        .unwrap(),
+    );
+    assert_ne!(v1, v2);
+    assert_eq!(v1.normalize(), v2.normalize());
+    assert_eq!(v1.normalize(), v1);
+
+    // Inside a data value
+    let dv1 = Variant::from(DataValue::value_only(v1.clone()));
+    let dv2 = Variant::from(DataValue::value_only(v2.clone()));
+    assert_ne!(dv1, dv2);
+    assert_eq!(dv1.normalize(), dv2.normalize());
+
+    // True multi dimensional arrays keep their dimensions
+    let v3 = Variant::from(
+        Array::new_multi(
+            VariantTypeId::Int32,
+            vec![1i32.into(), 2i32.into(), 3i32.into(), 4i32.into()],
+            vec![2u32, 2],
+        )
+        .unwrap(),
+    );
+    assert_eq!(v3.normalize(), v3);
+
+    // Differences in meaning survive normalizing
+    assert_ne!(
+        Variant::from(1i32).normalize(),
+        Variant::from(vec![1i32]).normalize()
+    );
+    assert_ne!(
+        Variant::from(UAString::null()).normalize(),
+        Variant::from("").normalize()
+    );
+    assert_ne!(
+        Variant::null_array(VariantTypeId::Int32)
+            .unwrap()
+            .normalize(),
+        Variant::empty_array(VariantTypeId::Int32)
+            .unwrap()
+            .normalize()
+    );
+}
+
+#[test]
+fn data_value_value_as() {
+    // Scalars come out as their own type
+    let v = DataValue::new_now(-55i32);
+    assert_eq!(v.value_as::<i32>().unwrap(), -55);
+
+    let v = DataValue::new_now("Hello World");
+    assert_eq!(v.value_as::<String>().unwrap(), "Hello World");
+    assert_eq!(
+        v.value_as::<UAString>().unwrap(),
+        UAString::from("Hello World")
+    );
+
+    let node_id = NodeId::new(2, "xyz");
+    let v = DataValue::new_now(node_id.clone());
+    assert_eq!(v.value_as::<NodeId>().unwrap(), node_id);
+
+    // No conversion happens between types, even numeric ones
+    let v = DataValue::new_now(-55i32);
+    assert_eq!(
+        v.value_as::<String>().unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+    assert_eq!(
+        v.value_as::<i64>().unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+    assert_eq!(
+        DataValue::new_now("1").value_as::<i32>().unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+
+    // A missing value reports the status of the data value, or no data
+    let mut v = DataValue::null();
+    assert_eq!(v.value_as::<i32>().unwrap_err(), StatusCode::BadNoData);
+    v.status = Some(StatusCode::BadNodeIdUnknown);
+    assert_eq!(
+        v.value_as::<i32>().unwrap_err(),
+        StatusCode::BadNodeIdUnknown
+    );
+}
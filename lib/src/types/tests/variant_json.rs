@@ -0,0 +1,263 @@
+use serde_json::json;
+
+use crate::types::{
+    service_types::Argument,
+    status_code::StatusCode,
+    variant::{Variant, VariantTypeId},
+    Array, ByteString, DecodingOptions, ExtensionObject, ExtensionObjectEncoding, LocalizedText,
+    NodeId, ObjectId, UAString,
+};
+
+#[test]
+fn json_value_int32() {
+    let v = Variant::Int32(-42);
+    assert_eq!(v.to_json_value(), json!(-42));
+    assert_eq!(
+        Variant::from_json_value(&json!(-42), VariantTypeId::Int32).unwrap(),
+        v
+    );
+
+    // The hint decides the type and the range
+    assert_eq!(
+        Variant::from_json_value(&json!(-42), VariantTypeId::Int64).unwrap(),
+        Variant::Int64(-42)
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!(3000000000u64), VariantTypeId::Int32).unwrap_err(),
+        StatusCode::BadOutOfRange
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!("42"), VariantTypeId::Int32).unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+}
+
+#[test]
+fn json_value_int64() {
+    // 64-bit values are quoted so values beyond 2^53 survive
+    let v = Variant::UInt64(u64::MAX);
+    assert_eq!(v.to_json_value(), json!("18446744073709551615"));
+    assert_eq!(
+        Variant::from_json_value(&v.to_json_value(), VariantTypeId::UInt64).unwrap(),
+        v
+    );
+
+    let v = Variant::Int64(i64::MIN);
+    assert_eq!(v.to_json_value(), json!("-9223372036854775808"));
+    assert_eq!(
+        Variant::from_json_value(&v.to_json_value(), VariantTypeId::Int64).unwrap(),
+        v
+    );
+
+    let v = Variant::Int64((1i64 << 53) + 1);
+    assert_eq!(v.to_json_value(), json!("9007199254740993"));
+
+    // Plain numbers are accepted too
+    assert_eq!(
+        Variant::from_json_value(&json!(u64::MAX), VariantTypeId::UInt64).unwrap(),
+        Variant::UInt64(u64::MAX)
+    );
+
+    assert_eq!(
+        Variant::from_json_value(&json!("-1"), VariantTypeId::UInt64).unwrap_err(),
+        StatusCode::BadOutOfRange
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!("1.5"), VariantTypeId::Int64).unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+}
+
+#[test]
+fn json_value_string_array() {
+    let v = Variant::from(vec!["Hello".to_string(), "World".to_string()]);
+    assert_eq!(v.to_json_value(), json!(["Hello", "World"]));
+    assert_eq!(
+        Variant::from_json_value(&json!(["Hello", "World"]), VariantTypeId::String).unwrap(),
+        v
+    );
+
+    // A null string is a JSON null
+    assert_eq!(
+        Variant::String(UAString::null()).to_json_value(),
+        json!(null)
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!(null), VariantTypeId::String).unwrap(),
+        Variant::String(UAString::null())
+    );
+
+    // Values must all be of the hinted type
+    assert_eq!(
+        Variant::from_json_value(&json!(["Hello", 1]), VariantTypeId::String).unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+}
+
+#[test]
+fn json_value_multi_dimension_array() {
+    let values: Vec<Variant> = (1..=6).map(Variant::Int32).collect();
+    let v =
+        Variant::from(Array::new_multi(VariantTypeId::Int32, values, vec![2u32, 3u32]).unwrap());
+    let json = json!([[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(v.to_json_value(), json);
+    assert_eq!(
+        Variant::from_json_value(&json, VariantTypeId::Int32).unwrap(),
+        v
+    );
+
+    // Ragged arrays cannot be made into a multi dimensional array
+    assert_eq!(
+        Variant::from_json_value(&json!([[1, 2, 3], [4, 5]]), VariantTypeId::Int32).unwrap_err(),
+        StatusCode::BadTypeMismatch
+    );
+}
+
+#[test]
+fn json_value_extension_object() {
+    let argument = Argument {
+        name: UAString::from("arg"),
+        data_type: NodeId::from(ObjectId::BaseDataType),
+        value_rank: -1,
+        array_dimensions: None,
+        description: LocalizedText::new("en", "An argument"),
+    };
+    let v = Variant::from(ExtensionObject::from_encodable(
+        ObjectId::Argument_Encoding_DefaultBinary,
+        &argument,
+    ));
+
+    let json = v.to_json_value();
+    assert_eq!(
+        json["typeId"],
+        json!(NodeId::from(ObjectId::Argument_Encoding_DefaultBinary).to_string())
+    );
+    // The body is the structure's fields, not the opaque binary body
+    assert_eq!(
+        json["body"],
+        json!({
+            "name": "arg",
+            "dataType": "i=24",
+            "valueRank": -1,
+            "description": { "locale": "en", "text": "An argument" }
+        })
+    );
+
+    let v2 = Variant::from_json_value(&json, VariantTypeId::ExtensionObject).unwrap();
+    assert_eq!(v2, v);
+    if let Variant::ExtensionObject(extension_object) = v2 {
+        let argument2: Argument = extension_object
+            .decode_inner(&DecodingOptions::test())
+            .unwrap();
+        assert_eq!(argument2, argument);
+    } else {
+        panic!("Expected an extension object");
+    }
+
+    // An array of structures
+    let array = Variant::from(
+        Array::new_single(VariantTypeId::ExtensionObject, vec![v.clone(), v]).unwrap(),
+    );
+    let json = array.to_json_value();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert_eq!(
+        Variant::from_json_value(&json, VariantTypeId::ExtensionObject).unwrap(),
+        array
+    );
+}
+
+#[test]
+fn json_value_extension_object_opaque() {
+    // A structure with no JSON form keeps its binary body as base64
+    let v = Variant::from(ExtensionObject::from_encodable(
+        ObjectId::ReadValueId_Encoding_DefaultBinary,
+        &NodeId::new(1, 100),
+    ));
+    let json = v.to_json_value();
+    assert!(json["body"].is_string());
+    assert_eq!(
+        Variant::from_json_value(&json, VariantTypeId::ExtensionObject).unwrap(),
+        v
+    );
+
+    // But a body of fields for it cannot be decoded
+    let json = json!({
+        "typeId": NodeId::from(ObjectId::ReadValueId_Encoding_DefaultBinary).to_string(),
+        "body": { "nodeId": "ns=1;i=100" }
+    });
+    assert_eq!(
+        Variant::from_json_value(&json, VariantTypeId::ExtensionObject).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // Nor can a structure missing a field
+    let json = json!({
+        "typeId": NodeId::from(ObjectId::Range_Encoding_DefaultBinary).to_string(),
+        "body": { "low": 1.0 }
+    });
+    assert_eq!(
+        Variant::from_json_value(&json, VariantTypeId::ExtensionObject).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+
+    // A body that does not decode as the structure stays opaque
+    let v = Variant::from(ExtensionObject {
+        node_id: NodeId::from(ObjectId::Range_Encoding_DefaultBinary),
+        body: ExtensionObjectEncoding::ByteString(ByteString::from(vec![1u8, 2u8])),
+    });
+    assert!(v.to_json_value()["body"].is_string());
+}
+
+#[test]
+fn json_value_float_range() {
+    assert_eq!(
+        Variant::from_json_value(&json!(1.5), VariantTypeId::Float).unwrap(),
+        Variant::Float(1.5)
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!(f32::MAX as f64), VariantTypeId::Float).unwrap(),
+        Variant::Float(f32::MAX)
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!(1e300), VariantTypeId::Float).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!(-1e300), VariantTypeId::Float).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        Variant::from_json_value(&json!(1e300), VariantTypeId::Double).unwrap(),
+        Variant::Double(1e300)
+    );
+}
+
+#[test]
+fn json_value_status_code() {
+    let v = Variant::StatusCode(StatusCode::BadNodeIdUnknown);
+    assert_eq!(
+        Variant::from_json_value(&v.to_json_value(), VariantTypeId::StatusCode).unwrap(),
+        v
+    );
+
+    // Bit flags are kept
+    let v = Variant::StatusCode(StatusCode::Good | StatusCode::HISTORICAL_CALCULATED);
+    assert_eq!(
+        Variant::from_json_value(&v.to_json_value(), VariantTypeId::StatusCode).unwrap(),
+        v
+    );
+
+    // A status that isn't a known code is rejected
+    assert_eq!(
+        Variant::from_json_value(&json!(0x8fff_0000u32), VariantTypeId::StatusCode).unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+    assert_eq!(
+        Variant::from_json_value(
+            &json!({ "statusCode": 0x8fff_0000u32 }),
+            VariantTypeId::DataValue
+        )
+        .unwrap_err(),
+        StatusCode::BadDecodingError
+    );
+}
@@ -66,6 +66,41 @@ impl EncodingMask {
     pub const ARRAY_MASK: u8 = EncodingMask::ARRAY_DIMENSIONS_BIT | EncodingMask::ARRAY_VALUES_BIT;
 }
 
+/// Serializes a 64-bit integer as a string as OPC UA JSON does, since a JSON number cannot hold
+/// every value exactly. A plain number is accepted when deserializing.
+mod serde_large_integer {
+    use std::{fmt::Display, str::FromStr};
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber<T> {
+        String(String),
+        Number(T),
+    }
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de> + FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        match StringOrNumber::<T>::deserialize(deserializer)? {
+            StringOrNumber::String(s) => s.parse().map_err(de::Error::custom),
+            StringOrNumber::Number(n) => Ok(n),
+        }
+    }
+}
+
 /// A `Variant` holds built-in OPC UA data types, including single and multi dimensional arrays,
 /// data values and extension objects.
 ///
@@ -91,8 +126,10 @@ pub enum Variant {
     /// Unsigned 32-bit int
     UInt32(u32),
     /// Signed 64-bit int
+    #[serde(with = "serde_large_integer")]
     Int64(i64),
     /// Unsigned 64-bit int
+    #[serde(with = "serde_large_integer")]
     UInt64(u64),
     /// Float
     Float(f32),
@@ -172,6 +209,7 @@ impl TryFrom<&NodeId> for VariantTypeId {
             if let Identifier::Numeric(type_id) = value.identifier {
                 match type_id {
                     type_id if type_id == DataTypeId::Boolean as u32 => Ok(VariantTypeId::Boolean),
+                    type_id if type_id == DataTypeId::SByte as u32 => Ok(VariantTypeId::SByte),
                     type_id if type_id == DataTypeId::Byte as u32 => Ok(VariantTypeId::Byte),
                     type_id if type_id == DataTypeId::Int16 as u32 => Ok(VariantTypeId::Int16),
                     type_id if type_id == DataTypeId::UInt16 as u32 => Ok(VariantTypeId::UInt16),
@@ -649,6 +687,60 @@ try_from_variant_to_array_impl!(u64, UInt64);
 try_from_variant_to_array_impl!(f32, Float);
 try_from_variant_to_array_impl!(f64, Double);
 
+/// Extracts a scalar of a known type from a `Variant`, e.g. when processing the results of a read.
+/// Unlike `cast()` there is no conversion, the variant must hold exactly the requested type or
+/// `BadTypeMismatch` is returned.
+pub trait FromVariant: Sized {
+    fn from_variant(v: &Variant) -> Result<Self, StatusCode>;
+}
+
+macro_rules! from_variant_impl {
+    ($rtype: ty, $vtype: ident) => {
+        impl FromVariant for $rtype {
+            fn from_variant(v: &Variant) -> Result<Self, StatusCode> {
+                if let Variant::$vtype(v) = v {
+                    Ok(<$rtype>::clone(v))
+                } else {
+                    Err(StatusCode::BadTypeMismatch)
+                }
+            }
+        }
+    };
+}
+
+from_variant_impl!(bool, Boolean);
+from_variant_impl!(i8, SByte);
+from_variant_impl!(u8, Byte);
+from_variant_impl!(i16, Int16);
+from_variant_impl!(u16, UInt16);
+from_variant_impl!(i32, Int32);
+from_variant_impl!(u32, UInt32);
+from_variant_impl!(i64, Int64);
+from_variant_impl!(u64, UInt64);
+from_variant_impl!(f32, Float);
+from_variant_impl!(f64, Double);
+from_variant_impl!(UAString, String);
+from_variant_impl!(DateTime, DateTime);
+from_variant_impl!(Guid, Guid);
+from_variant_impl!(StatusCode, StatusCode);
+from_variant_impl!(ByteString, ByteString);
+from_variant_impl!(QualifiedName, QualifiedName);
+from_variant_impl!(LocalizedText, LocalizedText);
+from_variant_impl!(NodeId, NodeId);
+from_variant_impl!(ExpandedNodeId, ExpandedNodeId);
+from_variant_impl!(ExtensionObject, ExtensionObject);
+
+impl FromVariant for String {
+    /// A null string is returned as an empty string.
+    fn from_variant(v: &Variant) -> Result<Self, StatusCode> {
+        if let Variant::String(v) = v {
+            Ok(v.as_ref().to_string())
+        } else {
+            Err(StatusCode::BadTypeMismatch)
+        }
+    }
+}
+
 impl BinaryEncoder<Variant> for Variant {
     fn byte_len(&self) -> usize {
         let mut size: usize = 0;
@@ -738,25 +830,34 @@ impl BinaryEncoder<Variant> for Variant {
             Variant::Variant(value) => value.encode(stream)?,
             Variant::Diagnostics(value) => value.encode(stream)?,
             Variant::Array(array) => {
-                let mut size = write_i32(stream, array.values.len() as i32)?;
+                let array_length = if !array.is_null() {
+                    array.values.len() as i32
+                } else if array.is_empty() {
+                    -1
+                } else {
+                    error!("Cannot encode a null array that has values");
+                    return Err(StatusCode::BadEncodingError);
+                };
+                let mut size = write_i32(stream, array_length)?;
                 for value in array.values.iter() {
                     size += Variant::encode_variant_value(stream, value)?;
                 }
                 if array.has_dimensions() {
-                    // Note array dimensions are encoded as Int32 even though they are presented
-                    // as UInt32 through attribute.
+                    // Dimensions are a length prefixed array of UInt32 values. The spec types
+                    // them as Int32 but a valid dimension is never negative so the encoding is
+                    // the same.
 
                     // Encode dimensions length
                     size += write_i32(stream, array.dimensions.len() as i32)?;
                     // Encode dimensions
                     for dimension in &array.dimensions {
-                        size += write_i32(stream, *dimension as i32)?;
+                        size += dimension.encode(stream)?;
                     }
                 }
                 size
             }
         };
-        assert_eq!(size, self.byte_len());
+        debug_assert_eq!(size, self.byte_len());
         Ok(size)
     }
 
@@ -775,9 +876,14 @@ impl BinaryEncoder<Variant> for Variant {
             // null array of type
             if array_length == -1 {
                 let value_type_id = VariantTypeId::from_encoding_mask(element_encoding_mask)?;
-                return Array::new_multi(value_type_id, Vec::new(), Vec::new()).map(Variant::from);
+                return Array::new_null(value_type_id).map(Variant::from);
+            }
+            // empty array of type
+            if array_length == 0 && encoding_mask & EncodingMask::ARRAY_DIMENSIONS_BIT == 0 {
+                let value_type_id = VariantTypeId::from_encoding_mask(element_encoding_mask)?;
+                return Array::new_single(value_type_id, Vec::new()).map(Variant::from);
             }
-            if array_length <= 0 {
+            if array_length < 0 {
                 error!("Invalid array_length {}", array_length);
                 return Err(StatusCode::BadDecodingError);
             }
@@ -804,33 +910,10 @@ impl BinaryEncoder<Variant> for Variant {
             }
             let value_type_id = VariantTypeId::from_encoding_mask(element_encoding_mask)?;
             if encoding_mask & EncodingMask::ARRAY_DIMENSIONS_BIT != 0 {
-                if let Some(dimensions) = read_array(stream, decoding_options)? {
-                    if dimensions.iter().any(|d| *d == 0) {
-                        error!("Invalid array dimensions");
-                        Err(StatusCode::BadDecodingError)
-                    } else {
-                        // This looks clunky but it's to prevent a panic from malicious data
-                        // causing an overflow panic
-                        let mut array_dimensions_length = 1u32;
-                        for d in &dimensions {
-                            if let Some(v) = array_dimensions_length.checked_mul(*d) {
-                                array_dimensions_length = v;
-                            } else {
-                                error!("Array dimension overflow!");
-                                return Err(StatusCode::BadDecodingError);
-                            }
-                        }
-                        if array_dimensions_length != array_length as u32 {
-                            error!(
-                                "Array dimensions does not match array length {}",
-                                array_length
-                            );
-                            Err(StatusCode::BadDecodingError)
-                        } else {
-                            // Note Array::new_multi can fail
-                            Array::new_multi(value_type_id, values, dimensions).map(Variant::from)
-                        }
-                    }
+                if let Some(dimensions) = read_array::<_, u32>(stream, decoding_options)? {
+                    Array::validate_dimensions(&dimensions, array_length)?;
+                    // Note Array::new_multi can fail
+                    Array::new_multi(value_type_id, values, dimensions).map(Variant::from)
                 } else {
                     error!("No array dimensions despite the bit flag being set");
                     Err(StatusCode::BadDecodingError)
@@ -1533,11 +1616,31 @@ impl Variant {
         )
     }
 
+    /// Creates an empty array of the specified type. An empty array is encoded with a length of 0.
+    pub fn empty_array(type_id: VariantTypeId) -> Result<Variant, StatusCode> {
+        Array::new_single(type_id, Vec::new()).map(Variant::from)
+    }
+
+    /// Creates a null array of the specified type. A null array is encoded with a length of -1.
+    pub fn null_array(type_id: VariantTypeId) -> Result<Variant, StatusCode> {
+        Array::new_null(type_id).map(Variant::from)
+    }
+
     /// Test if the variant holds an array
     pub fn is_array(&self) -> bool {
         matches!(self, Variant::Array(_))
     }
 
+    /// Test if the variant holds a null array
+    pub fn is_null_array(&self) -> bool {
+        matches!(self, Variant::Array(array) if array.is_null())
+    }
+
+    /// Test if the variant holds an array with no values, null or otherwise
+    pub fn is_empty_array(&self) -> bool {
+        matches!(self, Variant::Array(array) if array.is_empty())
+    }
+
     pub fn is_array_of_type(&self, variant_type: VariantTypeId) -> bool {
         // A non-numeric value in the array means it is not numeric
         match self {
@@ -1546,6 +1649,68 @@ impl Variant {
         }
     }
 
+    /// Compares two variants for equality like `==` except that floating point values are
+    /// compared by their bit patterns, so a NaN equals the same NaN. This is useful for caching
+    /// and detecting changes, where a value must always be equal to itself. The spec compliant
+    /// `PartialEq` is unaffected.
+    pub fn bitwise_eq(&self, other: &Variant) -> bool {
+        match (self, other) {
+            (Variant::Float(v1), Variant::Float(v2)) => v1.to_bits() == v2.to_bits(),
+            (Variant::Double(v1), Variant::Double(v2)) => v1.to_bits() == v2.to_bits(),
+            (Variant::Variant(v1), Variant::Variant(v2)) => v1.bitwise_eq(v2),
+            (Variant::DataValue(v1), Variant::DataValue(v2)) => v1.bitwise_eq(v2),
+            (Variant::Array(v1), Variant::Array(v2)) => {
+                v1.value_type == v2.value_type
+                    && v1.dimensions == v2.dimensions
+                    && v1.null == v2.null
+                    && v1.values.len() == v2.values.len()
+                    && v1
+                        .values
+                        .iter()
+                        .zip(v2.values.iter())
+                        .all(|(v1, v2)| v1.bitwise_eq(v2))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Returns the value in a canonical representation, so two values that mean the same thing
+    /// but were constructed or decoded differently compare as equal. The transformations are:
+    ///
+    /// * A single dimension array whose explicit dimensions are just its length loses the
+    ///   dimensions, since they say nothing the length doesn't.
+    /// * The elements of an array, an inner variant and the value of a data value are normalized
+    ///   in turn.
+    ///
+    /// Anything that carries meaning is left alone, so a scalar and a single element array, a
+    /// null and an empty string, or a null and an empty array still differ after normalizing.
+    pub fn normalize(&self) -> Variant {
+        match self {
+            Variant::Array(array) => {
+                let dimensions = if array.dimensions.len() == 1
+                    && array.dimensions[0] as usize == array.values.len()
+                {
+                    Vec::new()
+                } else {
+                    array.dimensions.clone()
+                };
+                Variant::Array(Box::new(Array {
+                    value_type: array.value_type,
+                    values: array.values.iter().map(Variant::normalize).collect(),
+                    dimensions,
+                    null: array.null,
+                }))
+            }
+            Variant::Variant(v) => Variant::Variant(Box::new(v.normalize())),
+            Variant::DataValue(v) => {
+                let mut data_value = v.as_ref().clone();
+                data_value.value = v.value.as_ref().map(Variant::normalize);
+                Variant::DataValue(Box::new(data_value))
+            }
+            _ => self.clone(),
+        }
+    }
+
     /// Tests that the variant is in a valid state. In particular for arrays ensuring that the
     /// values are all acceptable and for a multi dimensional array that the dimensions equal
     /// the actual values.
@@ -1732,7 +1897,7 @@ impl Variant {
                     NumericRange::None => Err(StatusCode::BadIndexRangeNoData),
                     NumericRange::Index(idx) => {
                         let idx = idx as usize;
-                        if idx >= values.len() || other_values.is_empty() {
+                        if idx >= values.len() || other_values.len() != 1 {
                             Err(StatusCode::BadIndexRangeNoData)
                         } else {
                             values[idx] = other_values[0].clone();
@@ -1741,17 +1906,12 @@ impl Variant {
                     }
                     NumericRange::Range(min, max) => {
                         let (min, max) = (min as usize, max as usize);
-                        if min >= values.len() {
+                        if min > max || max >= values.len() || other_values.len() != max - min + 1 {
+                            // The range must lie inside the array and the written values must
+                            // exactly fill it.
                             Err(StatusCode::BadIndexRangeNoData)
                         } else {
-                            // Possibly this could splice or something but it's trying to copy elements
-                            // until either the source or destination array is finished.
-                            let mut idx = min;
-                            while idx < values.len() && idx <= max && idx - min < other_values.len()
-                            {
-                                values[idx] = other_values[idx - min].clone();
-                                idx += 1;
-                            }
+                            values[min..=max].clone_from_slice(other_values);
                             Ok(())
                         }
                     }
@@ -0,0 +1,642 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+//! Converts a [`Variant`] to and from a plain `serde_json::Value`, for REST gateways and
+//! the like that want a natural JSON view of a value rather than the reversible OPC UA JSON
+//! encoding.
+//!
+//! Scalars map to the obvious JSON type, except that 64-bit integers are written as strings as
+//! OPC UA JSON does, since a JSON number cannot hold them exactly. Arrays map to JSON arrays,
+//! with multi dimensional arrays nested per dimension. Types with no JSON equivalent map to a
+//! string, e.g. a `NodeId` is written as `"ns=1;i=100"`, a `ByteString` as base64 and a
+//! `DateTime` as RFC 3339, or to an object, e.g. a `LocalizedText` is
+//! `{ "locale": "en", "text": "Hello" }`.
+//!
+//! An `ExtensionObject` is `{ "typeId": "i=298", "body": ... }`. The body of the standard
+//! structures that variables commonly hold, i.e. `Argument`, `Range`, `EUInformation` and
+//! `EnumValueType`, is a nested object of the structure's fields. Any other body is base64.
+//!
+//! JSON doesn't say which OPC UA type a value is, so converting from JSON takes a type hint.
+
+use std::{convert::TryFrom, str::FromStr};
+
+use serde_json::{Map, Number, Value};
+
+use crate::types::{
+    array::Array,
+    byte_string::ByteString,
+    data_value::DataValue,
+    date_time::DateTime,
+    encoding::{BinaryEncoder, DecodingOptions},
+    extension_object::{ExtensionObject, ExtensionObjectEncoding},
+    guid::Guid,
+    localized_text::LocalizedText,
+    node_id::{ExpandedNodeId, NodeId},
+    node_ids::ObjectId,
+    qualified_name::QualifiedName,
+    service_types::{Argument, EUInformation, EnumValueType, Range},
+    status_code::StatusCode,
+    string::UAString,
+    variant::{Variant, VariantTypeId},
+};
+
+impl Variant {
+    /// Returns a plain JSON view of the variant.
+    pub fn to_json_value(&self) -> Value {
+        match self {
+            Variant::Empty => Value::Null,
+            Variant::Boolean(v) => Value::Bool(*v),
+            Variant::SByte(v) => Value::from(*v),
+            Variant::Byte(v) => Value::from(*v),
+            Variant::Int16(v) => Value::from(*v),
+            Variant::UInt16(v) => Value::from(*v),
+            Variant::Int32(v) => Value::from(*v),
+            Variant::UInt32(v) => Value::from(*v),
+            // 64-bit integers are strings because JSON numbers lose precision beyond 2^53
+            Variant::Int64(v) => Value::String(v.to_string()),
+            Variant::UInt64(v) => Value::String(v.to_string()),
+            // NaN and infinity have no JSON representation
+            Variant::Float(v) => Number::from_f64(*v as f64).map_or(Value::Null, Value::Number),
+            Variant::Double(v) => Number::from_f64(*v).map_or(Value::Null, Value::Number),
+            Variant::String(v) | Variant::XmlElement(v) => string_to_json(v),
+            Variant::DateTime(v) => Value::String(v.to_string()),
+            Variant::Guid(v) => Value::String(v.to_string()),
+            Variant::StatusCode(v) => Value::from(v.bits()),
+            Variant::ByteString(v) => {
+                if v.is_null() {
+                    Value::Null
+                } else {
+                    Value::String(v.as_base64())
+                }
+            }
+            Variant::QualifiedName(v) => {
+                let mut object = Map::new();
+                object.insert("namespaceIndex".into(), Value::from(v.namespace_index));
+                object.insert("name".into(), string_to_json(&v.name));
+                Value::Object(object)
+            }
+            Variant::LocalizedText(v) => localized_text_to_json(v),
+            Variant::NodeId(v) => Value::String(v.to_string()),
+            Variant::ExpandedNodeId(v) => Value::String(v.to_string()),
+            Variant::ExtensionObject(v) => {
+                let mut object = Map::new();
+                object.insert("typeId".into(), Value::String(v.node_id.to_string()));
+                match v.body {
+                    ExtensionObjectEncoding::None => {}
+                    ExtensionObjectEncoding::ByteString(ref body) => {
+                        let body =
+                            structure_to_json(v).unwrap_or_else(|| Value::String(body.as_base64()));
+                        object.insert("body".into(), body);
+                    }
+                    ExtensionObjectEncoding::XmlElement(ref body) => {
+                        object.insert("xml".into(), string_to_json(body));
+                    }
+                }
+                Value::Object(object)
+            }
+            Variant::Variant(v) => v.to_json_value(),
+            Variant::DataValue(v) => {
+                let mut object = Map::new();
+                if let Some(ref value) = v.value {
+                    object.insert("value".into(), value.to_json_value());
+                }
+                if let Some(ref status) = v.status {
+                    object.insert("statusCode".into(), Value::from(status.bits()));
+                }
+                if let Some(ref source_timestamp) = v.source_timestamp {
+                    object.insert(
+                        "sourceTimestamp".into(),
+                        Value::String(source_timestamp.to_string()),
+                    );
+                }
+                if let Some(ref server_timestamp) = v.server_timestamp {
+                    object.insert(
+                        "serverTimestamp".into(),
+                        Value::String(server_timestamp.to_string()),
+                    );
+                }
+                Value::Object(object)
+            }
+            Variant::Diagnostics(v) => serde_json::to_value(v).unwrap_or(Value::Null),
+            Variant::Array(array) => {
+                let values: Vec<Value> = array.values.iter().map(|v| v.to_json_value()).collect();
+                if array.dimensions.len() > 1 {
+                    nest_array(values, &array.dimensions)
+                } else {
+                    Value::Array(values)
+                }
+            }
+        }
+    }
+
+    /// Makes a variant of the hinted type from a plain JSON value, the inverse of
+    /// `to_json_value()`. A JSON array makes an array of the hinted type, where nested JSON arrays
+    /// of equal length make a multi dimensional array. A JSON null makes a null string, byte
+    /// string or xml element for those hints and `Variant::Empty` otherwise.
+    ///
+    /// Fails with `BadTypeMismatch` if the JSON value is the wrong kind for the hint,
+    /// `BadOutOfRange` if an integer does not fit in the hinted type, or `BadDecodingError` if a
+    /// float is beyond the range of a `Float`, a status code is not a known status or a structure
+    /// body is missing a field or is for a type that has no JSON form.
+    pub fn from_json_value(v: &Value, type_hint: VariantTypeId) -> Result<Variant, StatusCode> {
+        match v {
+            Value::Null => Ok(match type_hint {
+                VariantTypeId::String => Variant::String(UAString::null()),
+                VariantTypeId::XmlElement => Variant::XmlElement(UAString::null()),
+                VariantTypeId::ByteString => Variant::ByteString(ByteString::null()),
+                _ => Variant::Empty,
+            }),
+            Value::Array(_) => {
+                let value_type = if type_hint == VariantTypeId::Array {
+                    infer_type(v)
+                } else {
+                    type_hint
+                };
+                let mut dimensions = Vec::new();
+                let mut values = Vec::new();
+                flatten_array(v, 0, &mut dimensions, &mut values, value_type)?;
+                let array = if dimensions.len() > 1 {
+                    Array::new_multi(value_type, values, dimensions)
+                } else {
+                    Array::new_single(value_type, values)
+                };
+                array
+                    .map(Variant::from)
+                    .map_err(|_| StatusCode::BadTypeMismatch)
+            }
+            v => scalar_from_json(v, type_hint),
+        }
+    }
+}
+
+fn string_to_json(v: &UAString) -> Value {
+    if v.is_null() {
+        Value::Null
+    } else {
+        Value::String(v.as_ref().to_string())
+    }
+}
+
+fn localized_text_to_json(v: &LocalizedText) -> Value {
+    let mut object = Map::new();
+    object.insert("locale".into(), string_to_json(&v.locale));
+    object.insert("text".into(), string_to_json(&v.text));
+    Value::Object(object)
+}
+
+/// A structure whose extension object body has a JSON form as an object of its fields
+trait JsonStructure: BinaryEncoder<Self> + Sized {
+    fn to_json_fields(&self) -> Map<String, Value>;
+
+    fn from_json_fields(object: &Map<String, Value>) -> Result<Self, StatusCode>;
+}
+
+impl JsonStructure for Argument {
+    fn to_json_fields(&self) -> Map<String, Value> {
+        let mut object = Map::new();
+        object.insert("name".into(), string_to_json(&self.name));
+        object.insert("dataType".into(), Value::String(self.data_type.to_string()));
+        object.insert("valueRank".into(), Value::from(self.value_rank));
+        if let Some(ref array_dimensions) = self.array_dimensions {
+            object.insert(
+                "arrayDimensions".into(),
+                Value::from(array_dimensions.clone()),
+            );
+        }
+        object.insert(
+            "description".into(),
+            localized_text_to_json(&self.description),
+        );
+        object
+    }
+
+    fn from_json_fields(object: &Map<String, Value>) -> Result<Self, StatusCode> {
+        let array_dimensions = match object.get("arrayDimensions") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(dimensions)) => {
+                Some(dimensions.iter().map(integer).collect::<Result<_, _>>()?)
+            }
+            Some(_) => return Err(StatusCode::BadTypeMismatch),
+        };
+        Ok(Argument {
+            name: optional_string(object, "name")?,
+            data_type: parse(field(object, "dataType")?)?,
+            value_rank: integer(field(object, "valueRank")?)?,
+            array_dimensions,
+            description: optional_localized_text(object, "description")?,
+        })
+    }
+}
+
+impl JsonStructure for Range {
+    fn to_json_fields(&self) -> Map<String, Value> {
+        let mut object = Map::new();
+        object.insert("low".into(), Variant::Double(self.low).to_json_value());
+        object.insert("high".into(), Variant::Double(self.high).to_json_value());
+        object
+    }
+
+    fn from_json_fields(object: &Map<String, Value>) -> Result<Self, StatusCode> {
+        Ok(Range {
+            low: double(field(object, "low")?)?,
+            high: double(field(object, "high")?)?,
+        })
+    }
+}
+
+impl JsonStructure for EUInformation {
+    fn to_json_fields(&self) -> Map<String, Value> {
+        let mut object = Map::new();
+        object.insert("namespaceUri".into(), string_to_json(&self.namespace_uri));
+        object.insert("unitId".into(), Value::from(self.unit_id));
+        object.insert(
+            "displayName".into(),
+            localized_text_to_json(&self.display_name),
+        );
+        object.insert(
+            "description".into(),
+            localized_text_to_json(&self.description),
+        );
+        object
+    }
+
+    fn from_json_fields(object: &Map<String, Value>) -> Result<Self, StatusCode> {
+        Ok(EUInformation {
+            namespace_uri: optional_string(object, "namespaceUri")?,
+            unit_id: integer(field(object, "unitId")?)?,
+            display_name: optional_localized_text(object, "displayName")?,
+            description: optional_localized_text(object, "description")?,
+        })
+    }
+}
+
+impl JsonStructure for EnumValueType {
+    fn to_json_fields(&self) -> Map<String, Value> {
+        let mut object = Map::new();
+        object.insert("value".into(), Variant::Int64(self.value).to_json_value());
+        object.insert(
+            "displayName".into(),
+            localized_text_to_json(&self.display_name),
+        );
+        object.insert(
+            "description".into(),
+            localized_text_to_json(&self.description),
+        );
+        object
+    }
+
+    fn from_json_fields(object: &Map<String, Value>) -> Result<Self, StatusCode> {
+        Ok(EnumValueType {
+            value: large_integer(field(object, "value")?)?,
+            display_name: optional_localized_text(object, "displayName")?,
+            description: optional_localized_text(object, "description")?,
+        })
+    }
+}
+
+/// Returns the body of an extension object as an object of fields, if it is a structure with a
+/// JSON form and it decodes
+fn structure_to_json(v: &ExtensionObject) -> Option<Value> {
+    fn decode<T: JsonStructure>(v: &ExtensionObject) -> Option<Value> {
+        v.decode_inner::<T>(&DecodingOptions::default())
+            .ok()
+            .map(|v| Value::Object(v.to_json_fields()))
+    }
+    match v.object_id().ok()? {
+        ObjectId::Argument_Encoding_DefaultBinary => decode::<Argument>(v),
+        ObjectId::Range_Encoding_DefaultBinary => decode::<Range>(v),
+        ObjectId::EUInformation_Encoding_DefaultBinary => decode::<EUInformation>(v),
+        ObjectId::EnumValueType_Encoding_DefaultBinary => decode::<EnumValueType>(v),
+        _ => None,
+    }
+}
+
+/// Makes the extension object for a structure body that is an object of fields
+fn structure_from_json(
+    node_id: NodeId,
+    object: &Map<String, Value>,
+) -> Result<ExtensionObject, StatusCode> {
+    fn encode<T: JsonStructure>(
+        node_id: NodeId,
+        object: &Map<String, Value>,
+    ) -> Result<ExtensionObject, StatusCode> {
+        Ok(ExtensionObject::from_encodable(
+            node_id,
+            &T::from_json_fields(object)?,
+        ))
+    }
+    match node_id.as_object_id() {
+        Ok(ObjectId::Argument_Encoding_DefaultBinary) => encode::<Argument>(node_id, object),
+        Ok(ObjectId::Range_Encoding_DefaultBinary) => encode::<Range>(node_id, object),
+        Ok(ObjectId::EUInformation_Encoding_DefaultBinary) => {
+            encode::<EUInformation>(node_id, object)
+        }
+        Ok(ObjectId::EnumValueType_Encoding_DefaultBinary) => {
+            encode::<EnumValueType>(node_id, object)
+        }
+        _ => {
+            error!(
+                "JSON extension object body for type {} has no structure to decode into",
+                node_id
+            );
+            Err(StatusCode::BadDecodingError)
+        }
+    }
+}
+
+/// Nests the flat values of a multi dimensional array into JSON arrays, one level per dimension
+fn nest_array(values: Vec<Value>, dimensions: &[u32]) -> Value {
+    if dimensions.len() <= 1 {
+        Value::Array(values)
+    } else {
+        let chunk_size: usize = dimensions[1..].iter().map(|d| *d as usize).product();
+        if chunk_size == 0 {
+            return Value::Array(values);
+        }
+        let mut values = values.into_iter();
+        let nested = (0..dimensions[0])
+            .map(|_| {
+                let chunk: Vec<Value> = values.by_ref().take(chunk_size).collect();
+                nest_array(chunk, &dimensions[1..])
+            })
+            .collect();
+        Value::Array(nested)
+    }
+}
+
+/// Flattens nested JSON arrays into values, recording the length at each depth as a dimension.
+/// Arrays at the same depth must all be the same length.
+fn flatten_array(
+    v: &Value,
+    depth: usize,
+    dimensions: &mut Vec<u32>,
+    values: &mut Vec<Variant>,
+    value_type: VariantTypeId,
+) -> Result<(), StatusCode> {
+    match v {
+        Value::Array(elements) => {
+            if depth == dimensions.len() {
+                if values.is_empty() {
+                    dimensions.push(elements.len() as u32);
+                } else {
+                    error!("JSON array mixes nested arrays and values");
+                    return Err(StatusCode::BadTypeMismatch);
+                }
+            } else if dimensions[depth] != elements.len() as u32 {
+                error!("JSON array is ragged and cannot be a multi dimensional array");
+                return Err(StatusCode::BadTypeMismatch);
+            }
+            elements
+                .iter()
+                .try_for_each(|e| flatten_array(e, depth + 1, dimensions, values, value_type))
+        }
+        v => {
+            if depth != dimensions.len() {
+                error!("JSON array mixes nested arrays and values");
+                return Err(StatusCode::BadTypeMismatch);
+            }
+            values.push(Variant::from_json_value(v, value_type)?);
+            Ok(())
+        }
+    }
+}
+
+/// Infers the variant type of a JSON value when there is no better hint
+fn infer_type(v: &Value) -> VariantTypeId {
+    match v {
+        Value::Null => VariantTypeId::Empty,
+        Value::Bool(_) => VariantTypeId::Boolean,
+        Value::Number(n) => {
+            if n.is_i64() {
+                VariantTypeId::Int64
+            } else if n.is_u64() {
+                VariantTypeId::UInt64
+            } else {
+                VariantTypeId::Double
+            }
+        }
+        Value::String(_) => VariantTypeId::String,
+        Value::Array(elements) => elements
+            .iter()
+            .find(|e| !e.is_null())
+            .map(infer_type)
+            .unwrap_or(VariantTypeId::Empty),
+        Value::Object(_) => VariantTypeId::DataValue,
+    }
+}
+
+fn as_str(v: &Value) -> Result<&str, StatusCode> {
+    v.as_str().ok_or(StatusCode::BadTypeMismatch)
+}
+
+fn as_object(v: &Value) -> Result<&Map<String, Value>, StatusCode> {
+    v.as_object().ok_or(StatusCode::BadTypeMismatch)
+}
+
+/// Gets a field that a structure must have
+fn field<'a>(object: &'a Map<String, Value>, key: &str) -> Result<&'a Value, StatusCode> {
+    object.get(key).ok_or_else(|| {
+        error!("JSON structure is missing field {}", key);
+        StatusCode::BadDecodingError
+    })
+}
+
+fn parse<T: FromStr>(v: &Value) -> Result<T, StatusCode> {
+    as_str(v)?.parse().map_err(|_| StatusCode::BadTypeMismatch)
+}
+
+fn integer<T>(v: &Value) -> Result<T, StatusCode>
+where
+    T: TryFrom<i64> + TryFrom<u64>,
+{
+    if let Some(n) = v.as_i64() {
+        <T as TryFrom<i64>>::try_from(n).map_err(|_| StatusCode::BadOutOfRange)
+    } else if let Some(n) = v.as_u64() {
+        <T as TryFrom<u64>>::try_from(n).map_err(|_| StatusCode::BadOutOfRange)
+    } else {
+        Err(StatusCode::BadTypeMismatch)
+    }
+}
+
+fn double(v: &Value) -> Result<f64, StatusCode> {
+    v.as_f64().ok_or(StatusCode::BadTypeMismatch)
+}
+
+fn float(v: &Value) -> Result<f32, StatusCode> {
+    let v = double(v)?;
+    // JSON has no NaN or infinity so anything that becomes infinite was too large for a float
+    let f = v as f32;
+    if f.is_infinite() {
+        error!("JSON number {} is out of range for a float", v);
+        Err(StatusCode::BadDecodingError)
+    } else {
+        Ok(f)
+    }
+}
+
+/// Makes a status code from its number, which must be a known status with any bit flags
+fn status_code(v: &Value) -> Result<StatusCode, StatusCode> {
+    let bits: u32 = integer(v)?;
+    // The bit masks cover every bit, so the status part is checked against the known codes too
+    match StatusCode::from_u32(bits) {
+        Some(status_code) if status_code.status().name().parse::<StatusCode>().is_ok() => {
+            Ok(status_code)
+        }
+        _ => {
+            error!("JSON number {:#010x} is not a known status code", bits);
+            Err(StatusCode::BadDecodingError)
+        }
+    }
+}
+
+/// A 64-bit integer is normally a string holding the number but a plain number is accepted too
+fn large_integer<T>(v: &Value) -> Result<T, StatusCode>
+where
+    T: TryFrom<i64> + TryFrom<u64> + TryFrom<i128>,
+{
+    if let Some(s) = v.as_str() {
+        let n: i128 = s.parse().map_err(|_| StatusCode::BadTypeMismatch)?;
+        <T as TryFrom<i128>>::try_from(n).map_err(|_| StatusCode::BadOutOfRange)
+    } else {
+        integer(v)
+    }
+}
+
+fn optional_string(object: &Map<String, Value>, key: &str) -> Result<UAString, StatusCode> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(UAString::null()),
+        Some(v) => Ok(UAString::from(as_str(v)?)),
+    }
+}
+
+fn localized_text(v: &Value) -> Result<LocalizedText, StatusCode> {
+    match v {
+        // A plain string is text with no locale
+        Value::String(text) => Ok(LocalizedText::new("", text)),
+        v => {
+            let object = as_object(v)?;
+            Ok(LocalizedText {
+                locale: optional_string(object, "locale")?,
+                text: optional_string(object, "text")?,
+            })
+        }
+    }
+}
+
+fn optional_localized_text(
+    object: &Map<String, Value>,
+    key: &str,
+) -> Result<LocalizedText, StatusCode> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(LocalizedText::null()),
+        Some(v) => localized_text(v),
+    }
+}
+
+fn optional_timestamp(
+    object: &Map<String, Value>,
+    key: &str,
+) -> Result<Option<DateTime>, StatusCode> {
+    object.get(key).map(parse::<DateTime>).transpose()
+}
+
+fn scalar_from_json(v: &Value, type_hint: VariantTypeId) -> Result<Variant, StatusCode> {
+    let variant = match type_hint {
+        VariantTypeId::Empty => {
+            error!("JSON value {} cannot be converted to an empty variant", v);
+            return Err(StatusCode::BadTypeMismatch);
+        }
+        VariantTypeId::Boolean => Variant::Boolean(v.as_bool().ok_or(StatusCode::BadTypeMismatch)?),
+        VariantTypeId::SByte => Variant::SByte(integer(v)?),
+        VariantTypeId::Byte => Variant::Byte(integer(v)?),
+        VariantTypeId::Int16 => Variant::Int16(integer(v)?),
+        VariantTypeId::UInt16 => Variant::UInt16(integer(v)?),
+        VariantTypeId::Int32 => Variant::Int32(integer(v)?),
+        VariantTypeId::UInt32 => Variant::UInt32(integer(v)?),
+        VariantTypeId::Int64 => Variant::Int64(large_integer(v)?),
+        VariantTypeId::UInt64 => Variant::UInt64(large_integer(v)?),
+        VariantTypeId::Float => Variant::Float(float(v)?),
+        VariantTypeId::Double => Variant::Double(double(v)?),
+        VariantTypeId::String => Variant::String(UAString::from(as_str(v)?)),
+        VariantTypeId::XmlElement => Variant::XmlElement(UAString::from(as_str(v)?)),
+        VariantTypeId::DateTime => Variant::from(parse::<DateTime>(v)?),
+        VariantTypeId::Guid => Variant::from(parse::<Guid>(v)?),
+        VariantTypeId::StatusCode => Variant::StatusCode(status_code(v)?),
+        VariantTypeId::ByteString => {
+            // A null byte string is JSON null, so an empty string is an empty byte string
+            let v = as_str(v)?;
+            Variant::ByteString(if v.is_empty() {
+                ByteString::from(Vec::new())
+            } else {
+                ByteString::from_base64(v).map_err(|_| StatusCode::BadTypeMismatch)?
+            })
+        }
+        VariantTypeId::QualifiedName => {
+            let object = as_object(v)?;
+            let namespace_index = match object.get("namespaceIndex") {
+                Some(namespace_index) => integer(namespace_index)?,
+                None => 0,
+            };
+            Variant::from(QualifiedName {
+                namespace_index,
+                name: optional_string(object, "name")?,
+            })
+        }
+        VariantTypeId::LocalizedText => Variant::from(localized_text(v)?),
+        VariantTypeId::NodeId => Variant::from(parse::<NodeId>(v)?),
+        VariantTypeId::ExpandedNodeId => Variant::from(parse::<ExpandedNodeId>(v)?),
+        VariantTypeId::ExtensionObject => {
+            let object = as_object(v)?;
+            let node_id = object
+                .get("typeId")
+                .map(parse::<NodeId>)
+                .transpose()?
+                .unwrap_or_else(NodeId::null);
+            let extension_object = match object.get("body") {
+                Some(Value::Object(body)) => structure_from_json(node_id, body)?,
+                Some(body) => ExtensionObject {
+                    node_id,
+                    body: ExtensionObjectEncoding::ByteString(
+                        ByteString::from_base64(as_str(body)?)
+                            .map_err(|_| StatusCode::BadTypeMismatch)?,
+                    ),
+                },
+                None => ExtensionObject {
+                    node_id,
+                    body: match object.get("xml") {
+                        Some(xml) => {
+                            ExtensionObjectEncoding::XmlElement(UAString::from(as_str(xml)?))
+                        }
+                        None => ExtensionObjectEncoding::None,
+                    },
+                },
+            };
+            Variant::from(extension_object)
+        }
+        VariantTypeId::Variant => {
+            Variant::Variant(Box::new(Variant::from_json_value(v, infer_type(v))?))
+        }
+        VariantTypeId::DataValue => {
+            let object = as_object(v)?;
+            let value = object
+                .get("value")
+                .map(|value| Variant::from_json_value(value, infer_type(value)))
+                .transpose()?;
+            let status = object.get("statusCode").map(status_code).transpose()?;
+            Variant::from(DataValue {
+                value,
+                status,
+                source_timestamp: optional_timestamp(object, "sourceTimestamp")?,
+                source_picoseconds: None,
+                server_timestamp: optional_timestamp(object, "serverTimestamp")?,
+                server_picoseconds: None,
+            })
+        }
+        VariantTypeId::Diagnostic => Variant::Diagnostics(Box::new(
+            serde_json::from_value(v.clone()).map_err(|_| StatusCode::BadTypeMismatch)?,
+        )),
+        VariantTypeId::Array => return Variant::from_json_value(v, infer_type(v)),
+    };
+    Ok(variant)
+}
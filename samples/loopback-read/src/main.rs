@@ -0,0 +1,111 @@
+// OPCUA for Rust
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (C) 2017-2022 Adam Lock
+
+//! This sample runs a server and a client in the same process. The server is run on its own
+//! thread, the client connects to it, reads a variable and browses the objects folder through
+//! the server's loop, and then the server is told to abort.
+use std::{sync::Arc, thread};
+
+use opcua::client::prelude::*;
+use opcua::server::prelude::*;
+use opcua::sync::RwLock;
+
+const PORT: u16 = 4860;
+
+fn main() {
+    // This enables logging via env_logger & log crate macros. If you don't need logging or want
+    // to implement your own, omit this line.
+    opcua::console_logging::init();
+
+    // Create a server with a single endpoint that accepts anonymous connections without security
+    let server = ServerBuilder::new_anonymous("Loopback Read Server")
+        .application_uri("urn:LoopbackReadServer")
+        .product_uri("urn:LoopbackReadServer")
+        .create_sample_keypair(true)
+        .pki_dir("./pki-server")
+        .host_and_port("127.0.0.1", PORT)
+        .discovery_server_url(None)
+        .server()
+        .unwrap();
+
+    // Add a variable for the client to read
+    let v1_node = {
+        let address_space = server.address_space();
+        let mut address_space = address_space.write();
+        let ns = address_space
+            .register_namespace("urn:loopback-read")
+            .unwrap();
+        let v1_node = NodeId::new(ns, "v1");
+        let _ = address_space.add_variables(
+            vec![Variable::new(&v1_node, "v1", "v1", 42i32)],
+            &NodeId::objects_folder_id(),
+        );
+        v1_node
+    };
+
+    // Run the server on its own thread. It blocks until it is told to abort
+    let server = Arc::new(RwLock::new(server));
+    let server_thread = {
+        let server = server.clone();
+        thread::spawn(move || Server::run_server(server))
+    };
+
+    // The client retries the connection in case the server is not listening yet
+    let mut client = ClientBuilder::new()
+        .application_name("Loopback Read Client")
+        .application_uri("urn:LoopbackReadClient")
+        .product_uri("urn:LoopbackReadClient")
+        .create_sample_keypair(true)
+        .pki_dir("./pki-client")
+        .trust_server_certs(true)
+        .session_retry_limit(5)
+        .client()
+        .unwrap();
+    let url = format!("opc.tcp://127.0.0.1:{}/", PORT);
+    let session = client
+        .connect_to_endpoint(
+            (
+                url.as_ref(),
+                SecurityPolicy::None.to_str(),
+                MessageSecurityMode::None,
+                UserTokenPolicy::anonymous(),
+            ),
+            IdentityToken::Anonymous,
+        )
+        .unwrap();
+
+    {
+        let session = session.read();
+
+        // Read the variable
+        let results = session
+            .read(&[v1_node.into()], TimestampsToReturn::Neither, 0.0)
+            .unwrap();
+        println!("v1 = {:?}", results[0].value);
+
+        // Browse the objects folder
+        let results = session
+            .browse(&[BrowseDescription {
+                node_id: ObjectId::ObjectsFolder.into(),
+                browse_direction: BrowseDirection::Forward,
+                reference_type_id: ReferenceTypeId::Organizes.into(),
+                include_subtypes: true,
+                node_class_mask: 0,
+                result_mask: BrowseDescriptionResultMask::all().bits() as u32,
+            }])
+            .unwrap()
+            .unwrap();
+        if let Some(ref references) = results[0].references {
+            for r in references {
+                println!("Objects folder organizes {}", r.browse_name.name);
+            }
+        }
+
+        session.disconnect();
+    }
+
+    // Tell the server to abort and wait for it to finish
+    server.write().abort();
+    server_thread.join().unwrap();
+}
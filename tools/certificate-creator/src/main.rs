@@ -228,6 +228,7 @@ fn parse_x509_args() -> Result<(X509Data, bool, PathBuf, PathBuf, PathBuf), ()>
                 organizational_unit,
                 country,
                 state,
+                application_uri,
                 alt_host_names,
                 certificate_duration_days,
             },
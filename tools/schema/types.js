@@ -88,13 +88,8 @@ function convertFieldName(name) {
 }
 
 function errorResponseForEnum(name) {
-    switch (name) {
-        case "BrowseDirection":
-        case "TimestampsToReturn":
-            return "Ok(Self::Invalid)";
-        default:
-            return "Err(StatusCode::BadUnexpectedError)";
-    }
+    // Values outside of the enumeration are a malformed message
+    return "Err(StatusCode::BadDecodingError)";
 }
 exports.from_xml = (bsd_file, rs_module) => {
     // Parse the types file, do something upon callback
@@ -259,6 +254,33 @@ pub use self::impls::*;
 `
     });
 
+    contents += `
+use crate::types::{
+    encoding::{BinaryEncoder, DecodingOptions, EncodingResult},
+    extension_object::ExtensionObject,
+    node_ids::ObjectId,
+};
+
+/// Decodes the body of an extension object as the structure that its encoding id identifies and
+/// encodes it again. Returns \`None\` if the encoding id is not for one of the structured types.
+pub fn reencode_extension_object(extension_object: &ExtensionObject, decoding_options: &DecodingOptions) -> Option<EncodingResult<ExtensionObject>> {
+    fn reencode<T: BinaryEncoder<T>>(object_id: ObjectId, extension_object: &ExtensionObject, decoding_options: &DecodingOptions) -> EncodingResult<ExtensionObject> {
+        let inner = extension_object.decode_inner::<T>(decoding_options)?;
+        Ok(ExtensionObject::from_encodable(object_id, &inner))
+    }
+    let object_id = extension_object.object_id().ok()?;
+    let result = match object_id {
+`;
+    _.each(structured_types, structured_type => {
+        contents += `        ObjectId::${structured_type.name}_Encoding_DefaultBinary => reencode::<${structured_type.name}>(object_id, extension_object, decoding_options),
+`
+    });
+    contents += `        _ => return None,
+    };
+    Some(result)
+}
+`;
+
     util.write_to_file(file_path, contents);
 }
 